@@ -0,0 +1,352 @@
+//! `emuforge` : accès en ligne de commande aux fonctions du forge.
+//!
+//! Chaque commande écrit un unique objet JSON sur stdout (`"status": "ok"`,
+//! `"partial"` pour un lot incomplet, ou `"error"`) ; les logs de progression
+//! vont sur stderr.
+
+use clap::{Args, Parser, Subcommand};
+use emuforge_core::detection::{FileAnalyzer, Platform};
+use emuforge_core::downloader::{EmulatorDownloader, KNOWN_EMULATORS};
+use emuforge_core::forge::{collect_roms, forge_batch, forge_game, BatchOptions, ForgeRequest};
+use emuforge_core::plugin::manager::PluginManager;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+/// Échec générique (forge, téléchargement, E/S).
+const EXIT_FAILURE: i32 = 1;
+// 2 : erreur d'usage, renvoyée par clap
+/// Plugin, émulateur ou plateforme inconnus.
+const EXIT_NOT_FOUND: i32 = 3;
+/// Prérequis non satisfaits (`validate`).
+const EXIT_INVALID: i32 = 4;
+/// Lot terminé avec au moins un jeu en échec (`"status": "partial"`).
+const EXIT_PARTIAL: i32 = 5;
+
+#[derive(Parser)]
+#[command(name = "emuforge", version, about = "Forge standalone game executables")]
+struct Cli {
+    /// Pretty-print the JSON output
+    #[arg(long, global = true)]
+    pretty: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Forge a shortcut or a portable executable
+    Forge(ForgeArgs),
+    /// Forge every ROM of directories or lists of files
+    Batch(BatchArgs),
+    /// Identify the platform of a ROM
+    Detect {
+        rom: PathBuf,
+    },
+    /// Download an emulator
+    Download {
+        /// Emulator id (ppsspp, pcsx2, ...)
+        emulator: String,
+        /// Install directory (default: ~/.emuforge/emulators)
+        #[arg(long)]
+        dir: Option<PathBuf>,
+    },
+    /// Show what an emulator plugin needs (BIOS, firmware, keys)
+    Requirements {
+        plugin: String,
+    },
+    /// Check that an emulator plugin's requirements are met
+    Validate {
+        plugin: String,
+        /// BIOS, firmware or keys to validate
+        #[arg(long)]
+        source: Option<PathBuf>,
+    },
+}
+
+#[derive(Args)]
+struct ForgeArgs {
+    /// Game name, used for the output file name
+    #[arg(long)]
+    name: String,
+    /// Emulator binary
+    #[arg(long)]
+    emulator: PathBuf,
+    #[arg(long)]
+    rom: PathBuf,
+    #[arg(long)]
+    bios: Option<PathBuf>,
+    /// Output directory
+    #[arg(long, short, default_value = ".")]
+    output: PathBuf,
+    /// Embed emulator, ROM and configs in the executable
+    #[arg(long)]
+    portable: bool,
+    #[arg(long)]
+    fullscreen: bool,
+    /// Extra emulator argument, repeatable (emulators without a plugin only)
+    #[arg(long = "arg", allow_hyphen_values = true)]
+    args: Vec<String>,
+    #[arg(long)]
+    width: Option<u32>,
+    #[arg(long)]
+    height: Option<u32>,
+    /// Name of the signing key (default: EMUFORGE_SIGNING_KEY)
+    #[arg(long)]
+    sign: Option<String>,
+    /// With --portable: write the emulator to a shared <id>.emupack next to the executable
+    #[arg(long, requires = "portable")]
+    thin: bool,
+    /// With --portable: write the game data to <name>.emudata next to a small executable
+    #[arg(long, requires = "portable")]
+    sidecar: bool,
+    /// With --sidecar: split the data file into parts of this size (e.g. 4000M, 2G)
+    #[arg(long, requires = "sidecar", value_parser = parse_size)]
+    split_size: Option<u64>,
+    /// With --portable: build <name>.AppImage instead of a self-extracting executable
+    #[arg(long, requires = "portable", conflicts_with_all = ["thin", "sidecar"])]
+    appimage: bool,
+}
+
+#[derive(Args)]
+struct BatchArgs {
+    /// ROM files or directories
+    #[arg(required = true)]
+    inputs: Vec<PathBuf>,
+    /// Scan directories recursively
+    #[arg(long, short)]
+    recursive: bool,
+    /// Output directory
+    #[arg(long, short, default_value = ".")]
+    output: PathBuf,
+    #[arg(long)]
+    portable: bool,
+    #[arg(long)]
+    fullscreen: bool,
+    /// Simultaneous forges (default: number of CPUs)
+    #[arg(long, short)]
+    jobs: Option<usize>,
+    /// Emulator binary for a plugin, as plugin=path (repeatable)
+    #[arg(long = "emulator", value_parser = parse_plugin_path)]
+    emulators: Vec<(String, PathBuf)>,
+    /// BIOS or firmware for a plugin, as plugin=path (repeatable)
+    #[arg(long = "bios", value_parser = parse_plugin_path)]
+    bios: Vec<(String, PathBuf)>,
+    /// Name of the signing key (default: EMUFORGE_SIGNING_KEY)
+    #[arg(long)]
+    sign: Option<String>,
+    /// Also write the JSON report to this file
+    #[arg(long)]
+    report: Option<PathBuf>,
+    /// With --portable: one shared <id>.emupack per emulator instead of a copy in every executable
+    #[arg(long, requires = "portable")]
+    thin: bool,
+    /// With --portable: write each game's data to <name>.emudata next to a small executable
+    #[arg(long, requires = "portable")]
+    sidecar: bool,
+    /// With --sidecar: split data files into parts of this size (e.g. 4000M, 2G)
+    #[arg(long, requires = "sidecar", value_parser = parse_size)]
+    split_size: Option<u64>,
+    /// With --portable: one <name>.AppImage per game
+    #[arg(long, requires = "portable", conflicts_with_all = ["thin", "sidecar"])]
+    appimage: bool,
+}
+
+fn parse_plugin_path(value: &str) -> Result<(String, PathBuf), String> {
+    match value.split_once('=') {
+        Some((plugin, path)) if !plugin.is_empty() && !path.is_empty() => Ok((plugin.to_string(), PathBuf::from(path))),
+        _ => Err(format!("expected plugin=path, got '{}'", value)),
+    }
+}
+
+/// Size in bytes, with an optional K, M, G or T suffix (powers of 1024).
+fn parse_size(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let (digits, shift) = match value.char_indices().last() {
+        Some((i, c)) if c.is_ascii_alphabetic() => {
+            let shift = match c.to_ascii_uppercase() {
+                'K' => 10,
+                'M' => 20,
+                'G' => 30,
+                'T' => 40,
+                _ => return Err(format!("unknown size suffix in '{}' (K, M, G or T)", value)),
+            };
+            (&value[..i], shift)
+        }
+        _ => (value, 0),
+    };
+    digits.parse::<u64>().ok()
+        .filter(|&n| n > 0)
+        .and_then(|n| n.checked_mul(1 << shift))
+        .ok_or_else(|| format!("expected a positive size like 4000M or 2G, got '{}'", value))
+}
+
+struct CliError {
+    code: i32,
+    message: String,
+}
+
+impl CliError {
+    fn new(code: i32, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+}
+
+impl From<anyhow::Error> for CliError {
+    fn from(e: anyhow::Error) -> Self {
+        Self::new(EXIT_FAILURE, format!("{:#}", e))
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    let result = match cli.command {
+        Command::Forge(args) => forge(args),
+        Command::Batch(args) => batch(args),
+        Command::Detect { rom } => detect(rom),
+        Command::Download { emulator, dir } => download(emulator, dir),
+        Command::Requirements { plugin } => requirements(&plugin),
+        Command::Validate { plugin, source } => validate(&plugin, source),
+    };
+
+    let (output, code) = match result {
+        Ok(mut value) => {
+            let code = if value["status"] == "partial" { EXIT_PARTIAL } else { 0 };
+            if value["status"].is_null() {
+                value["status"] = json!("ok");
+            }
+            (value, code)
+        }
+        Err(e) => (json!({ "status": "error", "error": e.message }), e.code),
+    };
+    let text = if cli.pretty {
+        serde_json::to_string_pretty(&output)
+    } else {
+        serde_json::to_string(&output)
+    };
+    println!("{}", text.expect("JSON values always serialize"));
+    std::process::exit(code);
+}
+
+fn forge(args: ForgeArgs) -> Result<Value, CliError> {
+    let request = ForgeRequest {
+        game_name: args.name,
+        emulator_path: args.emulator,
+        rom_path: args.rom,
+        bios_path: args.bios,
+        output_dir: args.output,
+        fullscreen: args.fullscreen,
+        args: args.args,
+        portable: args.portable,
+        screen_width: args.width,
+        screen_height: args.height,
+        signing_key: args.sign,
+        thin: args.thin,
+        sidecar: args.sidecar,
+        part_size: args.split_size,
+        appimage: args.appimage,
+    };
+    for (label, path) in [("Emulator", &request.emulator_path), ("ROM", &request.rom_path)] {
+        if !path.exists() {
+            return Err(CliError::new(EXIT_NOT_FOUND, format!("{} not found: {}", label, path.display())));
+        }
+    }
+
+    let progress = |percentage: u64, message: &str| eprintln!("[{:>3}%] {}", percentage, message);
+    let output = forge_game(&request, &[], &progress)?;
+    Ok(json!({ "output": output, "portable": request.portable }))
+}
+
+fn batch(args: BatchArgs) -> Result<Value, CliError> {
+    let mut roms = Vec::new();
+    for input in &args.inputs {
+        if input.is_dir() {
+            roms.extend(collect_roms(input, args.recursive)?);
+        } else if input.exists() {
+            roms.push(input.clone());
+        } else {
+            return Err(CliError::new(EXIT_NOT_FOUND, format!("Not found: {}", input.display())));
+        }
+    }
+
+    let jobs = args.jobs.unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()));
+    let options = BatchOptions {
+        output_dir: args.output,
+        portable: args.portable,
+        fullscreen: args.fullscreen,
+        jobs,
+        emulators: args.emulators.into_iter().collect(),
+        bios: args.bios.into_iter().collect(),
+        signing_key: args.sign,
+        thin: args.thin,
+        sidecar: args.sidecar,
+        part_size: args.split_size,
+        appimage: args.appimage,
+    };
+
+    let progress = |percentage: u64, message: &str| eprintln!("[{:>3}%] {}", percentage, message);
+    let report = forge_batch(&roms, &options, &[], &progress);
+    if let Some(path) = &args.report {
+        report.write(path)?;
+    }
+
+    let mut value = serde_json::to_value(&report).map_err(|e| CliError::new(EXIT_FAILURE, e.to_string()))?;
+    if report.failed > 0 {
+        value["status"] = json!("partial");
+    }
+    Ok(value)
+}
+
+fn detect(rom: PathBuf) -> Result<Value, CliError> {
+    if !rom.exists() {
+        return Err(CliError::new(EXIT_NOT_FOUND, format!("File not found: {}", rom.display())));
+    }
+    let platform = FileAnalyzer::identify_platform(&rom);
+    if platform == Platform::Unknown {
+        return Err(CliError::new(EXIT_NOT_FOUND, format!("Unrecognized platform: {}", rom.display())));
+    }
+    let file_type = FileAnalyzer::detect_type(&rom).map(|t| format!("{:?}", t));
+    Ok(json!({ "path": rom, "platform": platform.as_str(), "file_type": file_type }))
+}
+
+fn download(emulator: String, dir: Option<PathBuf>) -> Result<Value, CliError> {
+    if !KNOWN_EMULATORS.contains(&emulator.as_str()) {
+        return Err(CliError::new(
+            EXIT_NOT_FOUND,
+            format!("Unknown emulator '{}' (known: {})", emulator, KNOWN_EMULATORS.join(", ")),
+        ));
+    }
+    let downloader = EmulatorDownloader::new(dir.unwrap_or_else(EmulatorDownloader::default_dir));
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| CliError::new(EXIT_FAILURE, format!("Failed to start runtime: {}", e)))?;
+    let path = runtime.block_on(downloader.download(&emulator))?;
+    Ok(json!({ "emulator": emulator, "path": path }))
+}
+
+fn requirements(plugin_id: &str) -> Result<Value, CliError> {
+    let manager = PluginManager::new();
+    let plugin = find_plugin(&manager, plugin_id)?;
+    Ok(json!({ "plugin": plugin_id, "requirements": plugin.get_requirements() }))
+}
+
+fn validate(plugin_id: &str, source: Option<PathBuf>) -> Result<Value, CliError> {
+    let manager = PluginManager::new();
+    let plugin = find_plugin(&manager, plugin_id)?;
+    let result = plugin.validate_requirements(source.as_deref())?;
+    if !result.valid {
+        return Err(CliError::new(EXIT_INVALID, result.message));
+    }
+    Ok(json!({ "plugin": plugin_id, "validation": result }))
+}
+
+fn find_plugin<'a>(
+    manager: &'a PluginManager,
+    plugin_id: &str,
+) -> Result<&'a dyn emuforge_core::plugin::EmulatorPlugin, CliError> {
+    manager
+        .get_plugin_by_id(plugin_id)
+        .ok_or_else(|| CliError::new(EXIT_NOT_FOUND, format!("Unknown plugin '{}'", plugin_id)))
+}
@@ -1 +1,6 @@
 pub mod patcher;
+pub mod runtime;
+pub mod squashfs;
+pub mod writer;
+
+pub use writer::AppImageWriter;
@@ -3,14 +3,16 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::fs;
 
+use super::runtime;
+use super::writer::AppImageWriter;
+
 /// Patcher pour AppImages (modification et repackaging)
-pub struct AppImagePatcher {
-    appimagetool_path: PathBuf,
-}
+#[derive(Default)]
+pub struct AppImagePatcher;
 
 impl AppImagePatcher {
-    pub fn new(appimagetool_path: PathBuf) -> Self {
-        Self { appimagetool_path }
+    pub fn new() -> Self {
+        Self
     }
     
     /// Patch une AppImage RPCS3 avec firmware et configurations
@@ -20,7 +22,10 @@ impl AppImagePatcher {
         dev_flash_path: &Path,
         output_dir: &Path,
     ) -> Result<PathBuf> {
-        println!("🔧 Patching RPCS3 AppImage...");
+        eprintln!("🔧 Patching RPCS3 AppImage...");
+        
+        // Le runtime d'origine est réutilisé pour le re-packaging
+        let runtime = runtime::from_appimage(original_appimage)?;
         
         // 1. Extraire AppImage
         let squashfs = self.extract_appimage(original_appimage, output_dir)?;
@@ -32,17 +37,17 @@ impl AppImagePatcher {
         self.inject_wrapper(&squashfs)?;
         
         // 4. Re-packager
-        let patched = self.repackage_appimage(&squashfs, output_dir)?;
+        let patched = self.repackage_appimage(&squashfs, output_dir, &runtime)?;
         
         // 5. Cleanup squashfs temp
         let _ = fs::remove_dir_all(&squashfs);
         
-        println!("✅ AppImage patched successfully");
+        eprintln!("✅ AppImage patched successfully");
         Ok(patched)
     }
     
     fn extract_appimage(&self, appimage: &Path, work_dir: &Path) -> Result<PathBuf> {
-        println!("  📂 Extracting AppImage...");
+        eprintln!("  📂 Extracting AppImage...");
         
         // S'assurer que l'AppImage est exécutable
         #[cfg(unix)]
@@ -76,14 +81,14 @@ impl AppImagePatcher {
     }
     
     fn inject_firmware(&self, squashfs: &Path, dev_flash: &Path) -> Result<()> {
-        println!("  💾 Injecting firmware...");
+        eprintln!("  💾 Injecting firmware...");
         
         let target = squashfs.join("usr/bin/dev_flash");
         
         // Vérifier si dev_flash contient un sous-dossier dev_flash (structure imbriquée)
         let inner_dev_flash = dev_flash.join("dev_flash");
         let source = if inner_dev_flash.exists() && inner_dev_flash.is_dir() {
-            println!("     (using inner dev_flash structure)");
+            eprintln!("     (using inner dev_flash structure)");
             inner_dev_flash
         } else {
             dev_flash.to_path_buf()
@@ -97,7 +102,7 @@ impl AppImagePatcher {
     }
     
     fn inject_wrapper(&self, squashfs: &Path) -> Result<()> {
-        println!("  ⚙️  Injecting wrapper script...");
+        eprintln!("  ⚙️  Injecting wrapper script...");
         
         // Créer le wrapper script qui gère les deux modes
         let wrapper_script = r#"#!/bin/bash
@@ -436,34 +441,13 @@ exec "$REAL_EMULATOR" "$@"
         Ok(())
     }
     
-    fn repackage_appimage(&self, squashfs: &Path, output_dir: &Path) -> Result<PathBuf> {
-        println!("  📦 Repackaging AppImage...");
+    fn repackage_appimage(&self, squashfs: &Path, output_dir: &Path, runtime: &[u8]) -> Result<PathBuf> {
+        eprintln!("  📦 Repackaging AppImage...");
         
         let output = output_dir.join("RPCS3-Patched.AppImage");
-        
-        let cmd_output = Command::new(&self.appimagetool_path)
-            .arg(squashfs)
-            .arg(&output)
-            .output()
-            .context("Failed to run appimagetool")?;
-        
-        if !cmd_output.status.success() {
-            return Err(anyhow::anyhow!(
-                "appimagetool failed: {}",
-                String::from_utf8_lossy(&cmd_output.stderr)
-            ));
-        }
-        
-        // Rendre exécutable
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let mut perms = fs::metadata(&output)?.permissions();
-            perms.set_mode(0o755);
-            fs::set_permissions(&output, perms)?;
-        }
-        
-        Ok(output)
+        let mut image = AppImageWriter::create(&output, runtime)?;
+        image.add_tree(squashfs, "")?;
+        image.finish()
     }
 }
 
@@ -0,0 +1,131 @@
+//! Runtime AppImage (type 2) : l'ELF placé devant l'image squashfs, qui la
+//! monte puis lance `AppRun`.
+
+use anyhow::{Context, Result};
+use emuforge_format::manifest::hash_reader;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Runtime à utiliser à la place de celui téléchargé.
+pub const RUNTIME_ENV: &str = "EMUFORGE_APPIMAGE_RUNTIME";
+
+/// Runtime téléchargeable pour une architecture : release figée (jamais le
+/// tag `continuous`, qui bouge) et empreinte SHA-256 attendue.
+struct PinnedRuntime {
+    arch: &'static str,
+    url: &'static str,
+    sha256: &'static str,
+}
+
+/// Runtimes épinglés. URL et empreinte se mettent à jour ensemble, d'après
+/// le `runtime-<arch>` d'une release de AppImage/type2-runtime. Sans entrée
+/// pour l'architecture, le runtime doit être livré avec le stub.
+const PINNED: &[PinnedRuntime] = &[];
+
+/// Nom du runtime sur la plateforme courante, livré à côté du stub.
+pub fn runtime_file_name() -> String {
+    format!("runtime-{}", std::env::consts::ARCH)
+}
+
+/// Cherche le runtime, comme `stub::locate_stub_template` le stub :
+/// `EMUFORGE_APPIMAGE_RUNTIME`, les dossiers `hints` (ex: celui du stub),
+/// le dossier de l'exécutable courant, puis `~/.cache/emuforge/tools` où il
+/// est téléchargé une fois depuis une release épinglée.
+pub fn locate(hints: &[PathBuf]) -> Result<Vec<u8>> {
+    if let Some(path) = std::env::var_os(RUNTIME_ENV) {
+        return read_runtime(Path::new(&path));
+    }
+
+    let name = runtime_file_name();
+    let mut candidates: Vec<PathBuf> = hints.iter().map(|dir| dir.join(&name)).collect();
+    if let Some(exe_dir) = std::env::current_exe().ok().and_then(|p| p.parent().map(Path::to_path_buf)) {
+        candidates.push(exe_dir.join(&name));
+        candidates.push(exe_dir.join("../lib/emuforge").join(&name));
+    }
+    if let Some(path) = candidates.into_iter().find(|p| p.is_file()) {
+        return read_runtime(&path);
+    }
+
+    let tools_dir = dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join("emuforge/tools");
+    let path = tools_dir.join(&name);
+    if !path.is_file() {
+        fs::create_dir_all(&tools_dir)?;
+        download(&path)?;
+    }
+    read_runtime(&path)
+}
+
+/// Runtime d'une AppImage existante : l'ELF qui précède son image.
+pub fn from_appimage(appimage: &Path) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    File::open(appimage)
+        .with_context(|| format!("Failed to open {}", appimage.display()))?
+        .take(64)
+        .read_to_end(&mut data)?;
+    let size = elf_size(&data).with_context(|| format!("{} is not an ELF AppImage", appimage.display()))?;
+
+    let mut runtime = Vec::new();
+    File::open(appimage)?.take(size).read_to_end(&mut runtime)?;
+    anyhow::ensure!(runtime.len() as u64 == size, "Truncated AppImage runtime in {}", appimage.display());
+    Ok(runtime)
+}
+
+/// Taille d'un ELF d'après son en-tête (fin de la table des sections) :
+/// c'est là que commence l'image squashfs d'une AppImage.
+pub fn elf_size(header: &[u8]) -> Option<u64> {
+    if header.get(..4)? != b"\x7fELF" || *header.get(5)? != 1 {
+        return None;
+    }
+    let read = |offset: usize, len: usize| -> Option<u64> {
+        let bytes = header.get(offset..offset + len)?;
+        Some(bytes.iter().rev().fold(0, |value, &b| (value << 8) | u64::from(b)))
+    };
+    let (section_offset, entry_size, entries) = match header.get(4)? {
+        1 => (read(0x20, 4)?, read(0x2E, 2)?, read(0x30, 2)?),
+        2 => (read(0x28, 8)?, read(0x3A, 2)?, read(0x3C, 2)?),
+        _ => return None,
+    };
+    section_offset.checked_add(entry_size * entries)
+}
+
+fn read_runtime(path: &Path) -> Result<Vec<u8>> {
+    let runtime = fs::read(path).with_context(|| format!("Failed to read AppImage runtime {}", path.display()))?;
+    anyhow::ensure!(elf_size(&runtime).is_some(), "{} is not an ELF runtime", path.display());
+    Ok(runtime)
+}
+
+/// Téléchargement synchrone via curl (évite les conflits de runtime tokio),
+/// vérifié contre l'empreinte épinglée avant d'être mis en place.
+fn download(path: &Path) -> Result<()> {
+    let arch = std::env::consts::ARCH;
+    let Some(pinned) = PINNED.iter().find(|p| p.arch == arch) else {
+        anyhow::bail!(
+            "No AppImage runtime for {}: place a type 2 runtime named {} next to emuforge-stub, or set {}",
+            arch, runtime_file_name(), RUNTIME_ENV
+        );
+    };
+    eprintln!("⬇️ Téléchargement du runtime AppImage: {}", pinned.url);
+
+    let partial = path.with_extension("partial");
+    let output = std::process::Command::new("curl")
+        .args(["-fL", "-o"])
+        .arg(&partial)
+        .arg(pinned.url)
+        .output()
+        .context("Failed to run curl for the AppImage runtime")?;
+    if !output.status.success() {
+        let _ = fs::remove_file(&partial);
+        anyhow::bail!("curl failed: {} (or set {})", String::from_utf8_lossy(&output.stderr).trim(), RUNTIME_ENV);
+    }
+
+    let (sha256, _) = hash_reader(&mut File::open(&partial)?)?;
+    if !sha256.eq_ignore_ascii_case(pinned.sha256) {
+        let _ = fs::remove_file(&partial);
+        anyhow::bail!("AppImage runtime checksum mismatch for {}: expected {}, got {}", pinned.url, pinned.sha256, sha256);
+    }
+    fs::rename(&partial, path).context("Failed to save the AppImage runtime")?;
+    Ok(())
+}
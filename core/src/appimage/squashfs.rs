@@ -0,0 +1,455 @@
+//! Écriture native d'images squashfs 4.0 (compression gzip), le système de
+//! fichiers monté par le runtime des AppImages.
+//!
+//! Les données des fichiers sont écrites au fil de l'eau (un ISO de plusieurs
+//! Go n'est jamais chargé en mémoire) ; seules les tables d'inodes et de
+//! dossiers sont construites en mémoire, puis écrites par `finish`. Ni
+//! fragments ni attributs étendus : la fin d'un fichier est un bloc court.
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use std::collections::BTreeMap;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const MAGIC: u32 = 0x7371_7368;
+pub const BLOCK_SIZE: usize = 128 * 1024;
+const BLOCK_LOG: u16 = 17;
+const METADATA_SIZE: usize = 8192;
+const SUPERBLOCK_SIZE: usize = 96;
+/// Les images sont complétées à un multiple de 4 Kio (périphériques loop).
+const PADDING: u64 = 4096;
+
+const COMPRESSION_GZIP: u16 = 1;
+const FLAG_NO_FRAGMENTS: u16 = 0x0010;
+const FLAG_NO_XATTRS: u16 = 0x0200;
+const INVALID_TABLE: u64 = u64::MAX;
+const NO_FRAGMENT: u32 = u32::MAX;
+const NO_XATTR: u32 = u32::MAX;
+const UNCOMPRESSED_BLOCK: u32 = 1 << 24;
+const UNCOMPRESSED_METADATA: u16 = 1 << 15;
+/// Entrées au plus par en-tête de liste de dossier.
+const DIR_RUN_MAX: usize = 256;
+const NAME_MAX: usize = 256;
+
+const BASIC_DIR: u16 = 1;
+const BASIC_FILE: u16 = 2;
+const BASIC_SYMLINK: u16 = 3;
+const EXTENDED_DIR: u16 = 8;
+const EXTENDED_FILE: u16 = 9;
+
+enum Node {
+    Dir(BTreeMap<String, Entry>),
+    File { start: u64, size: u64, blocks: Vec<u32> },
+    Symlink(String),
+}
+
+struct Entry {
+    mode: u16,
+    inode: u32,
+    node: Node,
+}
+
+impl Entry {
+    fn new(mode: u32, node: Node) -> Self {
+        Entry { mode: (mode & 0o7777) as u16, inode: 0, node }
+    }
+
+    /// Type de l'entrée dans une liste de dossier (toujours le type de base).
+    fn basic_type(&self) -> u16 {
+        match self.node {
+            Node::Dir(_) => BASIC_DIR,
+            Node::File { .. } => BASIC_FILE,
+            Node::Symlink(_) => BASIC_SYMLINK,
+        }
+    }
+}
+
+/// Inode écrit : sa référence (bloc, position) et ce qu'en retient le dossier parent.
+struct Written {
+    block: u32,
+    offset: u16,
+    inode: u32,
+    kind: u16,
+}
+
+/// Image squashfs écrite à partir de la position courante de `W` (à la
+/// suite du runtime pour une AppImage). Les chemins sont relatifs à la
+/// racine, séparés par `/`.
+pub struct SquashfsWriter<W: Write + Seek> {
+    inner: W,
+    start: u64,
+    position: u64,
+    mtime: u32,
+    root: Entry,
+}
+
+impl<W: Write + Seek> SquashfsWriter<W> {
+    pub fn new(mut inner: W) -> io::Result<Self> {
+        let start = inner.stream_position()?;
+        // Superbloc réécrit par `finish`
+        inner.write_all(&[0; SUPERBLOCK_SIZE])?;
+        let mtime = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() as u32);
+        Ok(Self {
+            inner,
+            start,
+            position: SUPERBLOCK_SIZE as u64,
+            mtime,
+            root: Entry::new(0o755, Node::Dir(BTreeMap::new())),
+        })
+    }
+
+    /// Crée un dossier (et ses parents) ; un dossier existant prend `mode`.
+    pub fn add_dir(&mut self, path: &str, mode: u32) -> io::Result<()> {
+        let (parent, name) = match split_parent(path)? {
+            Some(split) => split,
+            None => {
+                self.root.mode = (mode & 0o7777) as u16;
+                return Ok(());
+            }
+        };
+        let children = self.dir_mut(&parent)?;
+        match children.get_mut(name) {
+            Some(Entry { node: Node::Dir(_), mode: existing, .. }) => *existing = (mode & 0o7777) as u16,
+            Some(_) => return Err(already_exists(path)),
+            None => {
+                children.insert(name.to_string(), Entry::new(mode, Node::Dir(BTreeMap::new())));
+            }
+        }
+        Ok(())
+    }
+
+    /// Écrit le contenu de `reader` par blocs ; `compress: false` pour les
+    /// données déjà compressées (ROM), stockées telles quelles.
+    pub fn add_file(&mut self, path: &str, reader: &mut impl Read, mode: u32, compress: bool) -> io::Result<u64> {
+        let (parent, name) = split_parent(path)?.ok_or_else(|| already_exists(path))?;
+        if self.dir_mut(&parent)?.contains_key(name) {
+            return Err(already_exists(path));
+        }
+
+        let start = self.position;
+        let mut blocks = Vec::new();
+        let mut size = 0;
+        let mut buffer = vec![0; BLOCK_SIZE];
+        loop {
+            let n = read_block(reader, &mut buffer)?;
+            if n == 0 {
+                break;
+            }
+            size += n as u64;
+            let block = &buffer[..n];
+            let compressed = if compress { Some(zlib(block)?).filter(|c| c.len() < n) } else { None };
+            match compressed {
+                Some(data) => {
+                    self.write(&data)?;
+                    blocks.push(data.len() as u32);
+                }
+                None => {
+                    self.write(block)?;
+                    blocks.push(n as u32 | UNCOMPRESSED_BLOCK);
+                }
+            }
+            if n < BLOCK_SIZE {
+                break;
+            }
+        }
+
+        self.dir_mut(&parent)?.insert(name.to_string(), Entry::new(mode, Node::File { start, size, blocks }));
+        Ok(size)
+    }
+
+    pub fn add_symlink(&mut self, path: &str, target: &str) -> io::Result<()> {
+        let (parent, name) = split_parent(path)?.ok_or_else(|| already_exists(path))?;
+        let children = self.dir_mut(&parent)?;
+        if children.contains_key(name) {
+            return Err(already_exists(path));
+        }
+        children.insert(name.to_string(), Entry::new(0o777, Node::Symlink(target.to_string())));
+        Ok(())
+    }
+
+    /// Écrit les tables et le superbloc, puis retourne le flux (placé après l'image).
+    pub fn finish(mut self) -> io::Result<W> {
+        // Numéros d'inodes : la racine vaut 1, les frères se suivent
+        self.root.inode = 1;
+        let mut next = 2;
+        if let Node::Dir(children) = &mut self.root.node {
+            number(children, &mut next);
+        }
+        let inode_count = next - 1;
+
+        let mut inodes = MetadataWriter::default();
+        let mut dirs = MetadataWriter::default();
+        let root = write_inode(&self.root, inode_count + 1, self.mtime, &mut inodes, &mut dirs)?;
+        let root_ref = (u64::from(root.block) << 16) | u64::from(root.offset);
+
+        let inode_table_start = self.position;
+        self.write(&inodes.finish()?)?;
+        let directory_table_start = self.position;
+        self.write(&dirs.finish()?)?;
+        let fragment_table_start = self.position;
+
+        // Table des uid/gid : un seul identifiant, root
+        let id_block = self.position;
+        self.write(&(4 | UNCOMPRESSED_METADATA).to_le_bytes())?;
+        self.write(&0u32.to_le_bytes())?;
+        let id_table_start = self.position;
+        self.write(&id_block.to_le_bytes())?;
+
+        let bytes_used = self.position;
+        let padding = (PADDING - bytes_used % PADDING) % PADDING;
+        self.write(&vec![0; padding as usize])?;
+
+        let mut superblock = Vec::with_capacity(SUPERBLOCK_SIZE);
+        superblock.extend(MAGIC.to_le_bytes());
+        superblock.extend(inode_count.to_le_bytes());
+        superblock.extend(self.mtime.to_le_bytes());
+        superblock.extend((BLOCK_SIZE as u32).to_le_bytes());
+        superblock.extend(0u32.to_le_bytes()); // fragments
+        superblock.extend(COMPRESSION_GZIP.to_le_bytes());
+        superblock.extend(BLOCK_LOG.to_le_bytes());
+        superblock.extend((FLAG_NO_FRAGMENTS | FLAG_NO_XATTRS).to_le_bytes());
+        superblock.extend(1u16.to_le_bytes()); // identifiants
+        superblock.extend(4u16.to_le_bytes());
+        superblock.extend(0u16.to_le_bytes());
+        for value in [
+            root_ref,
+            bytes_used,
+            id_table_start,
+            INVALID_TABLE, // xattr
+            inode_table_start,
+            directory_table_start,
+            fragment_table_start,
+            INVALID_TABLE, // export
+        ] {
+            superblock.extend(value.to_le_bytes());
+        }
+
+        let end = self.inner.stream_position()?;
+        self.inner.seek(SeekFrom::Start(self.start))?;
+        self.inner.write_all(&superblock)?;
+        self.inner.seek(SeekFrom::Start(end))?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+
+    fn write(&mut self, data: &[u8]) -> io::Result<()> {
+        self.inner.write_all(data)?;
+        self.position += data.len() as u64;
+        Ok(())
+    }
+
+    /// Contenu du dossier `components`, créé (mode 755) s'il manque.
+    fn dir_mut(&mut self, components: &[&str]) -> io::Result<&mut BTreeMap<String, Entry>> {
+        let mut entry = &mut self.root;
+        for name in components {
+            let Node::Dir(children) = &mut entry.node else {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("{} n'est pas un dossier", name)));
+            };
+            entry = children.entry(name.to_string()).or_insert_with(|| Entry::new(0o755, Node::Dir(BTreeMap::new())));
+        }
+        match &mut entry.node {
+            Node::Dir(children) => Ok(children),
+            _ => Err(io::Error::new(io::ErrorKind::InvalidInput, format!("{} n'est pas un dossier", components.join("/")))),
+        }
+    }
+}
+
+/// Dossier parent et nom ; `None` pour la racine.
+fn split_parent(path: &str) -> io::Result<Option<(Vec<&str>, &str)>> {
+    let mut components: Vec<&str> = path.split('/').filter(|c| !c.is_empty()).collect();
+    if let Some(bad) = components.iter().find(|c| **c == "." || **c == ".." || c.len() > NAME_MAX) {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("Nom invalide dans l'image: {}", bad)));
+    }
+    Ok(components.pop().map(|name| (components, name)))
+}
+
+fn already_exists(path: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::AlreadyExists, format!("Déjà présent dans l'image: {}", path))
+}
+
+fn number(children: &mut BTreeMap<String, Entry>, next: &mut u32) {
+    for entry in children.values_mut() {
+        entry.inode = *next;
+        *next += 1;
+    }
+    for entry in children.values_mut() {
+        if let Node::Dir(grandchildren) = &mut entry.node {
+            number(grandchildren, next);
+        }
+    }
+}
+
+/// Écrit les inodes du sous-arbre (enfants d'abord : le dossier référence
+/// leurs inodes dans sa liste) puis celui de `entry`.
+fn write_inode(entry: &Entry, parent: u32, mtime: u32, inodes: &mut MetadataWriter, dirs: &mut MetadataWriter) -> io::Result<Written> {
+    let mut children_written = Vec::new();
+    if let Node::Dir(children) = &entry.node {
+        for (name, child) in children {
+            children_written.push((name.as_str(), write_inode(child, entry.inode, mtime, inodes, dirs)?));
+        }
+    }
+
+    let (block, offset) = inodes.reference();
+    let mut data = Vec::new();
+    let header = |data: &mut Vec<u8>, kind: u16| {
+        data.extend(kind.to_le_bytes());
+        data.extend(entry.mode.to_le_bytes());
+        data.extend(0u16.to_le_bytes()); // uid
+        data.extend(0u16.to_le_bytes()); // gid
+        data.extend(mtime.to_le_bytes());
+        data.extend(entry.inode.to_le_bytes());
+    };
+
+    match &entry.node {
+        Node::Dir(children) => {
+            let links = 2 + children.values().filter(|c| matches!(c.node, Node::Dir(_))).count() as u32;
+            let (dir_block, dir_offset) = dirs.reference();
+            // Taille de la liste + 3 (entrées virtuelles . et ..)
+            let size = write_listing(dirs, &children_written)? + 3;
+            if size <= usize::from(u16::MAX) {
+                header(&mut data, BASIC_DIR);
+                data.extend(dir_block.to_le_bytes());
+                data.extend(links.to_le_bytes());
+                data.extend((size as u16).to_le_bytes());
+                data.extend(dir_offset.to_le_bytes());
+                data.extend(parent.to_le_bytes());
+            } else {
+                header(&mut data, EXTENDED_DIR);
+                data.extend(links.to_le_bytes());
+                data.extend((size as u32).to_le_bytes());
+                data.extend(dir_block.to_le_bytes());
+                data.extend(parent.to_le_bytes());
+                data.extend(0u16.to_le_bytes()); // index
+                data.extend(dir_offset.to_le_bytes());
+                data.extend(NO_XATTR.to_le_bytes());
+            }
+        }
+        Node::File { start, size, blocks } => {
+            match (u32::try_from(*start), u32::try_from(*size)) {
+                (Ok(start), Ok(size)) => {
+                    header(&mut data, BASIC_FILE);
+                    data.extend(start.to_le_bytes());
+                    data.extend(NO_FRAGMENT.to_le_bytes());
+                    data.extend(0u32.to_le_bytes());
+                    data.extend(size.to_le_bytes());
+                }
+                // Au-delà de 4 Gio (ISO, ou données placées après)
+                _ => {
+                    header(&mut data, EXTENDED_FILE);
+                    data.extend(start.to_le_bytes());
+                    data.extend(size.to_le_bytes());
+                    data.extend(0u64.to_le_bytes()); // octets creux
+                    data.extend(1u32.to_le_bytes()); // liens
+                    data.extend(NO_FRAGMENT.to_le_bytes());
+                    data.extend(0u32.to_le_bytes());
+                    data.extend(NO_XATTR.to_le_bytes());
+                }
+            }
+            for block in blocks {
+                data.extend(block.to_le_bytes());
+            }
+        }
+        Node::Symlink(target) => {
+            header(&mut data, BASIC_SYMLINK);
+            data.extend(1u32.to_le_bytes());
+            data.extend((target.len() as u32).to_le_bytes());
+            data.extend(target.as_bytes());
+        }
+    }
+    inodes.write(&data)?;
+    Ok(Written { block, offset, inode: entry.inode, kind: entry.basic_type() })
+}
+
+/// Écrit la liste d'un dossier (triée par nom) et retourne sa taille. Un
+/// en-tête regroupe au plus 256 entrées dont les inodes sont dans le même
+/// bloc de métadonnées.
+fn write_listing(dirs: &mut MetadataWriter, entries: &[(&str, Written)]) -> io::Result<usize> {
+    let mut size = 0;
+    let mut rest = entries;
+    while let Some((_, first)) = rest.first() {
+        let run = rest.iter()
+            .take(DIR_RUN_MAX)
+            .take_while(|(_, w)| w.block == first.block && i16::try_from(i64::from(w.inode) - i64::from(first.inode)).is_ok())
+            .count();
+        let mut data = Vec::new();
+        data.extend((run as u32 - 1).to_le_bytes());
+        data.extend(first.block.to_le_bytes());
+        data.extend(first.inode.to_le_bytes());
+        for (name, written) in &rest[..run] {
+            data.extend(written.offset.to_le_bytes());
+            data.extend(((i64::from(written.inode) - i64::from(first.inode)) as i16).to_le_bytes());
+            data.extend(written.kind.to_le_bytes());
+            data.extend((name.len() as u16 - 1).to_le_bytes());
+            data.extend(name.as_bytes());
+        }
+        dirs.write(&data)?;
+        size += data.len();
+        rest = &rest[run..];
+    }
+    Ok(size)
+}
+
+/// Table de métadonnées : blocs de 8 Kio compressés, chacun précédé de sa taille.
+#[derive(Default)]
+struct MetadataWriter {
+    output: Vec<u8>,
+    buffer: Vec<u8>,
+}
+
+impl MetadataWriter {
+    /// Référence de la prochaine écriture : début du bloc dans la table, position dans le bloc.
+    fn reference(&self) -> (u32, u16) {
+        (self.output.len() as u32, self.buffer.len() as u16)
+    }
+
+    fn write(&mut self, data: &[u8]) -> io::Result<()> {
+        self.buffer.extend_from_slice(data);
+        while self.buffer.len() >= METADATA_SIZE {
+            let rest = self.buffer.split_off(METADATA_SIZE);
+            self.flush_block()?;
+            self.buffer = rest;
+        }
+        Ok(())
+    }
+
+    fn flush_block(&mut self) -> io::Result<()> {
+        let compressed = zlib(&self.buffer)?;
+        if compressed.len() < self.buffer.len() {
+            self.output.extend((compressed.len() as u16).to_le_bytes());
+            self.output.extend(compressed);
+        } else {
+            self.output.extend((self.buffer.len() as u16 | UNCOMPRESSED_METADATA).to_le_bytes());
+            self.output.extend_from_slice(&self.buffer);
+        }
+        self.buffer.clear();
+        Ok(())
+    }
+
+    fn finish(mut self) -> io::Result<Vec<u8>> {
+        if !self.buffer.is_empty() {
+            self.flush_block()?;
+        }
+        Ok(self.output)
+    }
+}
+
+fn zlib(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    encoder.finish()
+}
+
+/// Remplit `buffer` autant que possible (moins seulement en fin de flux).
+fn read_block(reader: &mut impl Read, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
@@ -0,0 +1,124 @@
+//! Assemblage d'une AppImage (type 2) : le runtime suivi d'une image
+//! squashfs contenant `AppRun`, un fichier `.desktop` et son icône.
+
+use anyhow::{Context, Result};
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use super::squashfs::SquashfsWriter;
+
+/// Icône de l'application (celle de `ui/src-tauri/icons`), utilisée quand le jeu n'en fournit pas.
+pub const DEFAULT_ICON: &[u8] = include_bytes!("../assets/icon.png");
+
+pub struct AppImageWriter {
+    path: PathBuf,
+    image: SquashfsWriter<File>,
+}
+
+impl AppImageWriter {
+    pub fn create(path: &Path, runtime: &[u8]) -> Result<Self> {
+        let mut file = File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+        file.write_all(runtime).context("Failed to write AppImage runtime")?;
+        let image = SquashfsWriter::new(file).context("Failed to start squashfs image")?;
+        Ok(Self { path: path.to_path_buf(), image })
+    }
+
+    pub fn add_bytes(&mut self, dest: &str, data: &[u8], mode: u32) -> Result<()> {
+        self.image.add_file(dest, &mut &data[..], mode, true)
+            .with_context(|| format!("Failed to add {} to AppImage", dest))?;
+        Ok(())
+    }
+
+    /// Ajoute un fichier ; `compress: false` pour les ROM (déjà compressées ou
+    /// peu compressibles). `on_percent` reçoit chaque nouveau pourcentage lu.
+    pub fn add_file(&mut self, src: &Path, dest: &str, mode: u32, compress: bool, on_percent: impl Fn(u64)) -> Result<u64> {
+        let file = File::open(src).with_context(|| format!("Failed to open {}", src.display()))?;
+        let total = file.metadata().map(|m| m.len()).unwrap_or(0);
+        let mut reader = ProgressReader { inner: file, read: 0, total, last_percent: 0, on_percent };
+        self.image.add_file(dest, &mut reader, mode, compress)
+            .with_context(|| format!("Failed to add {} to AppImage", src.display()))
+    }
+
+    /// Ajoute un dossier tel quel : permissions et liens symboliques conservés.
+    pub fn add_tree(&mut self, src: &Path, dest: &str) -> Result<()> {
+        self.image.add_dir(dest, mode_of(&fs::metadata(src)?))?;
+        for entry in fs::read_dir(src).with_context(|| format!("Failed to read {}", src.display()))? {
+            let entry = entry?;
+            let path = entry.path();
+            let name = join(dest, &entry.file_name().to_string_lossy());
+            let metadata = fs::symlink_metadata(&path)?;
+            if metadata.file_type().is_symlink() {
+                let target = fs::read_link(&path)?;
+                self.image.add_symlink(&name, &target.to_string_lossy())?;
+            } else if metadata.is_dir() {
+                self.add_tree(&path, &name)?;
+            } else if metadata.is_file() {
+                self.add_file(&path, &name, mode_of(&metadata), true, |_| {})?;
+            }
+        }
+        Ok(())
+    }
+
+    /// `<id>.desktop` et `<id>.png` à la racine, plus `.DirIcon`, comme les
+    /// attendent les intégrateurs (AppImageLauncher, appimaged...).
+    pub fn add_desktop_entry(&mut self, id: &str, name: &str, icon_png: &[u8]) -> Result<()> {
+        let desktop = format!(
+            "[Desktop Entry]\nType=Application\nName={}\nExec=AppRun\nIcon={}\nCategories=Game;\nTerminal=false\n",
+            name.replace('\n', " "),
+            id
+        );
+        self.add_bytes(&format!("{}.desktop", id), desktop.as_bytes(), 0o644)?;
+        self.add_bytes(&format!("{}.png", id), icon_png, 0o644)?;
+        self.image.add_symlink(".DirIcon", &format!("{}.png", id))?;
+        Ok(())
+    }
+
+    /// Termine l'image et rend l'AppImage exécutable.
+    pub fn finish(self) -> Result<PathBuf> {
+        self.image.finish().context("Failed to finalize squashfs image")?.sync_all()?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&self.path, fs::Permissions::from_mode(0o755))
+                .context("Failed to set permissions")?;
+        }
+        Ok(self.path)
+    }
+}
+
+fn join(dir: &str, name: &str) -> String {
+    if dir.is_empty() { name.to_string() } else { format!("{}/{}", dir, name) }
+}
+
+fn mode_of(metadata: &fs::Metadata) -> u32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.permissions().mode()
+    }
+    #[cfg(not(unix))]
+    if metadata.is_dir() { 0o755 } else { 0o644 }
+}
+
+struct ProgressReader<R, F> {
+    inner: R,
+    read: u64,
+    total: u64,
+    last_percent: u64,
+    on_percent: F,
+}
+
+impl<R: Read, F: Fn(u64)> Read for ProgressReader<R, F> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.read += n as u64;
+        if let Some(percent) = (self.read * 100).checked_div(self.total) {
+            if percent > self.last_percent {
+                self.last_percent = percent;
+                (self.on_percent)(percent);
+            }
+        }
+        Ok(n)
+    }
+}
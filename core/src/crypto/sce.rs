@@ -190,7 +190,7 @@ fn decompress_zlib(data: &[u8]) -> Result<Vec<u8>> {
 
 /// Déchiffre un fichier SELF/PKG et retourne les sections déchiffrées
 pub fn decrypt_sce_file(data: &[u8]) -> Result<Vec<Vec<u8>>> {
-    println!("    🔐 Decrypting SCE file ({} bytes)...", data.len());
+    eprintln!("    🔐 Decrypting SCE file ({} bytes)...", data.len());
     
     // Load SCE header
     let sce_hdr = SceHeader::load(data)?;
@@ -199,7 +199,7 @@ pub fn decrypt_sce_file(data: &[u8]) -> Result<Vec<Vec<u8>>> {
         return Err(anyhow!("Invalid SCE magic: 0x{:08x}", sce_hdr.se_magic));
     }
     
-    println!("      SCE header valid: type=0x{:x}, meta_offset=0x{:x}", sce_hdr.se_type, sce_hdr.se_meta);
+    eprintln!("      SCE header valid: type=0x{:x}, meta_offset=0x{:x}", sce_hdr.se_type, sce_hdr.se_meta);
     
     // Read encrypted metadata info
     let meta_info_offset = (sce_hdr.se_meta as usize) + 0x20; // sizeof(sce_hdr) = 0x20
@@ -222,7 +222,7 @@ pub fn decrypt_sce_file(data: &[u8]) -> Result<Vec<Vec<u8>>> {
         return Err(anyhow!("Failed to decrypt SCE metadata info - wrong keys?"));
     }
     
-    println!("      ✅ Metadata info decrypted");
+    eprintln!("      ✅ Metadata info decrypted");
     
     // Read and decrypt metadata headers
     let metadata_headers_offset = meta_info_offset + 0x40;
@@ -239,7 +239,7 @@ pub fn decrypt_sce_file(data: &[u8]) -> Result<Vec<Vec<u8>>> {
     
     // Parse metadata header
     let meta_hdr = MetadataHeader::load(&metadata_headers)?;
-    println!("      Section count: {}, Key count: {}", meta_hdr.section_count, meta_hdr.key_count);
+    eprintln!("      Section count: {}, Key count: {}", meta_hdr.section_count, meta_hdr.key_count);
     
     // Parse section headers
     let mut section_headers = Vec::new();
@@ -268,7 +268,7 @@ pub fn decrypt_sce_file(data: &[u8]) -> Result<Vec<Vec<u8>>> {
         let section_size = shdr.data_size as usize;
         
         if section_offset + section_size > data.len() {
-            println!("      ⚠️  Section {} extends beyond file, skipping", i);
+            eprintln!("      ⚠️  Section {} extends beyond file, skipping", i);
             continue;
         }
         
@@ -294,7 +294,7 @@ pub fn decrypt_sce_file(data: &[u8]) -> Result<Vec<Vec<u8>>> {
             match decompress_zlib(&section_data) {
                 Ok(decompressed) => decompressed,
                 Err(e) => {
-                    println!("      ⚠️  Section {} decompression failed: {}", i, e);
+                    eprintln!("      ⚠️  Section {} decompression failed: {}", i, e);
                     section_data
                 }
             }
@@ -305,6 +305,6 @@ pub fn decrypt_sce_file(data: &[u8]) -> Result<Vec<Vec<u8>>> {
         result_sections.push(final_data);
     }
     
-    println!("      ✅ Decrypted {} sections", result_sections.len());
+    eprintln!("      ✅ Decrypted {} sections", result_sections.len());
     Ok(result_sections)
 }
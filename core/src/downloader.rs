@@ -4,6 +4,9 @@ use std::fs;
 use reqwest;
 
 
+/// Emulators that can be downloaded automatically.
+pub const KNOWN_EMULATORS: &[&str] = &["ppsspp", "pcsx2", "duckstation", "dolphin", "cemu", "rpcs3", "ryujinx", "xemu", "flycast", "azahar", "melonds"];
+
 pub struct EmulatorDownloader {
     base_dir: PathBuf,
 }
@@ -13,6 +16,20 @@ impl EmulatorDownloader {
         Self { base_dir }
     }
 
+    /// Default install location: `~/.emuforge/emulators`
+    pub fn default_dir() -> PathBuf {
+        dirs::home_dir().unwrap_or(PathBuf::from(".")).join(".emuforge/emulators")
+    }
+
+    /// Known emulators already installed in `base_dir`
+    pub fn installed(&self) -> Vec<String> {
+        KNOWN_EMULATORS
+            .iter()
+            .filter(|id| self.is_installed(id))
+            .map(|id| id.to_string())
+            .collect()
+    }
+
     fn get_url(&self, emu_id: &str) -> Option<(&'static str, &'static str)> {
         // Returns (url, filename_in_archive_or_archive_name)
         // TODO: This should ideally be a dynamic manifest fetched from a server.
@@ -160,15 +177,21 @@ impl EmulatorDownloader {
         None
     }
 
+    /// Binary of an emulator installed in `base_dir`, if any
+    pub fn installed_binary(&self, emu_id: &str) -> Option<PathBuf> {
+        let binary_name = self.get_binary_name(emu_id);
+        self.find_binary_recursive(&self.base_dir.join(emu_id), &binary_name, 3)
+    }
+
     pub fn is_installed(&self, emu_id: &str) -> bool {
         let install_dir = self.base_dir.join(emu_id);
         let binary_name = self.get_binary_name(emu_id);
         
         let found = self.find_binary_recursive(&install_dir, &binary_name, 3).is_some();
         if found {
-             println!("DEBUG: Found installed {} binary: {}", emu_id, binary_name);
+             eprintln!("DEBUG: Found installed {} binary: {}", emu_id, binary_name);
         } else {
-             println!("DEBUG: Could not find {} binary: {} in {:?}", emu_id, binary_name, install_dir);
+             eprintln!("DEBUG: Could not find {} binary: {} in {:?}", emu_id, binary_name, install_dir);
         }
         found
     }
@@ -179,7 +202,7 @@ impl EmulatorDownloader {
         
         // Check if already installed
         if let Some(existing) = self.find_binary_recursive(&install_dir, &binary_name, 3) {
-             println!("Emulator {} already exists at {:?}", emu_id, existing);
+             eprintln!("Emulator {} already exists at {:?}", emu_id, existing);
              return Ok(existing);
         }
 
@@ -189,7 +212,7 @@ impl EmulatorDownloader {
         let (url, archive_name) = self.get_url(emu_id)
             .ok_or_else(|| anyhow!("Download URL not defined for {} on this OS", emu_id))?;
 
-        println!("Downloading {} from {}...", emu_id, url);
+        eprintln!("Downloading {} from {}...", emu_id, url);
         
         
         let client = reqwest::Client::builder()
@@ -246,43 +269,6 @@ impl EmulatorDownloader {
         // Fallback
         Ok(install_dir.join(binary_name))
     }
-    
-    /// Télécharge appimagetool pour le patching d'AppImages
-    pub async fn download_appimagetool(&self) -> Result<PathBuf> {
-        let tools_dir = self.base_dir.parent()
-            .unwrap_or(&self.base_dir)
-            .join("tools");
-        fs::create_dir_all(&tools_dir)
-            .context("Failed to create tools directory")?;
-        
-        let output_path = tools_dir.join("appimagetool");
-        
-        // Vérifier si déjà téléchargé
-        if output_path.exists() {
-            println!("appimagetool already exists");
-            return Ok(output_path);
-        }
-        
-        let url = "https://github.com/AppImage/AppImageKit/releases/download/continuous/appimagetool-x86_64.AppImage";
-        
-        println!("Downloading appimagetool from {}...", url);
-        let response = reqwest::get(url).await.context("Failed to fetch appimagetool")?;
-        let bytes = response.bytes().await.context("Failed to download appimagetool bytes")?;
-        
-        fs::write(&output_path, bytes).context("Failed to write appimagetool")?;
-        
-        // Rendre exécutable
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let mut perms = fs::metadata(&output_path)?.permissions();
-            perms.set_mode(0o755);
-            fs::set_permissions(&output_path, perms)?;
-        }
-        
-        println!("✅ appimagetool downloaded successfully");
-        Ok(output_path)
-    }
 }
 
 
@@ -91,7 +91,7 @@ pub fn extract_firmware(pup_path: &Path, output_dir: &Path) -> Result<PathBuf> {
     fs::create_dir_all(&temp_dir)
         .context("Failed to create temp directory for firmware extraction")?;
     
-    println!("📦 Extracting PS3UPDAT.PUP to {:?}...", temp_dir);
+    eprintln!("📦 Extracting PS3UPDAT.PUP to {:?}...", temp_dir);
     
     // Lire le fichier PUP complet
     let mut file = File::open(pup_path)
@@ -113,10 +113,10 @@ pub fn extract_firmware(pup_path: &Path, output_dir: &Path) -> Result<PathBuf> {
         ));
     }
     
-    println!("  ✅ Valid PUP header:");
-    println!("     File count: {}", header.file_count);
-    println!("     Header length: 0x{:x}", header.header_length);
-    println!("     Data length: 0x{:x}", header.data_length);
+    eprintln!("  ✅ Valid PUP header:");
+    eprintln!("     File count: {}", header.file_count);
+    eprintln!("     Header length: 0x{:x}", header.header_length);
+    eprintln!("     Data length: 0x{:x}", header.data_length);
     
     // Parser la table des fichiers (après le header de 0x30 bytes)
     let mut entries = Vec::new();
@@ -137,9 +137,9 @@ pub fn extract_firmware(pup_path: &Path, output_dir: &Path) -> Result<PathBuf> {
         .find(|e| e.entry_id == UPDATE_FILES_ENTRY_ID)
         .ok_or_else(|| anyhow!("update_files.tar (entry 0x300) not found in PUP"))?;
     
-    println!("  📄 Found update_files.tar:");
-    println!("     Offset: 0x{:x}", update_files_entry.data_offset);
-    println!("     Size: {} bytes", update_files_entry.data_length);
+    eprintln!("  📄 Found update_files.tar:");
+    eprintln!("     Offset: 0x{:x}", update_files_entry.data_offset);
+    eprintln!("     Size: {} bytes", update_files_entry.data_length);
     
     // Extraire update_files.tar
     let tar_start = update_files_entry.data_offset as usize;
@@ -155,7 +155,7 @@ pub fn extract_firmware(pup_path: &Path, output_dir: &Path) -> Result<PathBuf> {
     let mut tar_file = File::create(&tar_path)?;
     tar_file.write_all(tar_data)?;
     
-    println!("  📦 Extracting update_files.tar...");
+    eprintln!("  📦 Extracting update_files.tar...");
     
     // Extraire le TAR
     let tar_file = File::open(&tar_path)?;
@@ -168,7 +168,7 @@ pub fn extract_firmware(pup_path: &Path, output_dir: &Path) -> Result<PathBuf> {
     fs::create_dir_all(&dev_flash_dir)?;
     
     // Trouver et traiter les fichiers dev_flash_*.tar.*
-    println!("  🔐 Decrypting dev_flash packages...");
+    eprintln!("  🔐 Decrypting dev_flash packages...");
     
     let mut dev_flash_files: Vec<_> = fs::read_dir(&temp_dir)?
         .filter_map(|e| e.ok())
@@ -184,12 +184,12 @@ pub fn extract_firmware(pup_path: &Path, output_dir: &Path) -> Result<PathBuf> {
         return Err(anyhow!("No dev_flash_* packages found in update_files.tar"));
     }
     
-    println!("     Found {} dev_flash packages", dev_flash_files.len());
+    eprintln!("     Found {} dev_flash packages", dev_flash_files.len());
     
     for entry in &dev_flash_files {
         let path = entry.path();
         let filename = path.file_name().unwrap().to_string_lossy();
-        println!("     Processing {}...", filename);
+        eprintln!("     Processing {}...", filename);
         
         // Lire le fichier SELF/SCE
         let sce_data = fs::read(&path)?;
@@ -206,10 +206,10 @@ pub fn extract_firmware(pup_path: &Path, output_dir: &Path) -> Result<PathBuf> {
                         let _ = inner_tar.unpack(&dev_flash_dir);
                     }
                 }
-                println!("       ✅ Extracted");
+                eprintln!("       ✅ Extracted");
             }
             Err(e) => {
-                println!("       ⚠️  Decryption failed: {}", e);
+                eprintln!("       ⚠️  Decryption failed: {}", e);
             }
         }
     }
@@ -220,8 +220,8 @@ pub fn extract_firmware(pup_path: &Path, output_dir: &Path) -> Result<PathBuf> {
         return Err(anyhow!("dev_flash is empty after extraction"));
     }
     
-    println!("✅ Firmware extracted successfully");
-    println!("   dev_flash: {:?} ({} entries)", dev_flash_dir, content_count);
+    eprintln!("✅ Firmware extracted successfully");
+    eprintln!("   dev_flash: {:?} ({} entries)", dev_flash_dir, content_count);
     
     Ok(dev_flash_dir)
 }
@@ -0,0 +1,268 @@
+//! Forge par lots : un dossier (ou une liste) de ROMs, un exécutable par jeu.
+//!
+//! La plateforme de chaque ROM est détectée par `FileAnalyzer`, le plugin
+//! choisi par `PluginManager`, puis les jeux sont forgés sur `jobs` threads.
+//! La préparation d'un même plugin (config de l'émulateur sur l'hôte) reste
+//! séquentielle. Une ROM en échec est consignée dans le rapport sans
+//! interrompre le lot.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::detection::{FileAnalyzer, FileType, Platform};
+use crate::downloader::EmulatorDownloader;
+use crate::forge::pipeline::{forge_game, sanitize_filename, ForgeRequest};
+use crate::forge::portable::disc_dependencies;
+use crate::forge::progress::ForgeProgress;
+use crate::plugin::manager::PluginManager;
+
+/// Paramètres communs à tous les jeux d'un lot.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BatchOptions {
+    pub output_dir: PathBuf,
+    pub portable: bool,
+    pub fullscreen: bool,
+    /// Nombre de forges simultanés (1 si 0)
+    pub jobs: usize,
+    /// Binaire d'émulateur par id de plugin ; sinon émulateur téléchargé, puis installé sur l'hôte
+    pub emulators: HashMap<String, PathBuf>,
+    /// BIOS / firmware par id de plugin
+    pub bios: HashMap<String, PathBuf>,
+    pub signing_key: Option<String>,
+    /// Émulateurs dans des packs `.emupack` partagés (portable uniquement)
+    pub thin: bool,
+    /// Archive de chaque jeu dans un `.emudata` séparé (portable uniquement)
+    pub sidecar: bool,
+    /// Taille des parties du `.emudata`, `None` : un seul fichier
+    pub part_size: Option<u64>,
+    /// Un `<jeu>.AppImage` par jeu (portable uniquement)
+    pub appimage: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchStatus {
+    Ok,
+    Failed,
+}
+
+/// Résultat du forge d'une ROM.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchEntry {
+    pub rom: PathBuf,
+    pub game_name: String,
+    pub platform: String,
+    pub plugin: Option<String>,
+    pub status: BatchStatus,
+    pub output: Option<PathBuf>,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// Rapport JSON d'un lot, dans l'ordre des ROMs fournies.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchReport {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub entries: Vec<BatchEntry>,
+}
+
+impl BatchReport {
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).context("Failed to serialize batch report")
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_json()?).with_context(|| format!("Failed to write batch report {:?}", path))
+    }
+}
+
+/// ROMs d'un dossier, triées. Les pistes référencées par un CUE ou un GDI
+/// sont écartées : elles sont embarquées avec leur image.
+pub fn collect_roms(dir: &Path, recursive: bool) -> Result<Vec<PathBuf>> {
+    let mut roms = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        for entry in fs::read_dir(&current).with_context(|| format!("Failed to read directory {:?}", current))? {
+            let path = entry?.path();
+            if path.is_dir() {
+                if recursive {
+                    pending.push(path);
+                }
+            } else if is_rom_candidate(&path) {
+                roms.push(path);
+            }
+        }
+    }
+
+    let mut tracks = HashSet::new();
+    for rom in &roms {
+        if let Ok(deps) = disc_dependencies(rom) {
+            tracks.extend(deps.into_iter().map(|(path, _)| path));
+        }
+    }
+    roms.retain(|rom| !tracks.contains(rom));
+    roms.sort();
+    Ok(roms)
+}
+
+fn is_rom_candidate(path: &Path) -> bool {
+    let known_type = !matches!(FileAnalyzer::detect_type(path), None | Some(FileType::Unknown(_)));
+    let is_3ds = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("3ds"));
+    (known_type || is_3ds) && FileAnalyzer::is_valid_rom(path)
+}
+
+/// Jeu prêt à forger, ou erreur à consigner telle quelle.
+struct Job {
+    entry: BatchEntry,
+    request: Result<ForgeRequest>,
+}
+
+/// Forge toutes les ROMs et retourne le rapport ; n'échoue jamais globalement.
+///
+/// `progress` reçoit l'avancement global du lot (pourcentage de ROMs traitées).
+pub fn forge_batch(
+    roms: &[PathBuf],
+    options: &BatchOptions,
+    stub_hints: &[PathBuf],
+    progress: &(dyn ForgeProgress + Sync),
+) -> BatchReport {
+    let jobs = plan(roms, options);
+    let total = jobs.len();
+    let next = AtomicUsize::new(0);
+    let done = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<BatchEntry>>> = Mutex::new(vec![None; total]);
+
+    let worker = || loop {
+        let index = next.fetch_add(1, Ordering::SeqCst);
+        let Some(job) = jobs.get(index) else { break };
+
+        let started = Instant::now();
+        let game_progress = |_: u64, message: &str| {
+            let percentage = done.load(Ordering::SeqCst) as u64 * 100 / total as u64;
+            progress.report(percentage, &format!("[{}/{}] {}: {}", index + 1, total, job.entry.game_name, message));
+        };
+        let outcome = match &job.request {
+            Ok(request) => forge_game(request, stub_hints, &game_progress),
+            Err(e) => Err(anyhow::anyhow!("{:#}", e)),
+        };
+
+        let mut entry = job.entry.clone();
+        entry.duration_ms = started.elapsed().as_millis() as u64;
+        match outcome {
+            Ok(output) => {
+                entry.status = BatchStatus::Ok;
+                entry.output = Some(output);
+            }
+            Err(e) => {
+                eprintln!("❌ {}: {:#}", job.entry.rom.display(), e);
+                entry.error = Some(format!("{:#}", e));
+            }
+        }
+        results.lock().unwrap()[index] = Some(entry);
+
+        let finished = done.fetch_add(1, Ordering::SeqCst) + 1;
+        progress.report(finished as u64 * 100 / total as u64, &format!("[{}/{}] {} terminé", index + 1, total, job.entry.game_name));
+    };
+
+    std::thread::scope(|scope| {
+        for _ in 0..options.jobs.clamp(1, total.max(1)) {
+            scope.spawn(worker);
+        }
+    });
+
+    let entries: Vec<BatchEntry> = results.into_inner().unwrap().into_iter().flatten().collect();
+    let succeeded = entries.iter().filter(|e| e.status == BatchStatus::Ok).count();
+    BatchReport { total, succeeded, failed: total - succeeded, entries }
+}
+
+/// Détecte plateforme, plugin et émulateur de chaque ROM (séquentiel, rapide).
+fn plan(roms: &[PathBuf], options: &BatchOptions) -> Vec<Job> {
+    let manager = PluginManager::new();
+    let downloader = EmulatorDownloader::new(EmulatorDownloader::default_dir());
+    let mut emulators: HashMap<String, Option<PathBuf>> = HashMap::new();
+    let mut used_names = HashSet::new();
+
+    roms.iter()
+        .map(|rom| {
+            let stem = rom.file_stem().unwrap_or_default().to_string_lossy();
+            let game_name = unique_name(&sanitize_filename(&stem), &mut used_names);
+            let platform = FileAnalyzer::identify_platform(rom);
+            let plugin = manager.plugin_for_platform(&platform);
+
+            let entry = BatchEntry {
+                rom: rom.clone(),
+                game_name: game_name.clone(),
+                platform: platform.as_str().to_string(),
+                plugin: plugin.map(|p| p.id().to_string()),
+                status: BatchStatus::Failed,
+                output: None,
+                error: None,
+                duration_ms: 0,
+            };
+
+            let request = (|| {
+                if !FileAnalyzer::is_valid_rom(rom) {
+                    anyhow::bail!("ROM not found or empty: {:?}", rom);
+                }
+                if platform == Platform::Unknown {
+                    anyhow::bail!("Unrecognized platform");
+                }
+                let plugin = plugin.ok_or_else(|| anyhow::anyhow!("No emulator plugin for platform '{}'", platform.as_str()))?;
+                let emulator = emulators
+                    .entry(plugin.id().to_string())
+                    .or_insert_with(|| {
+                        options.emulators.get(plugin.id()).cloned()
+                            .or_else(|| downloader.installed_binary(plugin.id()))
+                            .or_else(|| plugin.find_binary().ok())
+                    })
+                    .clone()
+                    .ok_or_else(|| anyhow::anyhow!("Emulator '{}' not found (download it or pass its path)", plugin.id()))?;
+
+                // Un raccourci garde ses configs à côté de lui : un dossier par jeu
+                let output_dir = if options.portable {
+                    options.output_dir.clone()
+                } else {
+                    options.output_dir.join(&game_name)
+                };
+
+                Ok(ForgeRequest {
+                    game_name: game_name.clone(),
+                    emulator_path: emulator,
+                    rom_path: rom.clone(),
+                    bios_path: options.bios.get(plugin.id()).cloned(),
+                    output_dir,
+                    fullscreen: options.fullscreen,
+                    portable: options.portable,
+                    signing_key: options.signing_key.clone(),
+                    thin: options.thin,
+                    sidecar: options.sidecar,
+                    part_size: options.part_size,
+                    appimage: options.appimage,
+                    ..Default::default()
+                })
+            })();
+
+            Job { entry, request }
+        })
+        .collect()
+}
+
+/// Deux ROMs de même nom ne doivent pas écraser le même exécutable.
+fn unique_name(base: &str, used: &mut HashSet<String>) -> String {
+    let mut name = base.to_string();
+    let mut n = 2;
+    while !used.insert(name.clone()) {
+        name = format!("{} {}", base, n);
+        n += 1;
+    }
+    name
+}
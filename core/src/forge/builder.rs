@@ -1,82 +1,157 @@
 use anyhow::{Context, Result};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use std::process::Command;
 use std::fs;
+use crate::forge::template::{self, Variables};
 use crate::forge::LaunchConfig;
+use crate::forge::stub;
+use crate::forge::signing::{self, SigningKey};
+use emuforge_format::container::SectionKind;
 
 pub struct ExecutableForge {
-    /// Path to the stub crate directory (containing Cargo.toml)
-    pub stub_crate_path: PathBuf,
+    /// Path to the prebuilt stub binary used as a template
+    pub stub_template_path: PathBuf,
     /// Destination directory for forged executables
     pub output_dir: PathBuf,
+    /// Optional ed25519 key used to sign forged executables
+    pub signing_key: Option<SigningKey>,
 }
 
 impl ExecutableForge {
-    pub fn new(stub_crate_path: PathBuf, output_dir: PathBuf) -> Self {
+    pub fn new(stub_template_path: PathBuf, output_dir: PathBuf) -> Self {
         Self {
-            stub_crate_path,
+            stub_template_path,
             output_dir,
+            signing_key: None,
         }
     }
 
-    pub fn forge(&self, game_name: &str, config: &LaunchConfig) -> Result<PathBuf> {
-        // 1. Create a build directory
-        let build_dir = std::env::temp_dir().join("emuforge").join(game_name);
-        if build_dir.exists() {
-            fs::remove_dir_all(&build_dir).context("Failed to clean build dir")?;
-        }
-        fs::create_dir_all(&build_dir).context("Failed to create build dir")?;
-
-        // 2. Serialize config
-        let config_path = build_dir.join("launch_config.json");
-        let config_json = serde_json::to_string_pretty(config)?;
-        fs::write(&config_path, config_json).context("Failed to write config file")?;
-
-        // 3. Compile the stub with the injected config
-        // We use the 'stub' crate located at stub_crate_path
-        let cargo_toml_path = self.stub_crate_path.join("Cargo.toml");
-        if !cargo_toml_path.exists() {
-            anyhow::bail!("Stub Cargo.toml not found at {:?}", cargo_toml_path);
-        }
-
-        println!("Compiling stub for {}...", game_name);
-        let output = Command::new("cargo")
-            .args(&["build", "--release", "--manifest-path", cargo_toml_path.to_str().unwrap(), "--target-dir", build_dir.join("target").to_str().unwrap()])
-            .env("EMUFORGE_CONFIG_PATH", &config_path)
-            .output()
-            .context("Failed to run cargo build")?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("Stub compilation failed: {}", stderr);
-        }
+    /// Signs every forged executable with `key`.
+    pub fn with_signing_key(mut self, key: Option<SigningKey>) -> Self {
+        self.signing_key = key;
+        self
+    }
 
-        // 4. Locate the compiled binary
-        // Note: With --target-dir, the binary is in <target-dir>/release/
-        #[cfg(windows)]
-        let binary_name = "emuforge-stub.exe";
-        #[cfg(not(windows))]
-        let binary_name = "emuforge-stub";
+    /// Creates a forge using the stub found by `stub::locate_stub_template`.
+    pub fn with_default_stub(output_dir: PathBuf, hints: &[PathBuf]) -> Result<Self> {
+        let stub_template_path = stub::locate_stub_template(hints)?;
+        Ok(Self::new(stub_template_path, output_dir))
+    }
 
-        let built_binary = build_dir.join("target").join("release").join(binary_name);
-        
-        if !built_binary.exists() {
-             anyhow::bail!("Compiled binary not found at {:?}", built_binary);
+    pub fn forge(&self, game_name: &str, config: &LaunchConfig) -> Result<PathBuf> {
+        if !self.stub_template_path.is_file() {
+            anyhow::bail!("Stub template not found at {:?}", self.stub_template_path);
         }
 
-        // 5. Copy to output
+        // 1. Serialize config, with paths relative to the executable where possible
+        config.validate_templates().context("Invalid template in launch config")?;
         fs::create_dir_all(&self.output_dir)?;
-        
+        let config = OutputDir::new(&self.output_dir)?.relocatable(config);
+        let config_json = config.to_json().context("Failed to serialize config")?;
+
         #[cfg(windows)]
         let final_name = format!("{}.exe", game_name);
         #[cfg(not(windows))]
         let final_name = game_name.to_string();
 
         let final_path = self.output_dir.join(&final_name);
-        
-        fs::copy(&built_binary, &final_path).context("Failed to copy binary to output")?;
+
+        // 2. Append the config as a container section
+        let mut container = stub::begin_container(&self.stub_template_path, &final_path)?;
+        container.add_section(SectionKind::LaunchConfig, &config_json)?;
+        if let Some(key) = &self.signing_key {
+            signing::sign_container(&mut container, key)?;
+        }
+        container.finish().context("Failed to finalize container")?;
+
+        // 3. Make executable on Unix
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&final_path, fs::Permissions::from_mode(0o755))
+                .context("Failed to set permissions")?;
+        }
 
         Ok(final_path)
     }
 }
+
+/// Dossier de sortie, où l'exécutable est écrit : ce qui s'y trouve est
+/// enregistré relativement à l'exécutable pour que le dossier reste
+/// déplaçable (autre disque, autre point de montage).
+struct OutputDir {
+    canonical: PathBuf,
+    absolute: PathBuf,
+}
+
+impl OutputDir {
+    fn new(dir: &Path) -> Result<Self> {
+        Ok(Self {
+            canonical: fs::canonicalize(dir).context("Failed to resolve output directory")?,
+            absolute: std::path::absolute(dir)?,
+        })
+    }
+
+    /// Chemin relatif au dossier de sortie si `path` s'y trouve.
+    fn inside(&self, path: &Path) -> Option<PathBuf> {
+        let absolute = std::path::absolute(path).ok()?;
+        let canonical = fs::canonicalize(path).unwrap_or_else(|_| absolute.clone());
+        canonical.strip_prefix(&self.canonical)
+            .or_else(|_| absolute.strip_prefix(&self.absolute))
+            .ok()
+            .map(Path::to_path_buf)
+    }
+
+    fn relocatable(&self, config: &LaunchConfig) -> LaunchConfig {
+        let mut config = config.clone();
+        for (what, path) in [("Emulator", &mut config.emulator_path), ("ROM", &mut config.rom_path)] {
+            if !path.as_os_str().is_empty() {
+                *path = self.relocate_path(path, Some(what));
+            }
+        }
+        for path in config.bios_path.iter_mut().chain(config.working_dir.iter_mut()) {
+            *path = self.relocate_path(path, None);
+        }
+        for (_, value) in &mut config.env_vars {
+            *value = self.relocate_template(value);
+        }
+        for arg in config.args.iter_mut().chain(config.args_after_rom.iter_mut()) {
+            *arg = self.relocate_template(arg);
+        }
+        config
+    }
+
+    /// Relatif si dans le dossier de sortie, sinon absolu (le stub résout
+    /// les chemins relatifs par rapport à l'exécutable, pas au dossier courant).
+    fn relocate_path(&self, path: &Path, required: Option<&str>) -> PathBuf {
+        if let Some(relative) = self.inside(path) {
+            return relative;
+        }
+        let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+        if let Some(what) = required {
+            eprintln!("⚠️ {} outside the output directory, the shortcut breaks if it moves: {:?}", what, absolute);
+        }
+        absolute
+    }
+
+    /// `{exe_dir}/...` pour un modèle littéral désignant un chemin absolu du
+    /// dossier de sortie. Les autres valeurs (`xcb`, `-f`...) sont gardées telles quelles.
+    fn relocate_template(&self, value: &str) -> String {
+        let Ok(literal) = Variables::new().render(value) else { return value.to_string() };
+        let path = Path::new(&literal);
+        if !path.is_absolute() {
+            return value.to_string();
+        }
+        match self.inside(path) {
+            Some(relative) if relative.as_os_str().is_empty() => "{exe_dir}".to_string(),
+            Some(relative) => {
+                let relative = relative.components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                format!("{{exe_dir}}/{}", template::escape(&relative))
+            }
+            None => value.to_string(),
+        }
+    }
+}
@@ -1,6 +1,18 @@
-pub mod config;
 pub mod builder;
+pub mod stub;
+pub mod signing;
+pub mod pipeline;
+pub mod portable;
+pub mod progress;
+pub mod batch;
 
-pub use config::LaunchConfig;
+pub use emuforge_format::config::{self, LaunchConfig, PortableConfig};
 pub use builder::ExecutableForge;
-
+pub use pipeline::{forge_game, ForgeRequest};
+pub use portable::{PortableForge, PortableGame};
+pub use progress::{ForgeProgress, NoProgress};
+pub use batch::{collect_roms, forge_batch, BatchOptions, BatchReport};
+pub use emuforge_format::bundle;
+pub use emuforge_format::container;
+pub use emuforge_format::manifest;
+pub use emuforge_format::template;
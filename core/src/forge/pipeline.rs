@@ -0,0 +1,185 @@
+//! Orchestration complète d'un forge (détection du plugin, config de
+//! lancement, raccourci ou portable), partagée par le GUI et la CLI.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::forge::portable::{PortableForge, PortableGame};
+use crate::forge::progress::ForgeProgress;
+use crate::forge::signing::{self, SigningKey};
+use crate::forge::{stub, ExecutableForge, LaunchConfig};
+use crate::plugin::manager::PluginManager;
+use crate::plugin::HostSpecs;
+
+/// Paramètres d'un forge.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ForgeRequest {
+    pub game_name: String,
+    pub emulator_path: PathBuf,
+    pub rom_path: PathBuf,
+    pub bios_path: Option<PathBuf>,
+    pub output_dir: PathBuf,
+    pub fullscreen: bool,
+    /// Extra arguments, only used when no plugin handles the emulator
+    pub args: Vec<String>,
+    /// Embed emulator, ROM and configs instead of forging a shortcut
+    pub portable: bool,
+    pub screen_width: Option<u32>,
+    pub screen_height: Option<u32>,
+    /// Name of the signing key; `EMUFORGE_SIGNING_KEY` is used when unset
+    pub signing_key: Option<String>,
+    /// Portable only: ship the emulator in a separate `<id>.emupack`
+    pub thin: bool,
+    /// Portable only: write the archive to `<name>.emudata` next to the executable
+    pub sidecar: bool,
+    /// Sidecar only: split the data file into parts of this many bytes
+    pub part_size: Option<u64>,
+    /// Portable only: build `<name>.AppImage` (squashfs image, stub as `AppRun`)
+    pub appimage: bool,
+}
+
+impl ForgeRequest {
+    fn load_signing_key(&self) -> Result<Option<SigningKey>> {
+        match &self.signing_key {
+            Some(name) => signing::load_signing_key(name).map(Some),
+            None => signing::default_signing_key(),
+        }
+    }
+}
+
+/// Verrou de préparation d'un plugin. Elle peut modifier la config de
+/// l'émulateur sur l'hôte (Ryujinx: `~/.config/Ryujinx/Config.json`) : les
+/// forges parallèles d'un lot la font chacun à son tour.
+fn plugin_lock(id: &str) -> Arc<Mutex<()>> {
+    static LOCKS: Mutex<BTreeMap<String, Arc<Mutex<()>>>> = Mutex::new(BTreeMap::new());
+    let mut locks = LOCKS.lock().unwrap_or_else(PoisonError::into_inner);
+    locks.entry(id.to_string()).or_default().clone()
+}
+
+/// Forge a game and return the path of the produced executable.
+///
+/// `stub_hints` are extra directories searched for the prebuilt stub.
+pub fn forge_game(request: &ForgeRequest, stub_hints: &[PathBuf], progress: &dyn ForgeProgress) -> Result<PathBuf> {
+    let rom_p = &request.rom_path;
+    let emu_p = &request.emulator_path;
+    let out_path = &request.output_dir;
+
+    let manager = PluginManager::new();
+
+    // Use configured_driver_for to start with a fresh plugin instance
+    // that knows about the user-provided binary path.
+    let maybe_plugin = manager.configured_driver_for(emu_p);
+
+    let plugin_progress = |msg: String| progress.report(0, &msg);
+    let host_specs = HostSpecs::detect(request.screen_width, request.screen_height);
+
+    let mut config = if let Some(plugin) = &maybe_plugin {
+        let lock = plugin_lock(plugin.id());
+        let _guard = lock.lock().unwrap_or_else(PoisonError::into_inner);
+        plugin.prepare_launch_config_with_specs(
+            rom_p,
+            out_path,
+            Some(host_specs),
+            Some(&plugin_progress)
+        ).context("Plugin error")?
+    } else {
+        LaunchConfig {
+            emulator_path: emu_p.clone(),
+            rom_path: rom_p.clone(),
+            bios_path: request.bios_path.clone(),
+            args: request.args.clone(),
+            args_after_rom: vec![],
+            working_dir: None,
+            env_vars: vec![],
+            extra_args_after_rom: false,
+            driver_id: "generic".to_string(),
+        }
+    };
+
+    // Fullscreen args via le plugin
+    if request.fullscreen {
+        if let Some(plugin) = &maybe_plugin {
+            // Args avant la ROM
+            config.args.extend(plugin.fullscreen_args());
+            // Args après la ROM (ex: Cemu -f)
+            config.args_after_rom.extend(plugin.fullscreen_args_after_rom());
+        } else {
+            config.args.push("--fullscreen".to_string());
+        }
+    }
+    config.extra_args_after_rom = maybe_plugin.as_ref().is_some_and(|p| p.extra_args_after_rom());
+
+    // Locate the prebuilt stub template (no compilation involved)
+    let stub_binary = stub::locate_stub_template(stub_hints)?;
+    let signing_key = request.load_signing_key()?;
+
+    if request.portable {
+        let game = PortableGame {
+            game_name: request.game_name.clone(),
+            emulator_path: emu_p.clone(),
+            rom_path: rom_p.clone(),
+            bios_path: request.bios_path.clone(),
+            fullscreen: request.fullscreen,
+            thin: request.thin,
+            sidecar: request.sidecar,
+            part_size: request.part_size,
+            appimage: request.appimage,
+        };
+        return PortableForge::new(stub_binary, out_path.clone())
+            .with_signing_key(signing_key)
+            .forge(&game, progress);
+    }
+
+    let forge = ExecutableForge::new(stub_binary, out_path.clone()).with_signing_key(signing_key);
+
+    // Setup environment pour le mode NON-PORTABLE (Raccourci)
+    // Ici, on VEUT que la structure persiste dans le dossier de sortie pour que le jeu fonctionne.
+    if let Some(plugin) = &maybe_plugin {
+        let bios_p = request.bios_path.as_deref();
+
+        // Setup environment (configs, BIOS, etc.)
+        plugin.setup_environment(out_path, bios_p)
+            .context("Environment setup failed")?;
+
+        // Vérifier si le plugin nécessite un patch de l'émulateur (ex: RPCS3 avec firmware)
+        let final_emulator_path = if let Some(patched) = plugin.prepare_portable_binary(emu_p, bios_p, out_path)
+            .context("Emulator patching failed")? {
+            eprintln!("🔧 Using patched emulator binary for shortcut");
+            patched
+        } else {
+            emu_p.clone()
+        };
+
+        // Update config with potentially patched emulator path
+        config.emulator_path = final_emulator_path;
+
+        // Generate wrapper script si nécessaire (ex: DuckStation)
+        if plugin.requires_wrapper() {
+            if let Some(wrapper_path) = plugin.generate_wrapper_script(&config, out_path, &request.game_name)
+                .context("Wrapper error")? {
+                // Modifier la config pour utiliser le wrapper
+                config.emulator_path = wrapper_path;
+                config.args.clear();
+                config.rom_path = PathBuf::from("");
+            }
+        }
+
+        // Inject Environment Variables (Force Standalone Config)
+        // `{config_dir}` est le dossier de l'exécutable en mode raccourci (= out_path)
+        let envs = plugin.portable_env_vars(Path::new("{config_dir}"));
+        config.env_vars.extend(envs);
+    }
+
+    forge.forge(&request.game_name, &config).context("Forge failed")
+}
+
+/// Sanitize filename to be safe for filesystem
+pub fn sanitize_filename(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' || c == ' ' { c } else { '_' })
+        .collect()
+}
@@ -0,0 +1,612 @@
+//! Forge d'exécutables portables : émulateur, ROM, BIOS et configs sont
+//! embarqués dans une archive ZIP écrite directement à la suite du stub, ou
+//! à côté de lui dans `<jeu>.emudata` (mode `sidecar`), ou encore dans une
+//! AppImage (mode `appimage`).
+
+use anyhow::{Context, Result};
+use std::fs::{self, File};
+use std::io::{Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use crate::appimage::runtime;
+use crate::appimage::writer::{AppImageWriter, DEFAULT_ICON};
+use crate::forge::config::PortableConfig;
+use crate::forge::manifest::{hash_reader, HashingWriter, IntegrityManifest};
+use crate::forge::pipeline::sanitize_filename;
+use crate::forge::progress::ForgeProgress;
+use crate::forge::signing::{self, SigningKey};
+use crate::forge::stub;
+use crate::plugin::manager::PluginManager;
+use crate::plugin::{BundleLayout, EmulatorPlugin};
+use emuforge_format::container::SectionKind;
+use emuforge_format::sidecar::{self, PartsReader, PartsWriter, SidecarInfo};
+
+/// Jeu à embarquer dans un exécutable portable.
+#[derive(Debug, Clone, Default)]
+pub struct PortableGame {
+    pub game_name: String,
+    pub emulator_path: PathBuf,
+    pub rom_path: PathBuf,
+    pub bios_path: Option<PathBuf>,
+    pub fullscreen: bool,
+    /// Émulateur écrit à part dans `<id>.emupack`, partagé entre exécutables
+    pub thin: bool,
+    /// Archive écrite à part dans `<exécutable>.emudata` plutôt qu'embarquée
+    pub sidecar: bool,
+    /// Découpe les données en parties de cette taille (`.emudata.001`...)
+    pub part_size: Option<u64>,
+    /// `<jeu>.AppImage` : émulateur et ROM en fichiers de l'image squashfs,
+    /// le stub en `AppRun` (prioritaire sur `thin` et `sidecar`)
+    pub appimage: bool,
+}
+
+/// Destination de l'archive : section de l'exécutable ou fichier de données.
+trait WriteSeek: Write + Seek {}
+
+impl<T: Write + Seek> WriteSeek for T {}
+
+pub struct PortableForge {
+    /// Path to the prebuilt stub binary used as a template
+    pub stub_template_path: PathBuf,
+    /// Destination directory for forged executables
+    pub output_dir: PathBuf,
+    /// Optional ed25519 key used to sign forged executables
+    pub signing_key: Option<SigningKey>,
+}
+
+impl PortableForge {
+    pub fn new(stub_template_path: PathBuf, output_dir: PathBuf) -> Self {
+        Self {
+            stub_template_path,
+            output_dir,
+            signing_key: None,
+        }
+    }
+
+    /// Creates a forge using the stub found by `stub::locate_stub_template`.
+    pub fn with_default_stub(output_dir: PathBuf, hints: &[PathBuf]) -> Result<Self> {
+        let stub_template_path = stub::locate_stub_template(hints)?;
+        Ok(Self::new(stub_template_path, output_dir))
+    }
+
+    /// Signs every forged executable with `key`.
+    pub fn with_signing_key(mut self, key: Option<SigningKey>) -> Self {
+        self.signing_key = key;
+        self
+    }
+
+    /// Create a portable all-in-one executable with embedded emulator, ROM, BIOS, and config
+    pub fn forge(&self, game: &PortableGame, progress: &dyn ForgeProgress) -> Result<PathBuf> {
+        if !self.stub_template_path.is_file() {
+            anyhow::bail!("Stub template not found at {:?}", self.stub_template_path);
+        }
+
+        // Create output directory for the FINAL file
+        fs::create_dir_all(&self.output_dir).context("Failed to create output directory")?;
+        let output_dir = self.output_dir.canonicalize()
+            .context("Failed to canonicalize output directory")?;
+
+        // Use SYSTEM TEMP directory for assembly to avoid triggering Tauri watcher
+        let work_dir = std::env::temp_dir().join(format!("emuforge_build_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&work_dir).context("Failed to create temp work dir")?;
+        eprintln!("🛠️  Build temporaire dans: {:?}", work_dir);
+
+        let result = self.build(game, &work_dir, &output_dir, progress);
+
+        // Cleanup temp files
+        eprintln!("🧹 Suppression du dossier temporaire: {:?}", work_dir);
+        let _ = fs::remove_dir_all(&work_dir);
+        result
+    }
+
+    fn build(&self, game: &PortableGame, work_dir: &Path, output_dir: &Path, progress: &dyn ForgeProgress) -> Result<PathBuf> {
+        let manager = PluginManager::new();
+        let plugin = manager.configured_driver_for(&game.emulator_path);
+        let driver_id = plugin.as_ref().map(|p| p.id().to_string()).unwrap_or_else(|| "generic".to_string());
+        let layout = plugin.as_ref().map(|p| p.bundle_layout()).unwrap_or_default();
+
+        let emulator_path = prepare_emulator(plugin.as_deref(), game, work_dir)?;
+        let emu_filename = archive_name(&emulator_path, "emulator")?;
+        let rom_filename = archive_name(&game.rom_path, "ROM")?;
+        stage_bios(&layout, game.bios_path.as_deref(), work_dir)?;
+
+        // Step 1: Create the portable config JSON
+        let config_dir_name = layout.config_root.as_str();
+
+        // Obtenir les configurations de lancement depuis le plugin
+        let (env_vars_list, args_before, args_after) = if let Some(plugin) = &plugin {
+            let config_path = PathBuf::from("{config_dir}");
+            let env_vars = plugin.portable_env_vars(&config_path);
+            let (before, after) = plugin.portable_launch_args(game.fullscreen);
+            (env_vars, before, after)
+        } else {
+            // Fallback générique
+            let before = if game.fullscreen { vec!["--fullscreen".to_string()] } else { vec![] };
+            (vec![], before, vec![])
+        };
+        let extra_args_after_rom = plugin.as_ref().is_some_and(|p| p.extra_args_after_rom());
+
+        let portable_config = PortableConfig {
+            game_name: sanitize_filename(&game.game_name),
+            emulator_filename: emu_filename.clone(),
+            rom_filename: rom_filename.clone(),
+            config_dir: config_dir_name.to_string(),
+            fullscreen: game.fullscreen,
+            env_vars: env_vars_list,
+            args_before_rom: args_before,
+            args_after_rom: args_after,
+            extra_args_after_rom,
+            driver_id, // Identifiant du plugin pour la détection dynamique
+            save_dirs: layout.save_dirs.clone(),
+            save_files: layout.save_files.clone(),
+            host_save_dirs: layout.host_save_dirs.clone(),
+            appdir: game.appimage,
+        };
+        portable_config.validate_templates().context("Invalid template in portable config")?;
+        let config_json = portable_config.to_json().context("Failed to serialize config")?;
+
+        if game.appimage {
+            let output_path = output_dir.join(format!("{}.AppImage", sanitize_filename(&game.game_name)));
+            let result = (|| {
+                // Runtime livré à côté du stub, sinon téléchargé
+                let runtime_hints: Vec<PathBuf> = self.stub_template_path.parent().map(Path::to_path_buf).into_iter().collect();
+                let runtime = runtime::locate(&runtime_hints).context("AppImage runtime unavailable")?;
+                let image = AppImageWriter::create(&output_path, &runtime)?;
+                // AppRun : le stub et sa config ; seuls les dossiers du plugin
+                // (configs, BIOS) sont extraits, depuis AppRun.emudata
+                let app_dir = work_dir.join("AppDir");
+                fs::create_dir_all(&app_dir).context("Failed to create AppDir")?;
+                let app_run_game = PortableGame { sidecar: true, part_size: None, ..game.clone() };
+                self.write_executable(&app_dir.join("AppRun"), &app_run_game, &config_json, progress, |payload| {
+                    payload.add_layout_dirs(&layout, work_dir)
+                })?;
+                write_appimage(image, &app_dir, &emulator_path, &emu_filename, game, &rom_filename, progress)
+            })();
+            if result.is_err() {
+                let _ = fs::remove_file(&output_path);
+            }
+            return result.map(|_| output_path);
+        }
+
+        // Step 2: stub + [config section] + [archive section, ZIP écrit sur place] + [manifest] + table + footer
+        let pack = if game.thin {
+            Some(write_emulator_pack(&emulator_path, &emu_filename, output_dir, progress)?)
+        } else {
+            None
+        };
+        let output_path = output_dir.join(sanitize_filename(&game.game_name));
+        let result = self.write_executable(&output_path, game, &config_json, progress, |payload| {
+            match &pack {
+                Some(pack) => payload.add_external_emulator(pack, &emu_filename),
+                None => payload.add_emulator(&emulator_path, &emu_filename)?,
+            }
+            payload.add_rom(&game.rom_path, &rom_filename)?;
+            payload.add_layout_dirs(&layout, work_dir)
+        });
+        if result.is_err() {
+            // Ne pas laisser un exécutable tronqué dans le dossier de sortie
+            let _ = fs::remove_file(&output_path);
+            if game.sidecar {
+                remove_sidecar_parts(&output_path);
+            }
+        }
+        result.map(|_| output_path)
+    }
+
+    fn write_executable(
+        &self,
+        output_path: &Path,
+        game: &PortableGame,
+        config_json: &[u8],
+        progress: &dyn ForgeProgress,
+        fill: impl FnOnce(&mut PayloadBuilder<'_, &mut dyn WriteSeek>) -> Result<()>,
+    ) -> Result<()> {
+        let mut container = stub::begin_container(&self.stub_template_path, output_path)?;
+
+        container.add_section(SectionKind::PortableConfig, config_json)
+            .context("Failed to write config")?;
+
+        let manifest = if game.sidecar {
+            let (info, manifest) = write_sidecar(output_path, game.part_size, progress, fill)?;
+            container.add_section(SectionKind::Sidecar, &info.to_json()?)
+                .context("Failed to write sidecar section")?;
+            manifest
+        } else {
+            // The ZIP writer targets the archive section directly (no temp archive)
+            container.start_section(SectionKind::Archive)
+                .context("Failed to start archive section")?;
+            let mut payload = PayloadBuilder::new(&mut container as &mut dyn WriteSeek, progress);
+            fill(&mut payload)?;
+            let manifest = payload.finish()?;
+            container.end_section().context("Failed to close archive section")?;
+            manifest
+        };
+
+        progress.report(100, "Assemblage final...");
+        container.add_section(SectionKind::Manifest, &manifest.to_json()?)
+            .context("Failed to write manifest")?;
+
+        if let Some(key) = &self.signing_key {
+            signing::sign_container(&mut container, key).context("Failed to sign executable")?;
+        }
+        container.finish().context("Failed to finalize container")?;
+
+        // Make executable on Unix
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(output_path, fs::Permissions::from_mode(0o755))
+                .context("Failed to set permissions")?;
+        }
+        Ok(())
+    }
+}
+
+/// Écrit l'émulateur seul dans `<output_dir>/<id>.emupack`, un ZIP que le
+/// stub d'un exécutable "thin" cherche à côté de lui. Retourne son manifeste.
+fn write_emulator_pack(emulator_path: &Path, emu_filename: &str, output_dir: &Path, progress: &dyn ForgeProgress) -> Result<IntegrityManifest> {
+    let partial = output_dir.join(format!(".{}.emupack.partial", uuid::Uuid::new_v4()));
+    let result = (|| {
+        let file = File::create(&partial).context("Failed to create emulator pack")?;
+        let mut payload = PayloadBuilder::new(file, progress);
+        payload.add_emulator(emulator_path, emu_filename)?;
+        let manifest = payload.finish()?;
+        let pack_path = output_dir.join(format!("{}.emupack", manifest.component_id(emu_filename)));
+        fs::rename(&partial, &pack_path).context("Failed to write emulator pack")?;
+        eprintln!("📦 Pack émulateur: {:?}", pack_path);
+        Ok(manifest)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&partial);
+    }
+    result
+}
+
+/// Écrit l'archive dans `<exécutable>.emudata` (ou ses parties `.001`, `.002`...)
+/// à côté de l'exécutable. Retourne la description de la section `Sidecar`.
+fn write_sidecar(
+    output_path: &Path,
+    part_size: Option<u64>,
+    progress: &dyn ForgeProgress,
+    fill: impl FnOnce(&mut PayloadBuilder<'_, &mut dyn WriteSeek>) -> Result<()>,
+) -> Result<(SidecarInfo, IntegrityManifest)> {
+    let dir = output_path.parent().context("Invalid output path")?;
+    let name = sidecar_name(output_path)?;
+    let partial = format!(".{}.{}.partial", uuid::Uuid::new_v4(), sidecar::EXTENSION);
+    let mut writer = PartsWriter::create(dir, &partial, part_size).context("Failed to create data file")?;
+
+    let result = (|| {
+        let mut payload = PayloadBuilder::new(&mut writer as &mut dyn WriteSeek, progress);
+        fill(&mut payload)?;
+        let manifest = payload.finish()?;
+        let parts = writer.finish().context("Failed to write data file")?;
+
+        let (sha256, size) = hash_reader(&mut PartsReader::open(&parts)?).context("Failed to hash data file")?;
+        let info = SidecarInfo { name, size, sha256, part_size: part_size.filter(|&size| size > 0) };
+
+        remove_parts(dir, &info.name);
+        for (part, final_name) in parts.iter().zip(info.part_names(&info.name)) {
+            fs::rename(part, dir.join(final_name)).context("Failed to write data file")?;
+        }
+        eprintln!("📦 Données: {} ({} partie(s))", info.name, parts.len());
+        Ok((info, manifest))
+    })();
+    if result.is_err() {
+        remove_parts(dir, &partial);
+    }
+    result
+}
+
+fn sidecar_name(output_path: &Path) -> Result<String> {
+    let file_name = archive_name(output_path, "output")?;
+    Ok(format!("{}.{}", file_name, sidecar::EXTENSION))
+}
+
+fn remove_sidecar_parts(output_path: &Path) {
+    if let (Some(dir), Ok(name)) = (output_path.parent(), sidecar_name(output_path)) {
+        remove_parts(dir, &name);
+    }
+}
+
+/// Supprime `name` et ses parties `name.NNN` (données d'un forge précédent ou interrompu).
+fn remove_parts(dir: &Path, name: &str) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let is_part = file_name.strip_prefix(name).is_some_and(|rest| {
+            rest.is_empty() || rest.strip_prefix('.').is_some_and(|n| n.len() == 3 && n.bytes().all(|b| b.is_ascii_digit()))
+        });
+        if is_part {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+/// Remplit l'AppImage : AppDir préparée (AppRun et ses données), émulateur,
+/// ROM et pistes, `.desktop` et icône.
+fn write_appimage(
+    mut image: AppImageWriter,
+    app_dir: &Path,
+    emulator_path: &Path,
+    emu_filename: &str,
+    game: &PortableGame,
+    rom_filename: &str,
+    progress: &dyn ForgeProgress,
+) -> Result<()> {
+    image.add_tree(app_dir, "")?;
+
+    progress.report(0, &format!("Mise en boîte: {}...", emu_filename));
+    if emulator_path.is_dir() {
+        eprintln!("📁 Bundling emulator directory: {}", emu_filename);
+        image.add_tree(emulator_path, emu_filename)?;
+    } else {
+        image.add_file(emulator_path, emu_filename, 0o755, true, |_| {})?;
+    }
+
+    // ROM non compressée, comme dans l'archive ZIP
+    let mut files = vec![(game.rom_path.clone(), rom_filename.to_string())];
+    files.extend(disc_dependencies(&game.rom_path)?);
+    for (path, name) in files {
+        eprintln!("💿 Ajout ROM à l'AppImage: {} (source: {:?})", name, path);
+        image.add_file(&path, &name, 0o644, false, |percent| {
+            progress.report(percent, &format!("Ajout de {}: {}%", name, percent));
+        })?;
+    }
+
+    progress.report(100, "Assemblage final...");
+    let desktop_id = sanitize_filename(&game.game_name).replace(' ', "_");
+    image.add_desktop_entry(&desktop_id, &game.game_name, DEFAULT_ICON)?;
+    let path = image.finish()?;
+    eprintln!("📦 AppImage: {:?}", path);
+    Ok(())
+}
+
+fn archive_name(path: &Path, what: &str) -> Result<String> {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .with_context(|| format!("Invalid {} path", what))
+}
+
+/// Fichiers compagnons d'une image disque : pistes `.bin` d'un CUE ou pistes
+/// d'un GDI. Retourne le chemin de chaque fichier existant et son nom dans l'archive.
+pub fn disc_dependencies(rom_path: &Path) -> Result<Vec<(PathBuf, String)>> {
+    let ext = rom_path.extension().map(|e| e.to_string_lossy().to_lowercase());
+    let parent_dir = rom_path.parent().unwrap_or(Path::new("."));
+
+    let names: Vec<String> = match ext.as_deref() {
+        Some("cue") => {
+            let content = fs::read_to_string(rom_path).context("Failed to read CUE file")?;
+            content
+                .lines()
+                .filter(|line| line.trim().starts_with("FILE"))
+                // Extract filename: FILE "filename.bin" BINARY
+                .filter_map(|line| line.split('"').nth(1))
+                .map(str::to_string)
+                .collect()
+        }
+        Some("gdi") => {
+            let content = fs::read_to_string(rom_path).context("Failed to read GDI file")?;
+            content
+                .lines()
+                // GDI format: track_id offset type sector_size filename ...
+                // Skip header line (usually just number of tracks)
+                .filter_map(|line| line.split_whitespace().nth(4))
+                // Filename might be quoted
+                .map(|name| name.trim_matches('"').to_string())
+                .collect()
+        }
+        _ => vec![],
+    };
+
+    Ok(names
+        .into_iter()
+        .map(|name| (parent_dir.join(&name), name))
+        .filter(|(path, _)| path.exists())
+        .collect())
+}
+
+/// Appelle `setup_environment` DANS LE TEMP DIR et retourne l'émulateur à
+/// embarquer, éventuellement patché (ex: RPCS3 avec firmware).
+fn prepare_emulator(plugin: Option<&dyn EmulatorPlugin>, game: &PortableGame, work_dir: &Path) -> Result<PathBuf> {
+    let plugin = match plugin {
+        Some(plugin) => plugin,
+        None => {
+            eprintln!("⚠️ Aucun plugin détecté pour: {:?}", game.emulator_path);
+            return Ok(game.emulator_path.clone());
+        }
+    };
+
+    eprintln!("🔌 Plugin détecté: {}", plugin.id());
+    let bios_p = game.bios_path.as_deref();
+    eprintln!("📀 BIOS path fourni: {:?}", bios_p);
+
+    plugin.setup_environment(work_dir, bios_p).context("Plugin setup error")?;
+    eprintln!("✅ setup_environment terminé");
+
+    match plugin.prepare_portable_binary(&game.emulator_path, bios_p, work_dir)
+        .context("Emulator patching failed")? {
+        Some(patched) => {
+            eprintln!("🔧 Using patched emulator binary");
+            Ok(patched)
+        }
+        None => Ok(game.emulator_path.clone()),
+    }
+}
+
+/// Copie le BIOS dans le dossier prévu par le layout AVANT l'archivage, pour
+/// qu'il soit inclus avec ce dossier (évite les "Duplicate filename").
+fn stage_bios(layout: &BundleLayout, bios_path: Option<&Path>, work_dir: &Path) -> Result<()> {
+    let bios_dest_dir = match &layout.bios_dir {
+        Some(dir) => work_dir.join(dir),
+        None => return Ok(()),
+    };
+    // Le dossier existe même sans BIOS (l'émulateur y cherche ses fichiers)
+    fs::create_dir_all(&bios_dest_dir).context("Failed to create BIOS dir")?;
+
+    // Only copy if it's a file (a firmware folder is handled by the plugin)
+    let bios = match bios_path {
+        Some(bios) if bios.is_file() => bios,
+        _ => return Ok(()),
+    };
+    let bios_filename = bios.file_name().context("Invalid BIOS path")?;
+    fs::copy(bios, bios_dest_dir.join(bios_filename)).context("Failed to copy BIOS to config dir")?;
+    Ok(())
+}
+
+/// Construit l'archive ZIP et son manifeste d'intégrité.
+struct PayloadBuilder<'a, W: Write + Seek> {
+    zip: ZipWriter<W>,
+    manifest: IntegrityManifest,
+    progress: &'a dyn ForgeProgress,
+}
+
+impl<'a, W: Write + Seek> PayloadBuilder<'a, W> {
+    fn new(inner: W, progress: &'a dyn ForgeProgress) -> Self {
+        Self {
+            zip: ZipWriter::new(inner),
+            manifest: IntegrityManifest::default(),
+            progress,
+        }
+    }
+
+    fn deflated() -> SimpleFileOptions {
+        SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated)
+    }
+
+    /// Optimization: Use Stored (no compression) for ROMs to speed up forging significantly.
+    /// Game files are often already compressed (CSO, CHD, GZ) or don't compress well (ISO).
+    fn stored() -> SimpleFileOptions {
+        SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored)
+            .large_file(true)
+    }
+
+    /// Ajoute l'émulateur (fichier ou dossier, ex: squashfs-root Ryujinx).
+    fn add_emulator(&mut self, emulator_path: &Path, emu_filename: &str) -> Result<()> {
+        // Ensure emulator is executable
+        let mut options = Self::deflated();
+        #[cfg(unix)]
+        {
+            options = options.unix_permissions(0o755);
+        }
+
+        if emulator_path.is_dir() {
+            eprintln!("📁 Bundling emulator directory: {}", emu_filename);
+            self.add_directory(emulator_path, emu_filename, options)?;
+        } else {
+            self.add_file(emulator_path, emu_filename, options)?;
+        }
+        // Extrait une seule fois par le stub pour tous les jeux qui le partagent
+        self.manifest.add_component(emu_filename, false);
+        Ok(())
+    }
+
+    /// Référence l'émulateur d'un pack sans l'embarquer : ses entrées restent
+    /// dans le manifeste pour vérifier le pack à l'extraction.
+    fn add_external_emulator(&mut self, pack: &IntegrityManifest, emu_filename: &str) {
+        self.manifest.entries.extend(pack.entries.iter().cloned());
+        self.manifest.add_component(emu_filename, true);
+    }
+
+    /// Ajoute la ROM et ses pistes (CUE/GDI).
+    fn add_rom(&mut self, rom_path: &Path, rom_filename: &str) -> Result<()> {
+        eprintln!("💿 Ajout ROM au ZIP: {} (source: {:?})", rom_filename, rom_path);
+        self.add_file(rom_path, rom_filename, Self::stored())?;
+
+        for (path, name) in disc_dependencies(rom_path)? {
+            self.progress.report(0, &format!("Détection dépendance: {}...", name));
+            self.add_file(&path, &name, Self::stored())?;
+        }
+        Ok(())
+    }
+
+    /// Ajoute les dossiers de config/données préparés par le plugin.
+    fn add_layout_dirs(&mut self, layout: &BundleLayout, work_dir: &Path) -> Result<()> {
+        for name in &layout.include_dirs {
+            let dir = work_dir.join(name);
+            if dir.exists() {
+                eprintln!("✅ {} ajouté au ZIP", name);
+                self.add_directory(&dir, name, Self::deflated())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<IntegrityManifest> {
+        self.zip.finish().context("Failed to finalize ZIP")?;
+        Ok(self.manifest)
+    }
+
+    /// Add a file to ZIP archive with Progress
+    fn add_file(&mut self, file_path: &Path, archive_name: &str, options: SimpleFileOptions) -> Result<()> {
+        self.zip.start_file(archive_name, options).context("Failed to start file in ZIP")?;
+
+        let mut file = File::open(file_path)
+            .with_context(|| format!("Failed to open {}", file_path.display()))?;
+        let total_size = file.metadata().map(|m| m.len()).unwrap_or(0);
+
+        let file_name = file_path.file_name().unwrap_or_default().to_string_lossy();
+        self.progress.report(0, &format!("Mise en boîte: {}...", file_name));
+
+        // Hashing as we go for the integrity manifest
+        let mut entry = HashingWriter::new(&mut self.zip);
+        let progress = self.progress;
+        copy_with_progress(&mut file, &mut entry, total_size, |percent| {
+            progress.report(percent, &format!("Ajout de {}: {}%", file_name, percent));
+        })
+        .with_context(|| format!("Failed to add {} to ZIP", file_path.display()))?;
+
+        let (_, sha256, size) = entry.finalize();
+        self.manifest.add(archive_name, size, sha256);
+        Ok(())
+    }
+
+    /// Recursively add a directory to ZIP archive
+    fn add_directory(&mut self, dir_path: &Path, prefix: &str, options: SimpleFileOptions) -> Result<()> {
+        for entry in fs::read_dir(dir_path).context("Failed to read dir")? {
+            let entry = entry.context("Failed to read entry")?;
+            let path = entry.path();
+            let name = format!("{}/{}", prefix, entry.file_name().to_string_lossy());
+
+            if path.is_dir() {
+                eprintln!("   📁 Dossier ZIP: {}", name);
+                self.add_directory(&path, &name, options)?;
+            } else {
+                eprintln!("   📄 Fichier ZIP: {} ({} bytes)", name, path.metadata().map(|m| m.len()).unwrap_or(0));
+                self.add_file(&path, &name, options)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Copie par blocs de 1 Mo en signalant chaque nouveau pourcentage atteint.
+fn copy_with_progress<R: Read, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    total_size: u64,
+    on_percent: impl Fn(u64),
+) -> std::io::Result<u64> {
+    let mut buffer = vec![0u8; 1024 * 1024];
+    let mut written = 0u64;
+    let mut last_percent = 0;
+
+    loop {
+        let n = reader.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        writer.write_all(&buffer[..n])?;
+        written += n as u64;
+
+        if let Some(percent) = (written * 100).checked_div(total_size) {
+            if percent > last_percent {
+                last_percent = percent;
+                on_percent(percent);
+            }
+        }
+    }
+    Ok(written)
+}
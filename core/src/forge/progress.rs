@@ -0,0 +1,19 @@
+/// Reçoit l'avancement d'un forge : le GUI le relaie en événements Tauri,
+/// la CLI l'affiche sur stderr.
+pub trait ForgeProgress {
+    /// `percentage` concerne l'étape en cours (0-100).
+    fn report(&self, percentage: u64, message: &str);
+}
+
+/// Ignore toute progression.
+pub struct NoProgress;
+
+impl ForgeProgress for NoProgress {
+    fn report(&self, _percentage: u64, _message: &str) {}
+}
+
+impl<F: Fn(u64, &str)> ForgeProgress for F {
+    fn report(&self, percentage: u64, message: &str) {
+        self(percentage, message)
+    }
+}
@@ -0,0 +1,155 @@
+//! Clés de signature du forge.
+//!
+//! Les clés sont stockées dans `<config>/emuforge/keys/<nom>.key` (clé privée
+//! en hexadécimal, permissions 600) avec la clé publique dans `<nom>.pub`.
+//! Pour qu'une autre machine fasse confiance aux exécutables signés, ajoutez
+//! le contenu du `.pub` à son fichier `trusted_keys`
+//! (voir `emuforge_format::signature`).
+
+use anyhow::{Context, Result};
+use emuforge_format::container::{ContainerWriter, SectionKind};
+use emuforge_format::manifest::to_hex;
+use emuforge_format::signature::{self, SignatureBlock, SIGNATURE_ALGORITHM, TRUSTED_KEYS_ENV, TRUSTED_KEYS_FILE};
+use ed25519_dalek::Signer;
+use serde::Serialize;
+use std::fs;
+use std::io::{Read, Seek, Write};
+use std::path::PathBuf;
+
+pub use ed25519_dalek::SigningKey;
+
+/// Variable d'environnement remplaçant le dossier des clés.
+pub const KEYS_DIR_ENV: &str = "EMUFORGE_KEYS_DIR";
+
+/// Variable d'environnement désignant la clé utilisée par défaut pour signer.
+pub const SIGNING_KEY_ENV: &str = "EMUFORGE_SIGNING_KEY";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyInfo {
+    pub name: String,
+    pub key_id: String,
+    pub public_key: String,
+    pub path: PathBuf,
+}
+
+impl KeyInfo {
+    fn new(name: &str, key: &SigningKey, path: PathBuf) -> Self {
+        let public_key = key.verifying_key().to_bytes();
+        Self {
+            name: name.to_string(),
+            key_id: signature::key_id(&public_key),
+            public_key: to_hex(&public_key),
+            path,
+        }
+    }
+}
+
+pub fn keys_dir() -> PathBuf {
+    match std::env::var(KEYS_DIR_ENV) {
+        Ok(dir) => PathBuf::from(dir),
+        Err(_) => dirs::config_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("emuforge")
+            .join("keys"),
+    }
+}
+
+/// Fichier de clés de confiance lu par le stub sur cette machine.
+pub fn trusted_keys_path() -> PathBuf {
+    match std::env::var(TRUSTED_KEYS_ENV) {
+        Ok(path) => PathBuf::from(path),
+        Err(_) => dirs::config_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("emuforge")
+            .join(TRUSTED_KEYS_FILE),
+    }
+}
+
+/// Génère une nouvelle clé `name`. Refuse d'écraser une clé existante.
+pub fn generate_key(name: &str) -> Result<KeyInfo> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        anyhow::bail!("Invalid key name: {:?}", name);
+    }
+    let dir = keys_dir();
+    fs::create_dir_all(&dir).context("Failed to create keys directory")?;
+
+    let key_path = dir.join(format!("{}.key", name));
+    if key_path.exists() {
+        anyhow::bail!("Key '{}' already exists at {:?}", name, key_path);
+    }
+
+    let key = SigningKey::generate(&mut rand_core::OsRng);
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&key_path).context("Failed to create key file")?;
+    writeln!(file, "{}", to_hex(&key.to_bytes()))?;
+
+    let info = KeyInfo::new(name, &key, key_path);
+    fs::write(dir.join(format!("{}.pub", name)), format!("{} {}\n", info.public_key, name))
+        .context("Failed to write public key")?;
+    Ok(info)
+}
+
+/// Liste les clés du dossier des clés, triées par nom.
+pub fn list_keys() -> Result<Vec<KeyInfo>> {
+    let dir = keys_dir();
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    let mut keys = Vec::new();
+    for entry in fs::read_dir(&dir).context("Failed to read keys directory")? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("key") {
+            continue;
+        }
+        let name = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+        match read_key_file(&path) {
+            Ok(key) => keys.push(KeyInfo::new(&name, &key, path)),
+            Err(e) => eprintln!("⚠️ Clé ignorée {:?}: {}", path, e),
+        }
+    }
+    keys.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(keys)
+}
+
+pub fn load_signing_key(name: &str) -> Result<SigningKey> {
+    read_key_file(&keys_dir().join(format!("{}.key", name)))
+        .with_context(|| format!("Failed to load signing key '{}'", name))
+}
+
+/// Clé désignée par `EMUFORGE_SIGNING_KEY`, si la variable est définie.
+pub fn default_signing_key() -> Result<Option<SigningKey>> {
+    match std::env::var(SIGNING_KEY_ENV) {
+        Ok(name) if !name.is_empty() => load_signing_key(&name).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// Signe tout ce qui a été écrit dans le conteneur et ajoute la section
+/// `Signature`. À appeler juste avant `finish()`.
+pub fn sign_container<W: Read + Write + Seek>(container: &mut ContainerWriter<W>, key: &SigningKey) -> Result<()> {
+    let digest = container
+        .reread(|reader, payload_offset, sections| signature::signed_digest(reader, payload_offset, sections))
+        .context("Failed to hash forged executable")?;
+
+    let block = SignatureBlock {
+        algorithm: SIGNATURE_ALGORITHM.to_string(),
+        public_key: to_hex(&key.verifying_key().to_bytes()),
+        signature: to_hex(&key.sign(&digest).to_bytes()),
+    };
+    container.add_section(SectionKind::Signature, &serde_json::to_vec(&block)?)?;
+    Ok(())
+}
+
+fn read_key_file(path: &std::path::Path) -> Result<SigningKey> {
+    let content = fs::read_to_string(path)?;
+    let bytes: [u8; 32] = signature::from_hex(&content)
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| anyhow::anyhow!("Malformed key file {:?}", path))?;
+    Ok(SigningKey::from_bytes(&bytes))
+}
@@ -0,0 +1,75 @@
+//! Localisation et patching du stub précompilé.
+//!
+//! Le stub n'est plus recompilé pour chaque jeu : un binaire unique
+//! (`emuforge-stub`) est livré avec l'application, et la configuration
+//! du jeu est ajoutée à la fin de ce modèle sous forme de conteneur
+//! (voir `emuforge_format::container`).
+
+use anyhow::{Context, Result};
+use emuforge_format::container::ContainerWriter;
+use std::fs::{self, File};
+use std::io::{Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// Variable d'environnement permettant de forcer le chemin du stub.
+pub const STUB_PATH_ENV: &str = "EMUFORGE_STUB_PATH";
+
+/// Nom du binaire stub sur la plateforme courante.
+pub fn stub_binary_name() -> &'static str {
+    if cfg!(windows) {
+        "emuforge-stub.exe"
+    } else {
+        "emuforge-stub"
+    }
+}
+
+/// Cherche le stub précompilé.
+///
+/// Ordre de recherche : `EMUFORGE_STUB_PATH`, les dossiers `hints` fournis
+/// par l'appelant (ex: dossier de ressources Tauri), le dossier de
+/// l'exécutable courant, puis les dossiers `target/` du workspace (dev).
+pub fn locate_stub_template(hints: &[PathBuf]) -> Result<PathBuf> {
+    if let Ok(path) = std::env::var(STUB_PATH_ENV) {
+        let path = PathBuf::from(path);
+        if path.is_file() {
+            return Ok(path);
+        }
+        anyhow::bail!("{} points to a missing file: {:?}", STUB_PATH_ENV, path);
+    }
+
+    let name = stub_binary_name();
+    let mut candidates: Vec<PathBuf> = hints.iter().map(|dir| dir.join(name)).collect();
+
+    if let Some(exe_dir) = std::env::current_exe().ok().and_then(|p| p.parent().map(Path::to_path_buf)) {
+        candidates.push(exe_dir.join(name));
+        candidates.push(exe_dir.join("../lib/emuforge").join(name));
+    }
+
+    // Build de développement : <workspace>/target/{release,debug}
+    let workspace = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+    candidates.push(workspace.join("target/release").join(name));
+    candidates.push(workspace.join("target/debug").join(name));
+
+    candidates
+        .into_iter()
+        .find(|p| p.is_file())
+        .ok_or_else(|| anyhow::anyhow!(
+            "Prebuilt stub '{}' not found. Build it with `cargo build --release -p emuforge-stub` or set {}",
+            name, STUB_PATH_ENV
+        ))
+}
+
+/// Copie le stub vers `output_path` et ouvre un `ContainerWriter` juste après.
+///
+/// Le fichier est ouvert en lecture/écriture pour pouvoir être relu par
+/// `signing::sign_container`.
+pub fn begin_container(stub_template: &Path, output_path: &Path) -> Result<ContainerWriter<File>> {
+    fs::copy(stub_template, output_path).context("Failed to copy stub template to output")?;
+    let mut file = fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(output_path)
+        .context("Failed to open forged executable")?;
+    file.seek(SeekFrom::End(0))?;
+    ContainerWriter::new(file).context("Failed to start container")
+}
@@ -8,5 +8,5 @@ pub mod crypto;
 
 
 pub fn welcome() {
-    println!("Welcome to EmuForge Core!");
+    eprintln!("Welcome to EmuForge Core!");
 }
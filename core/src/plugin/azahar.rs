@@ -1,5 +1,6 @@
 use crate::forge::LaunchConfig;
-use crate::plugin::EmulatorPlugin;
+use crate::detection::Platform;
+use crate::plugin::{BundleLayout, EmulatorPlugin};
 use anyhow::{Result, Context};
 use std::path::{Path, PathBuf};
 
@@ -17,6 +18,7 @@ impl EmulatorPlugin for AzaharPlugin {
     fn id(&self) -> &str { "azahar" }
     fn name(&self) -> &str { "Azahar (3DS)" }
     fn supported_extensions(&self) -> &[&str] { &["3ds", "cia", "cxi", "cci", "3dsx"] }
+    fn supported_platforms(&self) -> &[Platform] { &[Platform::Nintendo3DS] }
 
     fn find_binary(&self) -> Result<PathBuf> {
         if let Some(path) = &self.custom_binary_path {
@@ -49,6 +51,8 @@ impl EmulatorPlugin for AzaharPlugin {
             working_dir: None, 
             args_after_rom: vec![],
             env_vars: vec![],
+            extra_args_after_rom: false,
+            driver_id: self.id().to_string(),
         })
     }
 
@@ -57,6 +61,14 @@ impl EmulatorPlugin for AzaharPlugin {
         name.contains("lime3ds") || name.contains("citra") || name.contains("azahar")
     }
 
+    fn bundle_layout(&self) -> BundleLayout {
+        // Sauvegardes et extdata sur la SD virtuelle
+        BundleLayout {
+            save_dirs: vec!["data/azahar-emu/sdmc".to_string(), "data/azahar-emu/states".to_string()],
+            ..BundleLayout::default()
+        }
+    }
+
     fn portable_env_vars(&self, config_dir: &Path) -> Vec<(String, String)> {
         // Rediriger la config vers le dossier local pour portabilité et injection
         vec![
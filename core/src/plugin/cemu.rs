@@ -1,4 +1,5 @@
 use crate::forge::LaunchConfig;
+use crate::detection::Platform;
 use crate::plugin::EmulatorPlugin;
 use anyhow::{Result, Context};
 use std::path::{Path, PathBuf};
@@ -41,6 +42,7 @@ impl EmulatorPlugin for CemuPlugin {
     fn id(&self) -> &str { "cemu" }
     fn name(&self) -> &str { "Cemu (Wii U)" }
     fn supported_extensions(&self) -> &[&str] { &["wua", "wud", "wux", "rpx", "elf"] } 
+    fn supported_platforms(&self) -> &[Platform] { &[Platform::WiiU] }
 
     fn find_binary(&self) -> Result<PathBuf> {
         if let Some(path) = &self.custom_binary_path {
@@ -68,6 +70,7 @@ impl EmulatorPlugin for CemuPlugin {
             emulator_path: binary,
             rom_path: rom_path.to_path_buf(),
             args,
+            driver_id: self.id().to_string(),
             ..Default::default()
         })
     }
@@ -87,6 +90,11 @@ impl EmulatorPlugin for CemuPlugin {
         vec!["-f".to_string()]
     }
 
+    /// Les options de Cemu suivent `-g <jeu>` : celles passées au lancement aussi
+    fn extra_args_after_rom(&self) -> bool {
+        true
+    }
+
     fn setup_environment(&self, _output_dir: &Path, _bios_path: Option<&Path>) -> Result<()> {
         // Déployer keys.txt automatiquement depuis les assets intégrés
         // Le chemin est relatif à ce fichier source : ../assets/keys.txt
@@ -1,5 +1,6 @@
 use crate::forge::LaunchConfig;
-use crate::plugin::EmulatorPlugin;
+use crate::detection::Platform;
+use crate::plugin::{BundleLayout, EmulatorPlugin};
 use anyhow::{Result, Context};
 use std::path::{Path, PathBuf};
 
@@ -17,6 +18,7 @@ impl EmulatorPlugin for DolphinPlugin {
     fn id(&self) -> &str { "dolphin" }
     fn name(&self) -> &str { "Dolphin (GameCube/Wii)" }
     fn supported_extensions(&self) -> &[&str] { &["iso", "gcm", "wbfs", "ciso", "rvz", "elf", "dol"] }
+    fn supported_platforms(&self) -> &[Platform] { &[Platform::GameCube, Platform::Wii] }
 
     fn find_binary(&self) -> Result<PathBuf> {
         if let Some(path) = &self.custom_binary_path {
@@ -46,6 +48,7 @@ impl EmulatorPlugin for DolphinPlugin {
             rom_path: rom_path.to_path_buf(),
             args,
             env_vars: vec![("QT_QPA_PLATFORM".to_string(), "xcb".to_string())],
+            driver_id: self.id().to_string(),
             ..Default::default()
         })
     }
@@ -70,4 +73,16 @@ impl EmulatorPlugin for DolphinPlugin {
     fn clone_with_path(&self, binary_path: PathBuf) -> Box<dyn EmulatorPlugin> {
         Box::new(DolphinPlugin::new(Some(binary_path)))
     }
+
+    fn bundle_layout(&self) -> BundleLayout {
+        // Cartes mémoire GC, sauvegardes Wii (NAND) et states dans le dossier utilisateur Dolphin
+        BundleLayout {
+            host_save_dirs: vec![
+                "{data_home}/dolphin-emu/GC".to_string(),
+                "{data_home}/dolphin-emu/Wii/title".to_string(),
+                "{data_home}/dolphin-emu/StateSaves".to_string(),
+            ],
+            ..BundleLayout::default()
+        }
+    }
 }
@@ -1,5 +1,6 @@
 use crate::forge::LaunchConfig;
-use crate::plugin::EmulatorPlugin;
+use crate::detection::Platform;
+use crate::plugin::{BundleLayout, EmulatorPlugin};
 use anyhow::{Result, Context};
 use std::path::{Path, PathBuf};
 
@@ -441,6 +442,7 @@ impl EmulatorPlugin for DuckStationPlugin {
     fn id(&self) -> &str { "duckstation" }
     fn name(&self) -> &str { "DuckStation (PS1)" }
     fn supported_extensions(&self) -> &[&str] { &["bin", "cue", "iso", "chd", "m3u", "pbp"] }
+    fn supported_platforms(&self) -> &[Platform] { &[Platform::PS1] }
 
     fn find_binary(&self) -> Result<PathBuf> {
         if let Some(path) = &self.custom_binary_path {
@@ -471,6 +473,8 @@ impl EmulatorPlugin for DuckStationPlugin {
             working_dir: None, 
             args_after_rom: vec![],
             env_vars: vec![],
+            extra_args_after_rom: false,
+            driver_id: self.id().to_string(),
         })
     }
 
@@ -572,6 +576,21 @@ export QT_QPA_PLATFORM=xcb
         Box::new(DuckStationPlugin::new(Some(binary_path)))
     }
 
+    fn bundle_layout(&self) -> BundleLayout {
+        // Faux HOME : DuckStation cherche ~/.local/share/duckstation
+        BundleLayout {
+            include_dirs: vec![".duckstation_home".to_string(), "config".to_string(), "data".to_string()],
+            bios_dir: Some(".duckstation_home/.local/share/duckstation/bios".to_string()),
+            config_root: "./.duckstation_home".to_string(),
+            save_dirs: vec![
+                ".duckstation_home/.local/share/duckstation/memcards".to_string(),
+                ".duckstation_home/.local/share/duckstation/savestates".to_string(),
+            ],
+            save_files: vec![],
+            host_save_dirs: vec![],
+        }
+    }
+
     fn portable_env_vars(&self, config_dir: &Path) -> Vec<(String, String)> {
         // DuckStation ignore XDG, il faut définir HOME
         vec![
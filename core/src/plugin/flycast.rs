@@ -1,4 +1,5 @@
 use crate::forge::LaunchConfig;
+use crate::detection::Platform;
 use crate::plugin::EmulatorPlugin;
 use anyhow::{Result, Context};
 use std::path::{Path, PathBuf};
@@ -17,6 +18,7 @@ impl EmulatorPlugin for FlycastPlugin {
     fn id(&self) -> &str { "flycast" }
     fn name(&self) -> &str { "Flycast (Dreamcast)" }
     fn supported_extensions(&self) -> &[&str] { &["gdi", "cdi", "chd", "cue"] }
+    fn supported_platforms(&self) -> &[Platform] { &[Platform::Dreamcast] }
 
     fn find_binary(&self) -> Result<PathBuf> {
         if let Some(path) = &self.custom_binary_path {
@@ -101,6 +103,8 @@ impl EmulatorPlugin for FlycastPlugin {
             working_dir: None, 
             args_after_rom: vec![],
             env_vars: vec![],
+            extra_args_after_rom: false,
+            driver_id: self.id().to_string(),
         })
     }
 
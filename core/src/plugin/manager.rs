@@ -1,4 +1,5 @@
 use std::path::{Path};
+use crate::detection::Platform;
 use crate::plugin::EmulatorPlugin;
 // use crate::plugin::ppsspp::PpssppPlugin;
 // Future imports
@@ -49,4 +50,9 @@ impl PluginManager {
     pub fn get_plugin_by_id(&self, id: &str) -> Option<&dyn EmulatorPlugin> {
         self.plugins.iter().find(|p| p.id() == id).map(|b| b.as_ref())
     }
+
+    /// Finds the first plugin emulating the given platform.
+    pub fn plugin_for_platform(&self, platform: &Platform) -> Option<&dyn EmulatorPlugin> {
+        self.plugins.iter().find(|p| p.supported_platforms().contains(platform)).map(|b| b.as_ref())
+    }
 }
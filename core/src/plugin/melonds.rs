@@ -1,4 +1,5 @@
 use crate::forge::LaunchConfig;
+use crate::detection::Platform;
 use crate::plugin::EmulatorPlugin;
 use anyhow::{Result, Context};
 use std::path::{Path, PathBuf};
@@ -17,6 +18,7 @@ impl EmulatorPlugin for MelonDSPlugin {
     fn id(&self) -> &str { "melonds" }
     fn name(&self) -> &str { "melonDS (NDS)" }
     fn supported_extensions(&self) -> &[&str] { &["nds", "srl", "dsi"] }
+    fn supported_platforms(&self) -> &[Platform] { &[Platform::NintendoDS] }
 
     fn find_binary(&self) -> Result<PathBuf> {
         if let Some(path) = &self.custom_binary_path {
@@ -47,6 +49,8 @@ impl EmulatorPlugin for MelonDSPlugin {
             working_dir: None, 
             args_after_rom: vec![],
             env_vars: vec![],
+            extra_args_after_rom: false,
+            driver_id: self.id().to_string(),
         })
     }
 
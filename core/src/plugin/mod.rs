@@ -1,6 +1,7 @@
 use std::path::{Path, PathBuf};
 use anyhow::Result;
 use crate::forge::LaunchConfig;
+use crate::detection::Platform;
 use serde::{Deserialize, Serialize}; // Need serde for struct
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -17,6 +18,45 @@ pub struct ValidationResult {
     pub message: String,
     pub fixed: bool,
 }
+/// Organisation d'un plugin dans l'archive portable. Les chemins sont
+/// relatifs au dossier préparé par `setup_environment` (et au dossier
+/// d'extraction côté stub).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleLayout {
+    /// Dossiers embarqués s'ils existent.
+    pub include_dirs: Vec<String>,
+    /// Dossier où copier un BIOS/firmware fourni sous forme de fichier.
+    /// `None` : le plugin s'en charge lui-même (ex: `prepare_portable_binary`).
+    pub bios_dir: Option<String>,
+    /// Racine de config passée à `portable_env_vars` et au stub (`{config_dir}`).
+    pub config_root: String,
+    /// Dossiers de sauvegardes / states, redirigés par le stub vers un
+    /// dossier persistant pour survivre au nettoyage du cache.
+    #[serde(default)]
+    pub save_dirs: Vec<String>,
+    /// Fichiers de sauvegarde isolés (ex: disque dur virtuel).
+    #[serde(default)]
+    pub save_files: Vec<String>,
+    /// Sauvegardes que l'émulateur garde sur l'hôte, hors du bundle.
+    /// Modèles résolus par le stub (`{config_home}`, `{data_home}`, `{home}`).
+    #[serde(default)]
+    pub host_save_dirs: Vec<String>,
+}
+
+impl Default for BundleLayout {
+    /// Layout XDG : `config/` et `data/` à la racine.
+    fn default() -> Self {
+        Self {
+            include_dirs: vec!["config".to_string(), "data".to_string()],
+            bios_dir: None,
+            config_root: ".".to_string(),
+            save_dirs: vec![],
+            save_files: vec![],
+            host_save_dirs: vec![],
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct HostSpecs {
     pub screen_width: u32,
@@ -24,6 +64,33 @@ pub struct HostSpecs {
     pub vulkan_support: bool,
 }
 
+impl HostSpecs {
+    /// Détecte les capacités de la machine hôte (1920x1080 par défaut).
+    pub fn detect(screen_width: Option<u32>, screen_height: Option<u32>) -> Self {
+        Self {
+            screen_width: screen_width.unwrap_or(1920),
+            screen_height: screen_height.unwrap_or(1080),
+            vulkan_support: detect_vulkan(),
+        }
+    }
+}
+
+/// Détecter le support Vulkan (Commande OU Librairie)
+fn detect_vulkan() -> bool {
+    // 1. Essayer la commande standard
+    let cmd_success = std::process::Command::new("vulkaninfo")
+        .arg("--summary")
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+
+    // 2. Si échec, vérifier la présence de la librairie partagée (Linux)
+    cmd_success
+        || Path::new("/usr/lib/libvulkan.so.1").exists()
+        || Path::new("/usr/lib/x86_64-linux-gnu/libvulkan.so.1").exists()
+        || Path::new("/usr/lib/libvulkan.so").exists()
+}
+
 pub trait EmulatorPlugin: Send + Sync {
     /// Unique identifier for the emulator (e.g., "ppsspp").
     fn id(&self) -> &str;
@@ -33,6 +100,11 @@ pub trait EmulatorPlugin: Send + Sync {
     
     /// List of file extensions supported by this emulator (without dot).
     fn supported_extensions(&self) -> &[&str];
+
+    /// Plateformes émulées, utilisées pour choisir un plugin à partir d'une ROM.
+    fn supported_platforms(&self) -> &[Platform] {
+        &[]
+    }
     
     /// Locate the emulator binary on the host system.
     fn find_binary(&self) -> Result<PathBuf>;
@@ -125,12 +197,17 @@ pub trait EmulatorPlugin: Send + Sync {
 
     /// Variables d'environnement spécifiques pour le mode portable.
     /// Le stub appliquera ces variables avant de lancer l'émulateur.
-    /// `config_dir` est le chemin vers le dossier de config extrait.
+    /// `config_dir` vaut `{config_dir}` (modèle résolu par le stub, voir `forge::template`).
     fn portable_env_vars(&self, config_dir: &Path) -> Vec<(String, String)> {
         let _ = config_dir;
         vec![]
     }
 
+    /// Dossiers à embarquer, destination du BIOS et racine de config en mode portable.
+    fn bundle_layout(&self) -> BundleLayout {
+        BundleLayout::default()
+    }
+
     /// Arguments de lancement pour le mode portable.
     /// Retourne (args_before_rom, args_after_rom) pour une flexibilité maximale.
     /// Le stub construira: [emulator] [args_before] [rom] [args_after]
@@ -138,6 +215,12 @@ pub trait EmulatorPlugin: Send + Sync {
         let before = if fullscreen { vec!["--fullscreen".to_string()] } else { vec![] };
         (before, vec![])
     }
+
+    /// Place les arguments passés au lancement (`jeu -- ...`) après la ROM.
+    /// Par défaut ils sont mis en tête : [emulator] [extra] [args_before] [rom] [args_after]
+    fn extra_args_after_rom(&self) -> bool {
+        false
+    }
     
     /// Prépare le binaire de l'émulateur pour mode portable (patch si nécessaire)
     /// Retourne Some(PathBuf) si un binaire patché a été créé, None sinon
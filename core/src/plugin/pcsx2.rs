@@ -1,5 +1,6 @@
-use crate::forge::LaunchConfig;
-use crate::plugin::EmulatorPlugin;
+use crate::forge::{template, LaunchConfig};
+use crate::detection::Platform;
+use crate::plugin::{BundleLayout, EmulatorPlugin};
 use anyhow::{Result, Context};
 use std::path::{Path, PathBuf};
 
@@ -107,6 +108,7 @@ impl EmulatorPlugin for Pcsx2Plugin {
     fn id(&self) -> &str { "pcsx2" }
     fn name(&self) -> &str { "PCSX2 (PS2 Emulator)" }
     fn supported_extensions(&self) -> &[&str] { &["iso", "cso", "bin", "gz", "chd"] }
+    fn supported_platforms(&self) -> &[Platform] { &[Platform::PS2] }
 
     fn find_binary(&self) -> Result<PathBuf> {
         if let Some(path) = &self.custom_binary_path {
@@ -140,8 +142,9 @@ impl EmulatorPlugin for Pcsx2Plugin {
 
         // Generate minimal ini to skip the First Run Wizard
         // PCSX2 Qt with XDG_CONFIG_HOME looks for config in $XDG_CONFIG_HOME/PCSX2/inis/
-        let ini_path = config_dir.join("PCSX2").join("inis").join("PCSX2.ini");
-        std::fs::create_dir_all(ini_path.parent().unwrap())?;
+        let inis_dir = config_dir.join("PCSX2").join("inis");
+        std::fs::create_dir_all(&inis_dir)?;
+        let ini_path = inis_dir.join("PCSX2.ini");
         
         // The critical setting is SetupWizardIncomplete = false
         // We also need to point to the bios folder within our config structure
@@ -165,7 +168,7 @@ impl EmulatorPlugin for Pcsx2Plugin {
         // Use environment variables to control config location
         // PCSX2 AppImage respects XDG standards
         let env_vars = vec![
-            ("XDG_CONFIG_HOME".to_string(), config_dir.to_string_lossy().to_string()),
+            ("XDG_CONFIG_HOME".to_string(), template::escape(&config_dir.to_string_lossy())),
         ];
         
         // We store the bios_dir path in a special field so lib.rs can copy the BIOS file there
@@ -185,6 +188,7 @@ impl EmulatorPlugin for Pcsx2Plugin {
             rom_path: rom_path.to_path_buf(),
             args,
             env_vars,
+            driver_id: self.id().to_string(),
             ..Default::default()
         })
     }
@@ -245,6 +249,18 @@ impl EmulatorPlugin for Pcsx2Plugin {
         Box::new(Pcsx2Plugin::new(Some(binary_path)))
     }
 
+    fn bundle_layout(&self) -> BundleLayout {
+        // Tout vit sous pcsx2_data/PCSX2 (inis, bios, memcards)
+        BundleLayout {
+            include_dirs: vec!["pcsx2_data".to_string(), "config".to_string(), "data".to_string()],
+            bios_dir: Some("pcsx2_data/PCSX2/bios".to_string()),
+            config_root: "./pcsx2_data".to_string(),
+            save_dirs: vec!["pcsx2_data/PCSX2/memcards".to_string(), "pcsx2_data/PCSX2/sstates".to_string()],
+            save_files: vec![],
+            host_save_dirs: vec![],
+        }
+    }
+
     fn portable_env_vars(&self, _config_dir: &Path) -> Vec<(String, String)> {
         // PCSX2 AppImage utilise souvent XDG_CONFIG_HOME
         // Structure attendue: $XDG_CONFIG_HOME/PCSX2/inis/PCSX2.ini
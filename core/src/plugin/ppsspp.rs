@@ -1,5 +1,6 @@
 use crate::forge::LaunchConfig;
-use crate::plugin::EmulatorPlugin;
+use crate::detection::Platform;
+use crate::plugin::{BundleLayout, EmulatorPlugin};
 use anyhow::{Result, Context};
 use std::path::{Path, PathBuf};
 
@@ -27,6 +28,10 @@ impl EmulatorPlugin for PpssppPlugin {
         &["iso", "cso", "pbp", "elf"]
     }
 
+    fn supported_platforms(&self) -> &[Platform] {
+        &[Platform::PSP]
+    }
+
     fn find_binary(&self) -> Result<PathBuf> {
         if let Some(path) = &self.custom_binary_path {
             if path.exists() {
@@ -75,6 +80,8 @@ impl EmulatorPlugin for PpssppPlugin {
             working_dir: None, 
             args_after_rom: vec![],
             env_vars: vec![],
+            extra_args_after_rom: false,
+            driver_id: self.id().to_string(),
         })
     }
 
@@ -91,5 +98,15 @@ impl EmulatorPlugin for PpssppPlugin {
     fn clone_with_path(&self, binary_path: PathBuf) -> Box<dyn EmulatorPlugin> {
         Box::new(PpssppPlugin::new(Some(binary_path)))
     }
+
+    fn bundle_layout(&self) -> BundleLayout {
+        BundleLayout {
+            host_save_dirs: vec![
+                "{config_home}/ppsspp/PSP/SAVEDATA".to_string(),
+                "{config_home}/ppsspp/PSP/PPSSPP_STATE".to_string(),
+            ],
+            ..BundleLayout::default()
+        }
+    }
 }
 
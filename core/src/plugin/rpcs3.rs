@@ -1,49 +1,8 @@
 use crate::forge::LaunchConfig;
+use crate::detection::Platform;
 use crate::plugin::EmulatorPlugin;
 use anyhow::{Result, Context};
 use std::path::{Path, PathBuf};
-use std::fs;
-use std::os::unix::fs::PermissionsExt;
-
-/// Télécharge appimagetool de manière synchrone via curl (évite les problèmes de runtime tokio)
-fn download_appimagetool_sync() -> Result<PathBuf> {
-    let tools_dir = dirs::cache_dir()
-        .unwrap_or_else(|| PathBuf::from("/tmp"))
-        .join("emuforge/tools");
-    
-    fs::create_dir_all(&tools_dir)?;
-    
-    let appimagetool_path = tools_dir.join("appimagetool-x86_64.AppImage");
-    
-    if appimagetool_path.exists() {
-        println!("     ✅ appimagetool already available");
-        return Ok(appimagetool_path);
-    }
-    
-    println!("     ⬇️ Downloading appimagetool...");
-    let url = "https://github.com/AppImage/appimagetool/releases/download/continuous/appimagetool-x86_64.AppImage";
-    
-    // Utiliser curl pour éviter les conflits de runtime tokio
-    let output = std::process::Command::new("curl")
-        .args(["-L", "-o", appimagetool_path.to_str().unwrap(), url])
-        .output()
-        .context("Failed to run curl for appimagetool download")?;
-    
-    if !output.status.success() {
-        return Err(anyhow::anyhow!(
-            "curl failed: {}",
-            String::from_utf8_lossy(&output.stderr)
-        ));
-    }
-    
-    // Rendre exécutable
-    let mut perms = fs::metadata(&appimagetool_path)?.permissions();
-    perms.set_mode(0o755);
-    fs::set_permissions(&appimagetool_path, perms)?;
-    
-    println!("     ✅ appimagetool downloaded");
-    Ok(appimagetool_path)
-}
 
 pub struct Rpcs3Plugin {
     pub custom_binary_path: Option<PathBuf>,
@@ -59,6 +18,7 @@ impl EmulatorPlugin for Rpcs3Plugin {
     fn id(&self) -> &str { "rpcs3" }
     fn name(&self) -> &str { "RPCS3 (PS3)" }
     fn supported_extensions(&self) -> &[&str] { &["iso", "pkg", "bin", "edat", "self", "sprx", "elf"] } // EBOOT.BIN handling might be tricky via launcher without valid folder structure, but .iso is standard for dumps.
+    fn supported_platforms(&self) -> &[Platform] { &[Platform::PS3] }
 
     fn find_binary(&self) -> Result<PathBuf> {
         if let Some(path) = &self.custom_binary_path {
@@ -124,6 +84,8 @@ show_setup_wizard=false
             working_dir: None, 
             args_after_rom: vec![],
             env_vars: vec![],
+            extra_args_after_rom: false,
+            driver_id: self.id().to_string(),
         })
     }
 
@@ -156,25 +118,21 @@ show_setup_wizard=false
         // Vérifier si un firmware PS3 est fourni
         if let Some(fw_path) = bios_firmware_path {
             if fw_path.extension().and_then(|s| s.to_str()) == Some("PUP") {
-                println!("🔧 PS3 Firmware detected, patching RPCS3 AppImage...");
+                eprintln!("🔧 PS3 Firmware detected, patching RPCS3 AppImage...");
                 
                 // 1. Extraire le firmware depuis le PUP
-                println!("📦 Extracting PS3 firmware from PUP...");
+                eprintln!("📦 Extracting PS3 firmware from PUP...");
                 let dev_flash = crate::firmware::ps3::extract_firmware(fw_path, work_dir)?;
                 
-                // 2. Télécharger appimagetool si nécessaire (sync version)
-                println!("🔨 Preparing appimagetool...");
-                let appimagetool = download_appimagetool_sync()?;
-                
-                // 3. Patcher AppImage (injection dev_flash + configs)
-                println!("⚙️ Patching RPCS3 AppImage...");
-                let patcher = crate::appimage::patcher::AppImagePatcher::new(appimagetool);
+                // 2. Patcher AppImage (injection dev_flash + configs)
+                eprintln!("⚙️ Patching RPCS3 AppImage...");
+                let patcher = crate::appimage::patcher::AppImagePatcher::new();
                 let patched = patcher.patch_rpcs3(original_binary, &dev_flash, work_dir)?;
                 
-                // 4. Cleanup temp extract dir
+                // 3. Cleanup temp extract dir
                 let _ = std::fs::remove_dir_all(dev_flash.parent().unwrap_or(&dev_flash));
                 
-                println!("✅ RPCS3 AppImage patched successfully!");
+                eprintln!("✅ RPCS3 AppImage patched successfully!");
                 return Ok(Some(patched));
             }
         }
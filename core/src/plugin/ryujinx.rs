@@ -1,5 +1,6 @@
 use crate::forge::LaunchConfig;
-use crate::plugin::{EmulatorPlugin, RequirementInfo, ValidationResult};
+use crate::detection::Platform;
+use crate::plugin::{BundleLayout, EmulatorPlugin, RequirementInfo, ValidationResult};
 use anyhow::{Result, Context};
 use std::path::{Path, PathBuf};
 use std::fs;
@@ -200,10 +201,10 @@ impl RyujinxPlugin {
                             let name = controller.name();
                             
                             // CRITICAL: Ryujinx AUTHENTIC GUID generation (from SDL3GamepadDriver.cs)
-                            let joystick = match sdl_context.joystick().unwrap().open(i) {
-                                Ok(j) => j,
-                                Err(_) => {
-                                    println!("⚠️  Could not open joystick {} for GUID", i);
+                            let joystick = match sdl_context.joystick().ok().and_then(|j| j.open(i).ok()) {
+                                Some(j) => j,
+                                None => {
+                                    eprintln!("⚠️  Could not open joystick {} for GUID", i);
                                     continue;
                                 }
                             };
@@ -230,7 +231,7 @@ impl RyujinxPlugin {
                             // Remove CRC, add "0000" (line 86)
                             let final_guid = format!("0000{}", &rearranged[4..]);
                             let ryujinx_id = format!("{}-{}", i, final_guid);
-                            println!("🎮 Controller {}: {} → {}", i, name, ryujinx_id);
+                            eprintln!("🎮 Controller {}: {} → {}", i, name, ryujinx_id);
                             
                             let is_nintendo = name.to_lowercase().contains("nintendo");
                             let player_enum = format!("Player{}", player_idx_counter);
@@ -338,7 +339,7 @@ impl RyujinxPlugin {
         }.ok_or_else(|| anyhow::anyhow!("Could not determine Ryujinx config directory"))?;
 
         let config_path = config_dir.join("Config.json");
-        println!("🔧 Updating Ryujinx config at: {:?}", config_path);
+        eprintln!("🔧 Updating Ryujinx config at: {:?}", config_path);
         
         if config_path.exists() {
             let content = fs::read_to_string(&config_path)?;
@@ -362,14 +363,14 @@ impl RyujinxPlugin {
                 if let Some(first_config) = input_configs.get(0) {
                     let id = first_config.get("id").and_then(|v| v.as_str()).unwrap_or("N/A");
                     let name = first_config.get("name").and_then(|v| v.as_str()).unwrap_or("N/A");
-                    println!("✅ Saved Controller Config → ID: {} | Name: {}", id, name);
-                    println!("📁 Config file: {:?}", config_path);
+                    eprintln!("✅ Saved Controller Config → ID: {} | Name: {}", id, name);
+                    eprintln!("📁 Config file: {:?}", config_path);
                 }
             }
             
-            println!("✅ Updated Ryujinx InputConfig with detected controllers.");
+            eprintln!("✅ Updated Ryujinx InputConfig with detected controllers.");
         } else {
-            println!("⚠️ Ryujinx Config.json not found at {:?}, skipping input config update.", config_path);
+            eprintln!("⚠️ Ryujinx Config.json not found at {:?}, skipping input config update.", config_path);
         }
 
         Ok(())
@@ -381,6 +382,7 @@ impl EmulatorPlugin for RyujinxPlugin {
     fn id(&self) -> &str { "ryujinx" }
     fn name(&self) -> &str { "Ryujinx (Switch)" }
     fn supported_extensions(&self) -> &[&str] { &["nsp", "xci", "nca", "nro"] }
+    fn supported_platforms(&self) -> &[Platform] { &[Platform::Switch] }
 
     fn find_binary(&self) -> Result<PathBuf> {
         if let Some(path) = &self.custom_binary_path {
@@ -399,14 +401,16 @@ impl EmulatorPlugin for RyujinxPlugin {
         // CRITICAL DEBUGGING: Log to file + Panic on error
         use std::io::Write;
         let log_path = "/tmp/emuforge_controller_debug.log";
-        let mut log_file = fs::OpenOptions::new().create(true).append(true).open(log_path).unwrap_or_else(|_| fs::File::create("/tmp/emuforge_debug_fallback.txt").unwrap());
+        let mut log_file = fs::OpenOptions::new().create(true).append(true).open(log_path)
+            .or_else(|_| fs::File::create("/tmp/emuforge_debug_fallback.txt"))
+            .context("Failed to open controller debug log")?;
         
         writeln!(log_file, "\n[TIME] 🚀 Launching game via EmuForge...").ok();
         
         match Self::update_ryujinx_input_config() {
             Ok(_) => {
                 writeln!(log_file, "✅ Controller Config Updated SUCCESSFULLY").ok();
-                println!("✅ Controller Config Updated");
+                eprintln!("✅ Controller Config Updated");
             },
             Err(e) => {
                 let err_msg = format!("❌ CRITICAL CONTROLLER ERROR: {:?}", e);
@@ -427,6 +431,8 @@ impl EmulatorPlugin for RyujinxPlugin {
             working_dir: None, 
             args_after_rom: vec![],
             env_vars: vec![],
+            extra_args_after_rom: false,
+            driver_id: self.id().to_string(),
         })
     }
 
@@ -439,6 +445,14 @@ impl EmulatorPlugin for RyujinxPlugin {
         Box::new(RyujinxPlugin::new(Some(binary_path)))
     }
 
+    fn bundle_layout(&self) -> BundleLayout {
+        // Ryujinx garde ses sauvegardes dans ~/.config/Ryujinx même en portable
+        BundleLayout {
+            host_save_dirs: vec!["{config_home}/Ryujinx/bis/user/save".to_string()],
+            ..BundleLayout::default()
+        }
+    }
+
     fn get_requirements(&self) -> RequirementInfo {
         RequirementInfo {
             needs_bios: true,
@@ -583,7 +597,7 @@ impl EmulatorPlugin for RyujinxPlugin {
             _ => return Ok(None),
         };
 
-        println!("🔧 Patching Ryujinx AppImage for auto firmware install...");
+        eprintln!("🔧 Patching Ryujinx AppImage for auto firmware install...");
 
         // 1. Extract AppImage
         let extract_dir = work_dir.join("ryujinx_appimage");
@@ -619,11 +633,11 @@ impl EmulatorPlugin for RyujinxPlugin {
         // Copy keys to bundle
         if let Some(pk) = prod_keys {
             fs::copy(&pk, firmware_bundle.join("prod.keys"))?;
-            println!("  📁 Bundled prod.keys");
+            eprintln!("  📁 Bundled prod.keys");
         }
         if let Some(tk) = title_keys {
             fs::copy(&tk, firmware_bundle.join("title.keys"))?;
-            println!("  📁 Bundled title.keys");
+            eprintln!("  📁 Bundled title.keys");
         }
 
         // Copy NCAs to bundle
@@ -637,7 +651,7 @@ impl EmulatorPlugin for RyujinxPlugin {
                 let _ = fs::copy(nca, nca_dir.join("00"));
             }
         }
-        println!("  📁 Bundled {} firmware NCAs", nca_count);
+        eprintln!("  📁 Bundled {} firmware NCAs", nca_count);
 
         // Cleanup scan temp
         let _ = fs::remove_dir_all(&temp_scan);
@@ -697,7 +711,7 @@ exec $COMMAND "$SCRIPT_DIR/$RYUJINX_BIN" "$@"
 "#;
 
         fs::write(&launch_script_path, patched_script)?;
-        println!("  ✅ Patched Ryujinx.sh with auto-firmware script");
+        eprintln!("  ✅ Patched Ryujinx.sh with auto-firmware script");
 
         // 5. Return the squashfs-root directory (will be bundled as a directory)
         // Make AppRun and scripts executable
@@ -728,7 +742,7 @@ exec $COMMAND "$SCRIPT_DIR/$RYUJINX_BIN" "$@"
         // Update input configuration (Auto-detect controllers)
         Self::update_ryujinx_input_config().ok();
 
-        println!("🎯 Ryujinx AppImage patched successfully!");
+        eprintln!("🎯 Ryujinx AppImage patched successfully!");
         
         // Return the directory, not the AppRun file
         Ok(Some(squashfs_dir))
@@ -1,5 +1,6 @@
 use crate::forge::LaunchConfig;
-use crate::plugin::{EmulatorPlugin, RequirementInfo, ValidationResult, HostSpecs};
+use crate::detection::Platform;
+use crate::plugin::{BundleLayout, EmulatorPlugin, RequirementInfo, ValidationResult, HostSpecs};
 use crate::downloader::EmulatorDownloader;
 use anyhow::{Result, Context};
 use std::path::{Path, PathBuf};
@@ -111,7 +112,7 @@ hide_cursor = true
         let mut file = fs::File::create(&toml_path)?;
         file.write_all(toml_content.as_bytes())?;
         
-        println!("✅ xemu.toml généré: {:?}", toml_path);
+        eprintln!("✅ xemu.toml généré: {:?}", toml_path);
         Ok(toml_path)
     }
 
@@ -139,7 +140,7 @@ hide_cursor = true
                                         // xemu utilise un format GUID spécifique
                                         let guid_str = format!("{:032x}", guid as u128);
                                         bindings.push_str(&format!("    {{ gamepad_id = '{}'}}\n", guid_str));
-                                        println!("🎮 Manette {} détectée: {}", i + 1, controller.name());
+                                        eprintln!("🎮 Manette {} détectée: {}", i + 1, controller.name());
                                     }
                                 }
                             }
@@ -218,7 +219,7 @@ hide_cursor = true
                 if name_lower.contains("mcpx") && name_lower.ends_with(".bin") && mcpx_file.is_none() {
                     if let Ok(meta) = fs::metadata(&path) {
                         if meta.len() <= 1024 {
-                            println!("🔍 MCPX trouvé: {:?}", path);
+                            eprintln!("🔍 MCPX trouvé: {:?}", path);
                             *mcpx_file = Some(path.clone());
                             continue;
                         }
@@ -230,7 +231,7 @@ hide_cursor = true
                     && name_lower.ends_with(".bin") && bios_file.is_none() {
                     if let Ok(meta) = fs::metadata(&path) {
                         if meta.len() >= 256 * 1024 && meta.len() <= 2 * 1024 * 1024 {
-                            println!("🔍 BIOS trouvé: {:?}", path);
+                            eprintln!("🔍 BIOS trouvé: {:?}", path);
                             *bios_file = Some(path.clone());
                             continue;
                         }
@@ -241,14 +242,14 @@ hide_cursor = true
                 if (name_lower.ends_with(".qcow2") || 
                     (name_lower.contains("hdd") && name_lower.ends_with(".img"))) 
                     && hdd_file.is_none() {
-                    println!("🔍 HDD trouvé: {:?}", path);
+                    eprintln!("🔍 HDD trouvé: {:?}", path);
                     *hdd_file = Some(path.clone());
                     continue;
                 }
                 
                 // Archives ZIP
                 if name_lower.ends_with(".zip") {
-                    println!("📦 Extraction ZIP: {:?}", path);
+                    eprintln!("📦 Extraction ZIP: {:?}", path);
                     if let Ok(extracted) = Self::extract_zip_to_temp(&path, temp_extract_dir) {
                         Self::scan_directory_recursive(&extracted, temp_extract_dir, mcpx_file, bios_file, hdd_file);
                     }
@@ -330,7 +331,7 @@ impl XemuPlugin {
             match self.download_extract_xiso() {
                 Ok(path) => Some(path),
                 Err(e) => {
-                    println!("Error downloading extract-xiso: {}", e);
+                    eprintln!("Error downloading extract-xiso: {}", e);
                     if let Some(cb) = progress { cb(format!("❌ Download failed: {}", e)); }
                     None
                 }
@@ -378,14 +379,14 @@ impl XemuPlugin {
 
     fn run_conversion(&self, tool: &Path, input: &Path, output: &Path) -> Result<()> {
         // extract-xiso -r modifies in place. We MUST copy first.
-        println!("   Copying to cache...");
+        eprintln!("   Copying to cache...");
         fs::copy(input, output)?;
         
         // Get the directory containing the output file
         let output_dir = output.parent().context("Invalid output path")?;
         let output_filename = output.file_name().context("Invalid output filename")?;
         
-        println!("   Running extract-xiso -r...");
+        eprintln!("   Running extract-xiso -r...");
         let status = std::process::Command::new(tool)
             .arg("-r")
             .arg(output_filename)  // Just the filename, not full path
@@ -397,9 +398,9 @@ impl XemuPlugin {
             let old_file = output.with_extension("iso.old");
             if old_file.exists() {
                 let _ = fs::remove_file(&old_file);
-                println!("   Cleaned up backup file");
+                eprintln!("   Cleaned up backup file");
             }
-            println!("✅ Conversion successful!");
+            eprintln!("✅ Conversion successful!");
             Ok(())
         } else {
             // Nettoyage si échec
@@ -429,6 +430,7 @@ impl EmulatorPlugin for XemuPlugin {
     fn id(&self) -> &str { "xemu" }
     fn name(&self) -> &str { "xemu (Xbox)" }
     fn supported_extensions(&self) -> &[&str] { &["iso", "xiso"] }
+    fn supported_platforms(&self) -> &[Platform] { &[Platform::Xbox] }
 
     fn find_binary(&self) -> Result<PathBuf> {
         if let Some(path) = &self.custom_binary_path {
@@ -496,6 +498,8 @@ impl EmulatorPlugin for XemuPlugin {
             working_dir: None, 
             args_after_rom,
             env_vars: vec![],
+            extra_args_after_rom: false,
+            driver_id: self.id().to_string(),
         })
     }
 
@@ -533,17 +537,17 @@ impl EmulatorPlugin for XemuPlugin {
 
                 if let Some(mcpx) = found_mcpx {
                     fs::copy(&mcpx, xemu_data.join("mcpx_1.0.bin"))?;
-                    println!("📋 MCPX copié vers xemu_data");
+                    eprintln!("📋 MCPX copié vers xemu_data");
                 }
 
                 if let Some(bios) = found_bios {
                     fs::copy(&bios, xemu_data.join("bios.bin"))?;
-                    println!("📋 BIOS copié vers xemu_data");
+                    eprintln!("📋 BIOS copié vers xemu_data");
                 }
 
                 if let Some(hdd) = found_hdd {
                     fs::copy(&hdd, xemu_data.join("xbox_hdd.qcow2"))?;
-                    println!("📋 HDD copié vers xemu_data");
+                    eprintln!("📋 HDD copié vers xemu_data");
                 }
 
                 let _ = fs::remove_dir_all(&temp_dir);
@@ -556,6 +560,19 @@ impl EmulatorPlugin for XemuPlugin {
         Ok(())
     }
 
+    /// xemu_data/xemu/xemu préparé par setup_environment (BIOS, MCPX, HDD, xemu.toml)
+    fn bundle_layout(&self) -> BundleLayout {
+        BundleLayout {
+            include_dirs: vec!["xemu_data".to_string()],
+            bios_dir: None,
+            config_root: "./xemu_data".to_string(),
+            save_dirs: vec![],
+            // Les sauvegardes Xbox vivent dans le disque dur virtuel
+            save_files: vec!["xemu_data/xemu/xemu/xbox_hdd.qcow2".to_string()],
+            host_save_dirs: vec![],
+        }
+    }
+
     /// Variables d'environnement pour mode portable
     fn portable_env_vars(&self, config_dir: &Path) -> Vec<(String, String)> {
         vec![
@@ -653,7 +670,7 @@ impl EmulatorPlugin for XemuPlugin {
 
         let mcpx_installed = if let Some(ref mcpx) = found_mcpx {
             fs::copy(mcpx, &mcpx_path)?;
-            println!("✅ MCPX installé: {:?}", mcpx_path);
+            eprintln!("✅ MCPX installé: {:?}", mcpx_path);
             true
         } else {
             mcpx_exists
@@ -661,7 +678,7 @@ impl EmulatorPlugin for XemuPlugin {
 
         let bios_installed = if let Some(ref bios) = found_bios {
             fs::copy(bios, &bios_path)?;
-            println!("✅ BIOS installé: {:?}", bios_path);
+            eprintln!("✅ BIOS installé: {:?}", bios_path);
             true
         } else {
             bios_exists
@@ -669,7 +686,7 @@ impl EmulatorPlugin for XemuPlugin {
 
         let hdd_installed = if let Some(ref hdd) = found_hdd {
             fs::copy(hdd, &hdd_path)?;
-            println!("✅ HDD installé: {:?}", hdd_path);
+            eprintln!("✅ HDD installé: {:?}", hdd_path);
             true
         } else {
             hdd_exists
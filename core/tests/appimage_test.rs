@@ -0,0 +1,263 @@
+use emuforge_core::appimage::runtime::{self, RUNTIME_ENV};
+use emuforge_core::appimage::writer::DEFAULT_ICON;
+use emuforge_core::appimage::AppImageWriter;
+use emuforge_core::forge::{PortableForge, PortableGame};
+use flate2::read::ZlibDecoder;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::sync::Mutex;
+use tempfile::tempdir;
+
+/// `EMUFORGE_APPIMAGE_RUNTIME` est partagé par les tests du fichier.
+static ENV: Mutex<()> = Mutex::new(());
+
+/// En-tête ELF64 seul : la table des sections (une entrée) se termine à l'octet 128.
+fn fake_runtime() -> Vec<u8> {
+    let mut elf = vec![0u8; 128];
+    elf[..6].copy_from_slice(b"\x7fELF\x02\x01");
+    elf[0x28] = 64; // e_shoff
+    elf[0x3A] = 64; // e_shentsize
+    elf[0x3C] = 1; // e_shnum
+    elf
+}
+
+fn u32_at(image: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(image[offset..offset + 4].try_into().unwrap())
+}
+
+fn u16_at(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes(data[offset..offset + 2].try_into().unwrap())
+}
+
+fn u64_at(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+fn inflate(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    ZlibDecoder::new(data).read_to_end(&mut out).unwrap();
+    out
+}
+
+/// Lecteur squashfs minimal : relit l'image comme le ferait le noyau
+/// (superbloc, tables d'inodes et de dossiers, blocs de données).
+struct Squashfs<'a> {
+    image: &'a [u8],
+    inodes: (Vec<u8>, HashMap<u32, usize>),
+    dirs: (Vec<u8>, HashMap<u32, usize>),
+}
+
+impl<'a> Squashfs<'a> {
+    fn new(image: &'a [u8]) -> Self {
+        let table = |start: u64, end: u64| {
+            let (mut data, mut blocks) = (Vec::new(), HashMap::new());
+            let mut position = start as usize;
+            while position < end as usize {
+                blocks.insert((position - start as usize) as u32, data.len());
+                let header = u16_at(image, position);
+                let block = &image[position + 2..position + 2 + usize::from(header & 0x7FFF)];
+                data.extend(if header & 0x8000 != 0 { block.to_vec() } else { inflate(block) });
+                position += 2 + block.len();
+            }
+            (data, blocks)
+        };
+        Squashfs {
+            image,
+            inodes: table(u64_at(image, 64), u64_at(image, 72)),
+            dirs: table(u64_at(image, 72), u64_at(image, 80)),
+        }
+    }
+
+    fn inode(&self, reference: u64) -> &[u8] {
+        let (data, blocks) = &self.inodes;
+        &data[blocks[&((reference >> 16) as u32)] + (reference & 0xFFFF) as usize..]
+    }
+
+    /// Entrées d'un dossier : nom -> référence d'inode.
+    fn list(&self, reference: u64) -> HashMap<String, u64> {
+        let inode = self.inode(reference);
+        let (block, size, offset) = match u16_at(inode, 0) {
+            1 => (u32_at(inode, 16), usize::from(u16_at(inode, 24)), u16_at(inode, 26)),
+            8 => (u32_at(inode, 24), u32_at(inode, 20) as usize, u16_at(inode, 34)),
+            kind => panic!("not a directory: {}", kind),
+        };
+        let (data, blocks) = &self.dirs;
+        let start = blocks[&block] + usize::from(offset);
+        let listing = &data[start..start + size - 3];
+
+        let mut entries = HashMap::new();
+        let mut position = 0;
+        while position < listing.len() {
+            let count = u32_at(listing, position) + 1;
+            let inode_block = u32_at(listing, position + 4);
+            position += 12;
+            for _ in 0..count {
+                let name_len = usize::from(u16_at(listing, position + 6)) + 1;
+                let name = String::from_utf8(listing[position + 8..position + 8 + name_len].to_vec()).unwrap();
+                let inode_offset = u16_at(listing, position);
+                entries.insert(name, (u64::from(inode_block) << 16) | u64::from(inode_offset));
+                position += 8 + name_len;
+            }
+        }
+        entries
+    }
+
+    fn read(&self, path: &str) -> Vec<u8> {
+        let mut reference = u64_at(self.image, 32);
+        for name in path.split('/') {
+            reference = self.list(reference)[name];
+        }
+        let inode = self.inode(reference);
+        let (start, size, sizes) = match u16_at(inode, 0) {
+            2 => (u64::from(u32_at(inode, 16)), u64::from(u32_at(inode, 28)), &inode[32..]),
+            9 => (u64_at(inode, 16), u64_at(inode, 24), &inode[56..]),
+            kind => panic!("not a file: {}", kind),
+        };
+
+        let mut content = Vec::new();
+        let mut position = start as usize;
+        for index in 0..size.div_ceil(128 * 1024) as usize {
+            let word = u32_at(sizes, index * 4);
+            let len = (word & !(1 << 24)) as usize;
+            let block = &self.image[position..position + len];
+            content.extend(if word & (1 << 24) != 0 { block.to_vec() } else { inflate(block) });
+            position += len;
+        }
+        assert_eq!(content.len() as u64, size);
+        content
+    }
+}
+
+#[test]
+fn test_appimage_writer_appends_squashfs_to_runtime() {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path();
+    let tree = root.join("AppDir");
+    fs::create_dir_all(tree.join("usr/bin")).unwrap();
+    fs::write(tree.join("usr/bin/emu"), vec![1u8; 300_000]).unwrap();
+    fs::write(tree.join("AppRun"), b"#!/bin/sh\n").unwrap();
+
+    let output = root.join("Game.AppImage");
+    let mut image = AppImageWriter::create(&output, &fake_runtime()).unwrap();
+    image.add_tree(&tree, "").unwrap();
+    image.add_file(&tree.join("AppRun"), "game.iso", 0o644, false, |_| {}).unwrap();
+    assert!(image.add_file(&tree.join("AppRun"), "game.iso", 0o644, false, |_| {}).is_err());
+    image.add_desktop_entry("Game", "Game", DEFAULT_ICON).unwrap();
+    image.finish().unwrap();
+
+    // Le runtime est retrouvé d'après son en-tête ELF, l'image squashfs le suit
+    let runtime = runtime::from_appimage(&output).unwrap();
+    assert_eq!(runtime, fake_runtime());
+    let data = fs::read(&output).unwrap();
+    let squashfs = &data[runtime.len()..];
+    assert_eq!(&squashfs[..4], b"hsqs");
+    assert_eq!(squashfs.len() % 4096, 0);
+    // racine, AppRun, usr, usr/bin, emu, game.iso, .desktop, icône, .DirIcon
+    assert_eq!(u32_at(squashfs, 4), 9);
+    let bytes_used = u64::from_le_bytes(squashfs[40..48].try_into().unwrap());
+    assert!(bytes_used <= squashfs.len() as u64);
+}
+
+#[test]
+fn test_squashfs_files_read_back() {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path();
+    let tree = root.join("AppDir");
+    fs::create_dir_all(tree.join("usr/bin")).unwrap();
+    // Trois blocs compressés (le dernier court) et une ROM stockée telle quelle
+    let emu: Vec<u8> = (0..300_000u32).map(|i| (i % 251) as u8).collect();
+    let rom: Vec<u8> = (0..200_000u32).map(|i| (i.wrapping_mul(2_654_435_761) >> 24) as u8).collect();
+    fs::write(tree.join("usr/bin/emu"), &emu).unwrap();
+    fs::write(root.join("game.iso"), &rom).unwrap();
+    for i in 0..300 {
+        fs::write(tree.join(format!("usr/file{:03}.txt", i)), format!("file {}", i)).unwrap();
+    }
+
+    let output = root.join("Game.AppImage");
+    let mut image = AppImageWriter::create(&output, &fake_runtime()).unwrap();
+    image.add_tree(&tree, "").unwrap();
+    image.add_file(&root.join("game.iso"), "game.iso", 0o644, false, |_| {}).unwrap();
+    image.finish().unwrap();
+
+    let data = fs::read(&output).unwrap();
+    let squashfs = Squashfs::new(&data[fake_runtime().len()..]);
+    assert_eq!(squashfs.read("usr/bin/emu"), emu);
+    assert_eq!(squashfs.read("game.iso"), rom);
+    // Dossier de plus de 256 entrées : plusieurs en-têtes de liste
+    assert_eq!(squashfs.list(u64_at(squashfs.image, 32)).len(), 2);
+    assert_eq!(squashfs.read("usr/file000.txt"), b"file 0");
+    assert_eq!(squashfs.read("usr/file299.txt"), b"file 299");
+}
+
+#[test]
+fn test_default_icon_is_the_application_icon() {
+    // PNG carré d'au moins 128 px, comme l'attendent les intégrateurs
+    assert_eq!(&DEFAULT_ICON[..8], b"\x89PNG\r\n\x1a\n");
+    assert_eq!(&DEFAULT_ICON[12..16], b"IHDR");
+    let width = u32::from_be_bytes(DEFAULT_ICON[16..20].try_into().unwrap());
+    let height = u32::from_be_bytes(DEFAULT_ICON[20..24].try_into().unwrap());
+    assert_eq!(width, height);
+    assert!(width >= 128);
+    assert_eq!(DEFAULT_ICON, &fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/../ui/src-tauri/icons/icon.png")).unwrap()[..]);
+}
+
+#[test]
+fn test_runtime_is_found_next_to_the_stub() {
+    let _env = ENV.lock().unwrap_or_else(|e| e.into_inner());
+    std::env::remove_var(RUNTIME_ENV);
+    let temp_dir = tempdir().unwrap();
+    let stub_dir = temp_dir.path().join("stub");
+    fs::create_dir_all(&stub_dir).unwrap();
+    let shipped = stub_dir.join(runtime::runtime_file_name());
+    fs::write(&shipped, fake_runtime()).unwrap();
+
+    assert_eq!(runtime::locate(std::slice::from_ref(&stub_dir)).unwrap(), fake_runtime());
+
+    // Un fichier qui n'est pas un ELF est refusé plutôt que placé devant l'image
+    fs::write(&shipped, b"not a runtime").unwrap();
+    assert!(runtime::locate(std::slice::from_ref(&stub_dir)).is_err());
+
+    // La variable d'environnement reste prioritaire
+    let override_path = temp_dir.path().join("override");
+    let mut other = fake_runtime();
+    other[100] = 1;
+    fs::write(&override_path, &other).unwrap();
+    std::env::set_var(RUNTIME_ENV, &override_path);
+    let located = runtime::locate(std::slice::from_ref(&stub_dir));
+    std::env::remove_var(RUNTIME_ENV);
+    assert_eq!(located.unwrap(), other);
+}
+
+#[test]
+fn test_portable_forge_builds_appimage() {
+    let _env = ENV.lock().unwrap_or_else(|e| e.into_inner());
+    std::env::remove_var(RUNTIME_ENV);
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path();
+    // Runtime livré à côté du stub
+    fs::write(root.join(runtime::runtime_file_name()), fake_runtime()).unwrap();
+
+    let template = root.join("emuforge-stub");
+    fs::write(&template, b"STUB-TEMPLATE").unwrap();
+    let emulator = root.join("my-emulator");
+    fs::write(&emulator, b"#!/bin/sh\n").unwrap();
+    let rom = root.join("game.iso");
+    fs::write(&rom, vec![7u8; 10_000]).unwrap();
+
+    let game = PortableGame {
+        game_name: "My Game".to_string(),
+        emulator_path: emulator,
+        rom_path: rom,
+        appimage: true,
+        ..Default::default()
+    };
+    let out = root.join("out");
+    let forged = PortableForge::new(template, out.clone()).forge(&game, &|_: u64, _: &str| {}).unwrap();
+    assert_eq!(forged, out.canonicalize().unwrap().join("My Game.AppImage"));
+
+    // Seule l'AppImage est écrite dans le dossier de sortie
+    assert_eq!(fs::read_dir(&out).unwrap().count(), 1);
+    let data = fs::read(&forged).unwrap();
+    assert_eq!(&data[128..132], b"hsqs");
+}
@@ -0,0 +1,59 @@
+use emuforge_core::forge::batch::BatchStatus;
+use emuforge_core::forge::container::{Container, SectionKind};
+use emuforge_core::forge::{collect_roms, forge_batch, BatchOptions, NoProgress};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use tempfile::tempdir;
+
+#[test]
+fn test_batch_forges_good_roms_and_reports_bad_ones() {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path();
+    let roms_dir = root.join("roms");
+    fs::create_dir_all(&roms_dir).unwrap();
+
+    // 1. Stub, emulator, two DS games, an unidentifiable image, a CUE + track and noise
+    fs::write(root.join("emuforge-stub"), b"STUB-TEMPLATE").unwrap();
+    let emulator = root.join("melonDS");
+    fs::write(&emulator, b"#!/bin/sh\n").unwrap();
+    fs::write(roms_dir.join("Alpha.nds"), vec![1u8; 512]).unwrap();
+    fs::write(roms_dir.join("Beta.nds"), vec![2u8; 512]).unwrap();
+    fs::write(roms_dir.join("broken.iso"), vec![0u8; 64]).unwrap();
+    fs::write(roms_dir.join("disc.cue"), "FILE \"disc.bin\" BINARY\n").unwrap();
+    fs::write(roms_dir.join("disc.bin"), vec![0u8; 64]).unwrap();
+    fs::write(roms_dir.join("readme.txt"), b"not a game").unwrap();
+
+    let roms = collect_roms(&roms_dir, false).unwrap();
+    let names: Vec<_> = roms.iter().map(|p| p.file_name().unwrap().to_str().unwrap()).collect();
+    assert_eq!(names, ["Alpha.nds", "Beta.nds", "broken.iso", "disc.cue"]);
+
+    // 2. Forge the batch on two threads
+    let options = BatchOptions {
+        output_dir: root.join("out"),
+        jobs: 2,
+        emulators: HashMap::from([("melonds".to_string(), emulator)]),
+        ..Default::default()
+    };
+    let report = forge_batch(&roms, &options, &[root.to_path_buf()], &NoProgress);
+
+    // 3. Successes have an output, failures an error, order is preserved
+    assert_eq!((report.total, report.succeeded, report.failed), (4, 2, 2));
+    let alpha = &report.entries[0];
+    assert_eq!(alpha.status, BatchStatus::Ok);
+    assert_eq!(alpha.platform, "nds");
+    assert_eq!(alpha.plugin.as_deref(), Some("melonds"));
+    let output = alpha.output.as_ref().unwrap();
+    assert_eq!(output, &root.join("out/Alpha/Alpha"));
+    let container = Container::open(output).unwrap().expect("No container");
+    let config = container.read_section(&mut File::open(output).unwrap(), SectionKind::LaunchConfig).unwrap();
+    assert!(config.is_some());
+
+    let broken = &report.entries[2];
+    assert_eq!(broken.status, BatchStatus::Failed);
+    assert!(broken.output.is_none());
+    assert!(broken.error.as_deref().unwrap().contains("Unrecognized platform"));
+
+    let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+    assert_eq!(json["entries"][1]["status"], "ok");
+    assert_eq!(json["entries"][2]["status"], "failed");
+}
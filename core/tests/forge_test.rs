@@ -0,0 +1,74 @@
+use emuforge_core::forge::container::{Container, SectionKind};
+use emuforge_core::forge::{ExecutableForge, LaunchConfig};
+use std::fs::{self, File};
+use std::path::PathBuf;
+use tempfile::tempdir;
+
+#[test]
+fn test_shortcut_forge_appends_config_to_template() {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path();
+
+    // 1. A fake prebuilt stub template
+    let template = root.join("emuforge-stub");
+    fs::write(&template, b"STUB-TEMPLATE").unwrap();
+
+    // 2. Forge a shortcut
+    let config = LaunchConfig {
+        emulator_path: PathBuf::from("/usr/bin/ppsspp"),
+        rom_path: PathBuf::from("/games/game.iso"),
+        ..Default::default()
+    };
+    let forge = ExecutableForge::new(template, root.join("out"));
+    let forged = forge.forge("game", &config).expect("Forge failed");
+
+    // 3. Template bytes first, then the container
+    let data = fs::read(&forged).unwrap();
+    assert!(data.starts_with(b"STUB-TEMPLATE"));
+
+    let container = Container::open(&forged).unwrap().expect("No container");
+    assert!(!container.is_legacy());
+    assert_eq!(container.payload_offset, b"STUB-TEMPLATE".len() as u64);
+
+    let mut file = File::open(&forged).unwrap();
+    let json = container.read_section(&mut file, SectionKind::LaunchConfig).unwrap().unwrap();
+    let embedded = LaunchConfig::from_json(&json).unwrap();
+    assert_eq!(embedded.rom_path, config.rom_path);
+}
+
+#[test]
+fn test_shortcut_forge_records_paths_relative_to_executable() {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path();
+    let template = root.join("emuforge-stub");
+    fs::write(&template, b"STUB-TEMPLATE").unwrap();
+    let out = root.join("out");
+    fs::create_dir_all(out.join("emu")).unwrap();
+    fs::write(out.join("emu/pcsx2"), b"").unwrap();
+
+    let config = LaunchConfig {
+        emulator_path: out.join("emu/pcsx2"),
+        rom_path: PathBuf::from("/games/game.iso"),
+        env_vars: vec![
+            ("XDG_CONFIG_HOME".into(), out.join("pcsx2_data").to_string_lossy().to_string()),
+            ("QT_QPA_PLATFORM".into(), "xcb".into()),
+        ],
+        args: vec!["emu".into()],
+        ..Default::default()
+    };
+    let forged = ExecutableForge::new(template, out).forge("game", &config).unwrap();
+
+    let container = Container::open(&forged).unwrap().expect("No container");
+    let json = container.read_section(&mut File::open(&forged).unwrap(), SectionKind::LaunchConfig).unwrap().unwrap();
+    let mut embedded = LaunchConfig::from_json(&json).unwrap();
+    assert_eq!(embedded.emulator_path, PathBuf::from("emu/pcsx2"));
+    assert_eq!(embedded.rom_path, PathBuf::from("/games/game.iso"));
+    assert_eq!(embedded.env_vars[0].1, "{exe_dir}/pcsx2_data");
+    // Valeurs relatives : jamais prises pour des chemins
+    assert_eq!(embedded.env_vars[1].1, "xcb");
+    assert_eq!(embedded.args, ["emu"]);
+
+    // Dossier déplacé : résolu par rapport au nouvel emplacement
+    embedded.resolve_paths(&root.join("moved"));
+    assert_eq!(embedded.emulator_path, root.join("moved/emu/pcsx2"));
+}
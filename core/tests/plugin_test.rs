@@ -1,5 +1,7 @@
+use emuforge_core::plugin::cemu::CemuPlugin;
+use emuforge_core::plugin::manager::PluginManager;
 use emuforge_core::plugin::ppsspp::PpssppPlugin;
-use emuforge_core::plugin::EmulatorPlugin;
+use emuforge_core::plugin::{BundleLayout, EmulatorPlugin};
 use std::fs::File;
 use std::path::PathBuf;
 use tempfile::tempdir;
@@ -35,3 +37,30 @@ fn test_ppsspp_plugin_config_generation() {
     
     assert!(config.args.is_empty());
 }
+
+#[test]
+fn test_bundle_layouts_are_declared_by_plugins() {
+    let manager = PluginManager::new();
+
+    let pcsx2 = manager.get_plugin_by_id("pcsx2").unwrap().bundle_layout();
+    assert_eq!(pcsx2.config_root, "./pcsx2_data");
+    assert_eq!(pcsx2.bios_dir.as_deref(), Some("pcsx2_data/PCSX2/bios"));
+    assert!(pcsx2.include_dirs.iter().any(|d| d == "pcsx2_data"));
+    assert!(pcsx2.save_dirs.iter().any(|d| d.ends_with("/memcards")));
+
+    // Saves kept on the host are declared with placeholders resolved by the stub
+    let ryujinx = manager.get_plugin_by_id("ryujinx").unwrap().bundle_layout();
+    assert_eq!(ryujinx.host_save_dirs, ["{config_home}/Ryujinx/bis/user/save"]);
+
+    // Plugins without specific needs get the XDG layout
+    let melonds = manager.get_plugin_by_id("melonds").unwrap().bundle_layout();
+    assert_eq!(melonds, BundleLayout::default());
+    assert_eq!(melonds.config_root, ".");
+}
+
+#[test]
+fn test_extra_args_follow_the_rom_for_cemu() {
+    // `cemu -g <jeu> -f` : les options passées au lancement suivent aussi le jeu
+    assert!(CemuPlugin::new(None).extra_args_after_rom());
+    assert!(!PpssppPlugin::new(None).extra_args_after_rom());
+}
@@ -0,0 +1,109 @@
+use emuforge_core::forge::bundle::Bundle;
+use emuforge_core::forge::container::{Container, SectionKind};
+use emuforge_core::forge::manifest::IntegrityManifest;
+use emuforge_core::forge::portable::disc_dependencies;
+use emuforge_core::forge::{PortableForge, PortableGame};
+use std::cell::RefCell;
+use std::fs::{self, File};
+use std::io::Cursor;
+use tempfile::tempdir;
+
+#[test]
+fn test_portable_forge_embeds_game_and_cue_tracks() {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path();
+
+    // 1. Fake stub, emulator without plugin, CUE + BIN game
+    let template = root.join("emuforge-stub");
+    fs::write(&template, b"STUB-TEMPLATE").unwrap();
+    let emulator = root.join("my-emulator");
+    fs::write(&emulator, b"#!/bin/sh\n").unwrap();
+    let rom = root.join("game.cue");
+    fs::write(&rom, "FILE \"game (Track 1).bin\" BINARY\n  TRACK 01 MODE2/2352\n").unwrap();
+    fs::write(root.join("game (Track 1).bin"), vec![7u8; 4096]).unwrap();
+
+    let deps = disc_dependencies(&rom).unwrap();
+    assert_eq!(deps.len(), 1);
+    assert_eq!(deps[0].1, "game (Track 1).bin");
+
+    // 2. Forge, recording progress
+    let messages = RefCell::new(vec![]);
+    let progress = |_: u64, message: &str| messages.borrow_mut().push(message.to_string());
+    let game = PortableGame {
+        game_name: "My Game".to_string(),
+        emulator_path: emulator,
+        rom_path: rom,
+        ..Default::default()
+    };
+    let forged = PortableForge::new(template, root.join("out")).forge(&game, &progress).unwrap();
+    assert!(messages.borrow().iter().any(|m| m.starts_with("Assemblage final")));
+
+    // 3. The container holds config, archive and a manifest covering every entry
+    let container = Container::open(&forged).unwrap().expect("No container");
+    let mut file = File::open(&forged).unwrap();
+    let config = container.read_section(&mut file, SectionKind::PortableConfig).unwrap().unwrap();
+    let config: serde_json::Value = serde_json::from_slice(&config).unwrap();
+    assert_eq!(config["rom_filename"], "game.cue");
+    assert_eq!(config["driver_id"], "generic");
+    assert_eq!(config["extra_args_after_rom"], false);
+
+    let manifest = container.read_section(&mut file, SectionKind::Manifest).unwrap().unwrap();
+    let manifest = IntegrityManifest::from_json(&manifest).unwrap();
+    let archive = container.read_section(&mut file, SectionKind::Archive).unwrap().unwrap();
+    let archive = zip::ZipArchive::new(Cursor::new(archive)).unwrap();
+    let mut names: Vec<&str> = archive.file_names().collect();
+    names.sort();
+    assert_eq!(names, ["game (Track 1).bin", "game.cue", "my-emulator"]);
+    assert_eq!(manifest.entries.len(), 3);
+    assert_eq!(manifest.get("game (Track 1).bin").unwrap().size, 4096);
+
+    // The emulator is a shared component, identified by its content
+    assert_eq!(manifest.components.len(), 1);
+    assert_eq!(manifest.components[0].path, "my-emulator");
+    assert_eq!(manifest.components[0].id, manifest.component_id("my-emulator"));
+    assert!(!manifest.components[0].external);
+}
+
+#[test]
+fn test_portable_forge_writes_split_sidecar() {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path();
+    let template = root.join("emuforge-stub");
+    fs::write(&template, b"STUB-TEMPLATE").unwrap();
+    let emulator = root.join("my-emulator");
+    fs::write(&emulator, b"#!/bin/sh\n").unwrap();
+    let rom = root.join("game.iso");
+    fs::write(&rom, vec![7u8; 10_000]).unwrap();
+
+    let game = PortableGame {
+        game_name: "My Game".to_string(),
+        emulator_path: emulator,
+        rom_path: rom,
+        sidecar: true,
+        part_size: Some(4096),
+        ..Default::default()
+    };
+    let out = root.join("out");
+    let forged = PortableForge::new(template, out.clone()).forge(&game, &|_: u64, _: &str| {}).unwrap();
+
+    // Small executable: config, manifest and a Sidecar section, no archive
+    let container = Container::open(&forged).unwrap().expect("No container");
+    assert!(container.section(SectionKind::Archive).is_none());
+    let bundle = Bundle::open(&forged).unwrap().unwrap();
+    let info = bundle.sidecar().unwrap().expect("No sidecar section");
+    assert_eq!(info.name, "My Game.emudata");
+    assert_eq!(info.part_size, Some(4096));
+
+    let mut parts: Vec<String> = fs::read_dir(&out).unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .filter(|name| name.contains(".emudata"))
+        .collect();
+    parts.sort();
+    assert_eq!(parts, info.part_names(&info.name));
+    assert!(parts.len() >= 3);
+
+    // The stub reads the parts as the embedded archive
+    let mut names: Vec<String> = bundle.entries().unwrap().into_iter().map(|e| e.name).collect();
+    names.sort();
+    assert_eq!(names, ["game.iso", "my-emulator"]);
+}
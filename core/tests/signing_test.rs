@@ -0,0 +1,43 @@
+use emuforge_core::forge::container::Container;
+use emuforge_core::forge::signing;
+use emuforge_core::forge::{ExecutableForge, LaunchConfig};
+use emuforge_format::signature::{self, TrustedKeys, Verification};
+use std::fs::{self, File};
+use tempfile::tempdir;
+
+#[test]
+fn test_signed_shortcut_is_verified_against_trusted_keys() {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path();
+    std::env::set_var(signing::KEYS_DIR_ENV, root.join("keys"));
+
+    // 1. Generate a publisher key
+    let info = signing::generate_key("team").unwrap();
+    assert!(signing::generate_key("team").is_err(), "Existing keys must not be overwritten");
+    let listed = signing::list_keys().unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].key_id, info.key_id);
+
+    // 2. Forge a signed shortcut
+    let template = root.join("emuforge-stub");
+    fs::write(&template, b"STUB-TEMPLATE").unwrap();
+    let forge = ExecutableForge::new(template, root.join("out"))
+        .with_signing_key(Some(signing::load_signing_key("team").unwrap()));
+    let forged = forge.forge("game", &LaunchConfig::default()).unwrap();
+
+    let verify = |trusted: &TrustedKeys| {
+        let container = Container::open(&forged).unwrap().unwrap();
+        signature::verify_container(&mut File::open(&forged).unwrap(), &container, trusted).unwrap()
+    };
+
+    // 3. Unknown key, then trusted key
+    assert_eq!(verify(&TrustedKeys::default()), Verification::UntrustedKey(info.key_id.clone()));
+    let trusted = TrustedKeys::parse(&format!("policy = refuse\n{} team\n", info.public_key)).unwrap();
+    assert_eq!(verify(&trusted), Verification::Trusted("team".to_string()));
+
+    // 4. Tampering with the stub breaks the signature
+    let mut data = fs::read(&forged).unwrap();
+    data[0] ^= 0xFF;
+    fs::write(&forged, data).unwrap();
+    assert!(matches!(verify(&trusted), Verification::Invalid(_)));
+}
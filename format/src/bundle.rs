@@ -0,0 +1,191 @@
+//! Lecture d'un exécutable forgé sans le lancer : configuration embarquée,
+//! contenu de l'archive, extraction.
+//!
+//! Utilisé par le stub (`--info`, `--list`, `--extract-to`) et par le forge
+//! pour inspecter un exécutable existant.
+
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use crate::container::{Container, SectionKind, SectionReader};
+use crate::manifest::{HashingWriter, IntegrityManifest};
+use crate::sidecar::{PartsReader, SidecarInfo};
+
+/// Archive ZIP lue en place, dans l'exécutable ou dans ses données `.emudata`.
+pub type EmbeddedArchive = zip::ZipArchive<PayloadReader>;
+
+/// Flux de l'archive : section de l'exécutable ou parties du fichier de données.
+pub enum PayloadReader {
+    Embedded(SectionReader<File>),
+    Sidecar(PartsReader),
+}
+
+impl Read for PayloadReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            PayloadReader::Embedded(reader) => reader.read(buf),
+            PayloadReader::Sidecar(reader) => reader.read(buf),
+        }
+    }
+}
+
+impl Seek for PayloadReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match self {
+            PayloadReader::Embedded(reader) => reader.seek(pos),
+            PayloadReader::Sidecar(reader) => reader.seek(pos),
+        }
+    }
+}
+
+/// Entrée de l'archive embarquée.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PayloadEntry {
+    pub name: String,
+    pub size: u64,
+    pub compressed_size: u64,
+    pub is_dir: bool,
+}
+
+/// Exécutable forgé ouvert en lecture.
+#[derive(Debug, Clone)]
+pub struct Bundle {
+    pub path: PathBuf,
+    pub container: Container,
+}
+
+impl Bundle {
+    /// `Ok(None)` si le fichier ne contient pas de conteneur EmuForge.
+    pub fn open(path: &Path) -> io::Result<Option<Bundle>> {
+        Ok(Container::open(path)?.map(|container| Bundle { path: path.to_path_buf(), container }))
+    }
+
+    pub fn is_portable(&self) -> bool {
+        self.container.section(SectionKind::PortableConfig).is_some()
+    }
+
+    /// Description des données séparées, `Ok(None)` si l'archive est embarquée.
+    pub fn sidecar(&self) -> io::Result<Option<SidecarInfo>> {
+        read_sidecar(&self.path, &self.container)
+    }
+
+    /// Configuration embarquée (`PortableConfig` ou `LaunchConfig`) en JSON brut.
+    pub fn config(&self) -> io::Result<Option<serde_json::Value>> {
+        let kind = if self.is_portable() { SectionKind::PortableConfig } else { SectionKind::LaunchConfig };
+        match self.container.read_section(&mut File::open(&self.path)?, kind)? {
+            Some(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// `Ok(None)` pour un raccourci (pas d'archive).
+    pub fn archive(&self) -> io::Result<Option<EmbeddedArchive>> {
+        if !has_archive(&self.container) {
+            return Ok(None);
+        }
+        open_archive(&self.path, &self.container).map(Some)
+    }
+
+    /// Entrées de l'archive, dans l'ordre du ZIP.
+    pub fn entries(&self) -> io::Result<Vec<PayloadEntry>> {
+        let Some(mut archive) = self.archive()? else { return Ok(Vec::new()) };
+        (0..archive.len())
+            .map(|i| {
+                let entry = archive.by_index_raw(i)?;
+                Ok(PayloadEntry {
+                    name: entry.name().to_string(),
+                    size: entry.size(),
+                    compressed_size: entry.compressed_size(),
+                    is_dir: entry.is_dir(),
+                })
+            })
+            .collect()
+    }
+
+    /// Manifeste d'intégrité, `Ok(None)` si le forge n'en a pas écrit.
+    pub fn manifest(&self) -> io::Result<Option<IntegrityManifest>> {
+        match self.container.read_section(&mut File::open(&self.path)?, SectionKind::Manifest)? {
+            Some(data) => Ok(Some(IntegrityManifest::from_json(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Décompresse toute l'archive dans `dir` et retourne le nombre de fichiers.
+    /// Chaque fichier est comparé au manifeste d'intégrité quand il y en a un ;
+    /// les chemins sortant de `dir` sont refusés.
+    pub fn extract_to(&self, dir: &Path) -> io::Result<usize> {
+        let manifest = self.manifest()?;
+        let mut archive = self.archive()?
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No archive section"))?;
+
+        let mut seen = HashSet::new();
+        let mut errors = Vec::new();
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i)?;
+            let path = entry.enclosed_name()
+                .map(|relative| dir.join(relative))
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, format!("{}: path outside of the archive", entry.name())))?;
+            if entry.is_dir() {
+                fs::create_dir_all(&path)?;
+                continue;
+            }
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            let mut out = HashingWriter::new(File::create(&path)?);
+            io::copy(&mut entry, &mut out)?;
+            let (_, sha256, size) = out.finalize();
+            #[cfg(unix)]
+            if let Some(mode) = entry.unix_mode() {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(&path, fs::Permissions::from_mode(mode))?;
+            }
+
+            if let Some(manifest) = &manifest {
+                if let Err(e) = manifest.check(entry.name(), size, &sha256) {
+                    errors.push(e);
+                }
+            }
+            seen.insert(entry.name().to_string());
+        }
+
+        if let Some(manifest) = &manifest {
+            errors.extend(manifest.missing_from(&seen));
+        }
+        if !errors.is_empty() {
+            let details: Vec<String> = errors.iter().map(|e| format!("  - {}", e)).collect();
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} corrupted file(s):\n{}", errors.len(), details.join("\n")),
+            ));
+        }
+        Ok(seen.len())
+    }
+}
+
+/// Archive embarquée ou données séparées.
+pub fn has_archive(container: &Container) -> bool {
+    container.section(SectionKind::Archive).is_some() || container.section(SectionKind::Sidecar).is_some()
+}
+
+pub fn read_sidecar(path: &Path, container: &Container) -> io::Result<Option<SidecarInfo>> {
+    match container.read_section(&mut File::open(path)?, SectionKind::Sidecar)? {
+        Some(data) => Ok(Some(SidecarInfo::from_json(&data)?)),
+        None => Ok(None),
+    }
+}
+
+/// Ouvre l'archive en place : section de l'exécutable (lecteur borné) ou
+/// fichier de données trouvé à côté de lui.
+pub fn open_archive(path: &Path, container: &Container) -> io::Result<EmbeddedArchive> {
+    let reader = match read_sidecar(path, container)? {
+        Some(sidecar) => PayloadReader::Sidecar(PartsReader::open(&sidecar.locate(path)?)?),
+        None => container.section_reader(File::open(path)?, SectionKind::Archive)?
+            .map(PayloadReader::Embedded)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No archive section"))?,
+    };
+    Ok(zip::ZipArchive::new(reader)?)
+}
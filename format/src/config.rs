@@ -0,0 +1,244 @@
+//! Configurations embarquées dans les exécutables forgés : `LaunchConfig`
+//! (raccourci vers un émulateur installé) et `PortableConfig` (bundle).
+//!
+//! Le JSON porte un `schema_version`. Les configs plus anciennes sont migrées
+//! à la lecture, champ par champ, avant d'être désérialisées ; une config plus
+//! récente que le stub est refusée plutôt que mal interprétée.
+//!
+//! Les valeurs de `env_vars` et les arguments sont des modèles (`template`).
+
+use serde::de::{DeserializeOwned, Error as _};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+
+use crate::template::{self, TemplateError};
+
+/// Version écrite par ce crate. Les configs sans `schema_version` sont en version 0.
+pub const SCHEMA_VERSION: u32 = 3;
+
+const VERSION_KEY: &str = "schema_version";
+
+/// Configuration for launching an emulator, embedded into the stub.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LaunchConfig {
+    /// Path to the emulator executable (absolute, or relative to the forged executable).
+    pub emulator_path: PathBuf,
+    /// Path to the ROM file (absolute, or relative to the forged executable).
+    pub rom_path: PathBuf,
+    /// Optional path to the BIOS file.
+    pub bios_path: Option<PathBuf>,
+    /// Arguments to pass to the emulator BEFORE the ROM path (templates).
+    pub args: Vec<String>,
+    /// Arguments to pass to the emulator AFTER the ROM path.
+    #[serde(default)]
+    pub args_after_rom: Vec<String>,
+    /// Working directory for the emulator process.
+    pub working_dir: Option<PathBuf>,
+    /// Environment variables to set (values are templates).
+    pub env_vars: Vec<(String, String)>,
+    /// Arguments passés après `--` placés après la ROM plutôt qu'en tête
+    #[serde(default)]
+    pub extra_args_after_rom: bool,
+    /// Identifiant du plugin (ryujinx, pcsx2...), `generic` sans plugin.
+    #[serde(default)]
+    pub driver_id: String,
+}
+
+/// Configuration d'un exécutable portable ; les chemins sont relatifs au
+/// dossier d'extraction.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PortableConfig {
+    pub game_name: String,
+    pub emulator_filename: String,
+    pub rom_filename: String,
+    pub config_dir: String,
+    pub fullscreen: bool,
+    #[serde(default)]
+    pub env_vars: Vec<(String, String)>,
+    #[serde(default)]
+    pub args_before_rom: Vec<String>,
+    #[serde(default)]
+    pub args_after_rom: Vec<String>,
+    /// Arguments passés après `--` placés après la ROM plutôt qu'en tête
+    #[serde(default)]
+    pub extra_args_after_rom: bool,
+    /// Identifiant du plugin (ryujinx, pcsx2...), `generic` sans plugin.
+    #[serde(default)]
+    pub driver_id: String,
+    /// Sauvegardes redirigées hors du cache (relatifs au dossier d'extraction)
+    #[serde(default)]
+    pub save_dirs: Vec<String>,
+    #[serde(default)]
+    pub save_files: Vec<String>,
+    /// Sauvegardes gardées par l'émulateur sur l'hôte, en modèles (`{config_home}/...`)
+    #[serde(default)]
+    pub host_save_dirs: Vec<String>,
+    /// AppImage : émulateur et ROM lus à côté du stub (`AppRun`) dans l'image
+    /// montée ; seul le reste de l'archive est extrait
+    #[serde(default)]
+    pub appdir: bool,
+}
+
+impl LaunchConfig {
+    /// Vérifie les modèles des variables d'environnement et des arguments.
+    pub fn validate_templates(&self) -> Result<(), TemplateError> {
+        self.env_vars.iter().map(|(_, value)| value)
+            .chain(&self.args)
+            .chain(&self.args_after_rom)
+            .try_for_each(|t| template::validate(t))
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<Vec<u8>> {
+        to_versioned_json(self)
+    }
+
+    pub fn from_json(data: &[u8]) -> serde_json::Result<Self> {
+        from_versioned_json(data, |version, config| {
+            // v0 : pas de driver_id, le stub le devinait d'après le chemin
+            if version < 1 && !config.contains_key("driver_id") {
+                let path = config.get("emulator_path").and_then(Value::as_str).unwrap_or_default();
+                config.insert("driver_id".into(), guess_driver_id(Path::new(path)).into());
+            }
+            // v1 : seul {exe_dir} était remplacé, dans env_vars uniquement
+            if version < 2 {
+                migrate_env(config, &["exe_dir"], false);
+                migrate_args(config, "args");
+                migrate_args(config, "args_after_rom");
+            }
+            // v2 : les chemins relatifs l'étaient au dossier courant, pas à l'exécutable
+            if version < 3 {
+                if let Ok(cwd) = std::env::current_dir() {
+                    for key in ["emulator_path", "rom_path", "bios_path", "working_dir"] {
+                        if let Some(Value::String(path)) = config.get_mut(key) {
+                            if !path.is_empty() && Path::new(path.as_str()).is_relative() {
+                                *path = cwd.join(&*path).to_string_lossy().to_string();
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    /// Résout les chemins relatifs à l'exécutable forgé (raccourci déplacé).
+    pub fn resolve_paths(&mut self, exe_dir: &Path) {
+        let resolve = |path: &mut PathBuf| {
+            if !path.as_os_str().is_empty() && path.is_relative() {
+                *path = exe_dir.join(&*path);
+            }
+        };
+        resolve(&mut self.emulator_path);
+        resolve(&mut self.rom_path);
+        self.bios_path.iter_mut().for_each(resolve);
+        self.working_dir.iter_mut().for_each(resolve);
+    }
+}
+
+impl PortableConfig {
+    /// Vérifie les modèles des variables d'environnement et des arguments.
+    pub fn validate_templates(&self) -> Result<(), TemplateError> {
+        self.env_vars.iter().map(|(_, value)| value)
+            .chain(&self.args_before_rom)
+            .chain(&self.args_after_rom)
+            .chain(&self.host_save_dirs)
+            .try_for_each(|t| template::validate(t))
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<Vec<u8>> {
+        to_versioned_json(self)
+    }
+
+    pub fn from_json(data: &[u8]) -> serde_json::Result<Self> {
+        from_versioned_json(data, |version, config| {
+            // v0 : driver_id optionnel
+            if version < 1 && config.get("driver_id").and_then(Value::as_str).is_none_or(str::is_empty) {
+                config.insert("driver_id".into(), "generic".into());
+            }
+            // v1 : {config_dir} et {exe_dir} dans env_vars, et les valeurs en
+            // `./` étaient relatives au dossier d'extraction
+            if version < 2 {
+                migrate_env(config, &["exe_dir", "config_dir"], true);
+                migrate_args(config, "args_before_rom");
+                migrate_args(config, "args_after_rom");
+            }
+        })
+    }
+}
+
+fn to_versioned_json<T: Serialize>(config: &T) -> serde_json::Result<Vec<u8>> {
+    let mut value = serde_json::to_value(config)?;
+    if let Value::Object(map) = &mut value {
+        map.insert(VERSION_KEY.into(), SCHEMA_VERSION.into());
+    }
+    serde_json::to_vec(&value)
+}
+
+fn from_versioned_json<T: DeserializeOwned>(data: &[u8], migrate: impl FnOnce(u32, &mut Map<String, Value>)) -> serde_json::Result<T> {
+    let mut value: Value = serde_json::from_slice(data)?;
+    let Value::Object(map) = &mut value else {
+        return Err(serde_json::Error::custom("config: objet JSON attendu"));
+    };
+    let version = match map.remove(VERSION_KEY) {
+        None => 0,
+        Some(v) => v.as_u64().and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| serde_json::Error::custom(format!("{} invalide: {}", VERSION_KEY, v)))?,
+    };
+    if version > SCHEMA_VERSION {
+        return Err(serde_json::Error::custom(format!(
+            "config en version {} (ce stub lit jusqu'à la version {}), re-forger avec une version d'EmuForge compatible",
+            version, SCHEMA_VERSION
+        )));
+    }
+    migrate(version, map);
+    serde_json::from_value(value)
+}
+
+/// Échappe une valeur antérieure aux modèles, sauf les variables qu'elle utilisait déjà.
+fn legacy_template(value: &str, kept: &[&str]) -> String {
+    let mut escaped = template::escape(value);
+    for name in kept {
+        escaped = escaped.replace(&format!("{{{{{}}}}}", name), &format!("{{{}}}", name));
+    }
+    escaped
+}
+
+fn migrate_env(config: &mut Map<String, Value>, kept: &[&str], relative_dot: bool) {
+    let Some(Value::Array(pairs)) = config.get_mut("env_vars") else { return };
+    for pair in pairs {
+        let Some(Value::String(value)) = pair.get_mut(1) else { continue };
+        let mut migrated = legacy_template(value, kept);
+        if relative_dot {
+            if let Some(rest) = migrated.strip_prefix("./") {
+                migrated = format!("{{exe_dir}}/{}", rest);
+            }
+        }
+        *value = migrated;
+    }
+}
+
+fn migrate_args(config: &mut Map<String, Value>, key: &str) {
+    let Some(Value::Array(args)) = config.get_mut(key) else { return };
+    for arg in args {
+        if let Value::String(value) = arg {
+            *value = template::escape(value);
+        }
+    }
+}
+
+/// Plugin probable d'après le chemin de l'émulateur (configs v0 uniquement).
+fn guess_driver_id(path: &Path) -> &'static str {
+    let lower = path.to_string_lossy().to_lowercase();
+    if lower.contains("ryujinx") {
+        return "ryujinx";
+    }
+    // AppImage Ryujinx extraite : squashfs-root/AppRun à côté de usr/bin/Ryujinx
+    let root = if path.file_name().is_some_and(|n| n == "AppRun") { path.parent() } else { Some(path) };
+    if root.is_some_and(|r| r.join("usr/bin/Ryujinx").exists() || r.join("usr/bin/Ryujinx.sh").exists()) {
+        return "ryujinx";
+    }
+    if lower.contains("azahar") || lower.contains("lime3ds") {
+        return "azahar";
+    }
+    "generic"
+}
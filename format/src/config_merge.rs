@@ -0,0 +1,177 @@
+//! Fusion clé par clé des fichiers de config (INI, TOML, YAML) lors d'une
+//! réextraction : les valeurs modifiées par le joueur l'emportent, les clés
+//! apportées par la nouvelle version embarquée sont ajoutées.
+//!
+//! L'analyse se fait ligne par ligne et reste volontairement simple : les
+//! constructions non gérées (tableaux de tables TOML, chaînes multilignes,
+//! listes YAML...) font échouer la fusion et le fichier du joueur est gardé tel quel.
+
+use std::collections::{HashMap, HashSet};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Ini,
+    Toml,
+    Yaml,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    /// Section INI/TOML ou clé YAML parente.
+    Header,
+    Key,
+    /// Commentaire, ligne vide...
+    Other,
+}
+
+struct Line {
+    raw: String,
+    path: Vec<String>,
+    kind: Kind,
+}
+
+fn format_of(name: &str) -> Option<Format> {
+    let ext = name.rsplit_once('.')?.1.to_ascii_lowercase();
+    match ext.as_str() {
+        "ini" => Some(Format::Ini),
+        "toml" => Some(Format::Toml),
+        "yaml" | "yml" => Some(Format::Yaml),
+        _ => None,
+    }
+}
+
+pub fn is_mergeable(name: &str) -> bool {
+    format_of(name).is_some()
+}
+
+/// Fusionne `user` (fichier modifié sur disque) dans `embedded` (nouvelle
+/// version du forge). `None` si le format ou son contenu n'est pas géré.
+pub fn merge(name: &str, user: &str, embedded: &str) -> Option<String> {
+    let format = format_of(name)?;
+    let user_lines = parse(format, user)?;
+    let mut out = parse(format, embedded)?;
+
+    // Une clé peut se répéter (PCSX2 lie chaque touche au clavier puis à la
+    // manette) : les clés sont appariées par chemin et rang d'apparition
+    let user_keys: HashMap<(Vec<String>, usize), &str> = occurrences(&user_lines)
+        .map(|(key, line)| (key, line.raw.as_str()))
+        .collect();
+    let replacements: Vec<Option<String>> = occurrences(&out)
+        .map(|(key, _)| user_keys.get(&key).map(|raw| raw.to_string()))
+        .collect();
+    for (line, raw) in out.iter_mut().filter(|l| l.kind == Kind::Key).zip(replacements) {
+        if let Some(raw) = raw {
+            line.raw = raw;
+        }
+    }
+
+    // Clés absentes de la version embarquée (ajoutées par le joueur ou l'émulateur)
+    let known: HashSet<(Vec<String>, usize)> = occurrences(&out).map(|(key, _)| key).collect();
+    for (_, line) in occurrences(&user_lines).filter(|(key, _)| !known.contains(key)) {
+        let parent = &line.path[..line.path.len() - 1];
+        let key = Line { raw: line.raw.clone(), path: line.path.clone(), kind: Kind::Key };
+        match out.iter().rposition(|l| l.path.starts_with(parent)) {
+            Some(index) => out.insert(index + 1, key),
+            None if parent.iter().all(|p| p.is_empty()) => out.insert(0, key),
+            None if format != Format::Yaml => {
+                out.push(Line { raw: String::new(), path: vec![], kind: Kind::Other });
+                out.push(Line { raw: format!("[{}]", parent[0]), path: parent.to_vec(), kind: Kind::Header });
+                out.push(key);
+            }
+            // Parent YAML disparu : le fichier du joueur est gardé tel quel
+            None => return None,
+        }
+    }
+
+    let mut merged = out.into_iter().map(|l| l.raw).collect::<Vec<_>>().join("\n");
+    if embedded.ends_with('\n') {
+        merged.push('\n');
+    }
+    Some(merged)
+}
+
+/// Clés de `lines` avec leur rang parmi les clés de même chemin.
+fn occurrences(lines: &[Line]) -> impl Iterator<Item = ((Vec<String>, usize), &Line)> {
+    let mut seen: HashMap<&[String], usize> = HashMap::new();
+    lines.iter().filter(|l| l.kind == Kind::Key).map(move |line| {
+        let count = seen.entry(line.path.as_slice()).or_default();
+        *count += 1;
+        ((line.path.clone(), *count - 1), line)
+    })
+}
+
+fn parse(format: Format, content: &str) -> Option<Vec<Line>> {
+    match format {
+        Format::Ini | Format::Toml => parse_ini(format, content),
+        Format::Yaml => parse_yaml(content),
+    }
+}
+
+/// INI et TOML « plats » : `[section]` puis `clé = valeur`.
+fn parse_ini(format: Format, content: &str) -> Option<Vec<Line>> {
+    let mut section = String::new();
+    let mut lines = Vec::new();
+    for raw in content.lines() {
+        let trimmed = raw.trim();
+        let (path, kind) = if trimmed.starts_with("[[") {
+            return None;
+        } else if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            section = trimmed[1..trimmed.len() - 1].trim().to_string();
+            (vec![section.clone()], Kind::Header)
+        } else if trimmed.starts_with(';') || trimmed.starts_with('#') {
+            (vec![section.clone()], Kind::Other)
+        } else if let Some((key, value)) = trimmed.split_once('=') {
+            let value = value.trim();
+            let multiline = value.starts_with("\"\"\"")
+                || value.starts_with("'''")
+                || value.matches('[').count() > value.matches(']').count();
+            if format == Format::Toml && multiline {
+                return None;
+            }
+            (vec![section.clone(), key.trim().to_string()], Kind::Key)
+        } else {
+            (vec![section.clone()], Kind::Other)
+        };
+        lines.push(Line { raw: raw.to_string(), path, kind });
+    }
+    Some(lines)
+}
+
+/// YAML limité aux dictionnaires imbriqués de scalaires.
+fn parse_yaml(content: &str) -> Option<Vec<Line>> {
+    let mut parents: Vec<(usize, String)> = Vec::new();
+    let mut lines = Vec::new();
+    for raw in content.lines() {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed == "---" || trimmed == "..." {
+            lines.push(Line { raw: raw.to_string(), path: vec![], kind: Kind::Other });
+            continue;
+        }
+        if trimmed.starts_with('-') || raw.starts_with('\t') {
+            return None;
+        }
+
+        let indent = raw.len() - raw.trim_start_matches(' ').len();
+        while parents.last().is_some_and(|(i, _)| *i >= indent) {
+            parents.pop();
+        }
+
+        let (key, value) = trimmed.split_once(':')?;
+        let key = key.trim().trim_matches(['"', '\'']).to_string();
+        let value = value.trim();
+        if value.starts_with('|') || value.starts_with('>') {
+            return None;
+        }
+
+        let mut path: Vec<String> = parents.iter().map(|(_, k)| k.clone()).collect();
+        path.push(key.clone());
+        let kind = if value.is_empty() || value.starts_with('#') {
+            parents.push((indent, key));
+            Kind::Header
+        } else {
+            Kind::Key
+        };
+        lines.push(Line { raw: raw.to_string(), path, kind });
+    }
+    Some(lines)
+}
@@ -0,0 +1,473 @@
+//! Conteneur versionné ajouté à la fin d'un exécutable forgé.
+//!
+//! Layout (V3) :
+//!
+//! ```text
+//! [stub][section 0][section 1]...[section n][table des sections][footer]
+//! ```
+//!
+//! Le footer a une taille fixe (`FOOTER_LEN`) et se termine par `MAGIC`, ce
+//! qui permet de le lire directement à la fin du fichier, quelle que soit la
+//! taille du stub. Toutes les valeurs sont en little-endian :
+//!
+//! ```text
+//! payload_offset: u64   début de la première section (= taille du stub)
+//! toc_offset:     u64   début de la table des sections
+//! section_count:  u32
+//! version:        u16
+//! flags:          u16   réservé
+//! magic:          [u8; 8]
+//! ```
+//!
+//! Chaque entrée de la table fait `TOC_ENTRY_LEN` octets :
+//! `kind: u32, flags: u32, offset: u64, len: u64` (offsets absolus).
+//!
+//! Les exécutables "V2" (marqueur suivi de la config puis du ZIP) restent
+//! lisibles : `Container::read_from` retombe sur l'ancien marqueur si aucun
+//! footer n'est présent.
+
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// Magic terminant le footer.
+pub const MAGIC: [u8; 8] = *b"EMUFORGE";
+
+/// Version courante du format conteneur.
+pub const FORMAT_VERSION: u16 = 3;
+
+/// Version attribuée aux exécutables à marqueur (avant le footer).
+pub const LEGACY_V2_VERSION: u16 = 2;
+
+/// Taille du footer en octets.
+pub const FOOTER_LEN: u64 = 8 + 8 + 4 + 2 + 2 + 8;
+
+/// Taille d'une entrée de la table des sections.
+pub const TOC_ENTRY_LEN: u64 = 4 + 4 + 8 + 8;
+
+/// Marqueur V2 précédant une `PortableConfig` puis l'archive ZIP.
+pub const LEGACY_PORTABLE_MARKER: &[u8] = &[0xDE, 0xAD, 0xBE, 0xEF, 0x45, 0x4D, 0x55, 0x46, 0x4F, 0x52, 0x47, 0x45, 0x56, 0x32];
+
+/// Marqueur précédant une `LaunchConfig` (raccourcis forgés avant le footer).
+pub const LEGACY_LAUNCHER_MARKER: &[u8] = &[0xDE, 0xAD, 0xBE, 0xEF, 0x45, 0x4D, 0x55, 0x46, 0x4F, 0x52, 0x47, 0x45, 0x4C, 0x31];
+
+/// Zone scannée pour les marqueurs legacy (les anciens stubs faisaient moins de 10 Mo).
+const LEGACY_SCAN_LIMIT: u64 = 10 * 1024 * 1024;
+
+/// Nombre maximal de sections accepté à la lecture (protection contre un footer corrompu).
+const MAX_SECTIONS: u32 = 1024;
+
+/// Type de contenu d'une section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SectionKind {
+    /// `LaunchConfig` JSON (mode raccourci).
+    LaunchConfig,
+    /// `PortableConfig` JSON (mode portable).
+    PortableConfig,
+    /// Archive ZIP (émulateur, ROM, configs).
+    Archive,
+    /// Manifeste d'intégrité JSON (SHA-256 par entrée de l'archive).
+    Manifest,
+    /// Signature ed25519 de l'exécutable (voir `crate::signature`).
+    Signature,
+    /// Archive écrite à côté de l'exécutable (voir `crate::sidecar`).
+    Sidecar,
+    /// Type inconnu de cette version, conservé tel quel.
+    Other(u32),
+}
+
+impl SectionKind {
+    pub fn code(self) -> u32 {
+        match self {
+            SectionKind::LaunchConfig => 1,
+            SectionKind::PortableConfig => 2,
+            SectionKind::Archive => 3,
+            SectionKind::Manifest => 4,
+            SectionKind::Signature => 5,
+            SectionKind::Sidecar => 6,
+            SectionKind::Other(code) => code,
+        }
+    }
+
+    pub fn from_code(code: u32) -> Self {
+        match code {
+            1 => SectionKind::LaunchConfig,
+            2 => SectionKind::PortableConfig,
+            3 => SectionKind::Archive,
+            4 => SectionKind::Manifest,
+            5 => SectionKind::Signature,
+            6 => SectionKind::Sidecar,
+            other => SectionKind::Other(other),
+        }
+    }
+}
+
+/// Entrée de la table des sections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Section {
+    pub kind: SectionKind,
+    pub flags: u32,
+    /// Offset absolu dans le fichier.
+    pub offset: u64,
+    pub len: u64,
+}
+
+/// Conteneur lu depuis un exécutable forgé.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Container {
+    pub version: u16,
+    /// Début de la charge utile (= taille du stub).
+    pub payload_offset: u64,
+    pub sections: Vec<Section>,
+}
+
+impl Container {
+    /// Ouvre un exécutable et lit son conteneur. `Ok(None)` si aucun n'est présent.
+    pub fn open(path: &Path) -> io::Result<Option<Container>> {
+        let mut file = File::open(path)?;
+        Self::read_from(&mut file)
+    }
+
+    /// Lit le footer V3, ou à défaut un marqueur legacy.
+    pub fn read_from<R: Read + Seek>(reader: &mut R) -> io::Result<Option<Container>> {
+        let file_len = reader.seek(SeekFrom::End(0))?;
+        if let Some(container) = Self::read_footer(reader, file_len)? {
+            return Ok(Some(container));
+        }
+        Self::read_legacy(reader, file_len)
+    }
+
+    /// Première section du type demandé.
+    pub fn section(&self, kind: SectionKind) -> Option<&Section> {
+        self.sections.iter().find(|s| s.kind == kind)
+    }
+
+    /// Lit entièrement une section en mémoire (à réserver aux petites sections).
+    pub fn read_section<R: Read + Seek>(&self, reader: &mut R, kind: SectionKind) -> io::Result<Option<Vec<u8>>> {
+        let section = match self.section(kind) {
+            Some(s) => *s,
+            None => return Ok(None),
+        };
+        reader.seek(SeekFrom::Start(section.offset))?;
+        let mut data = vec![0u8; section.len as usize];
+        reader.read_exact(&mut data)?;
+        Ok(Some(data))
+    }
+
+    /// Ouvre une section en place, sans la copier (ex: l'archive ZIP).
+    pub fn section_reader<R: Read + Seek>(&self, reader: R, kind: SectionKind) -> io::Result<Option<SectionReader<R>>> {
+        match self.section(kind) {
+            Some(section) => SectionReader::new(reader, section).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Vrai pour les exécutables à marqueur (avant le footer versionné).
+    pub fn is_legacy(&self) -> bool {
+        self.version < FORMAT_VERSION
+    }
+
+    fn read_footer<R: Read + Seek>(reader: &mut R, file_len: u64) -> io::Result<Option<Container>> {
+        if file_len < FOOTER_LEN {
+            return Ok(None);
+        }
+        reader.seek(SeekFrom::Start(file_len - FOOTER_LEN))?;
+        let mut footer = [0u8; FOOTER_LEN as usize];
+        reader.read_exact(&mut footer)?;
+
+        if footer[24..32] != MAGIC {
+            return Ok(None);
+        }
+
+        let payload_offset = u64::from_le_bytes(footer[0..8].try_into().unwrap());
+        let toc_offset = u64::from_le_bytes(footer[8..16].try_into().unwrap());
+        let section_count = u32::from_le_bytes(footer[16..20].try_into().unwrap());
+        let version = u16::from_le_bytes(footer[20..22].try_into().unwrap());
+
+        if version > FORMAT_VERSION {
+            return Err(invalid_data(format!(
+                "Unsupported container version {} (this stub supports up to {})",
+                version, FORMAT_VERSION
+            )));
+        }
+        let toc_len = u64::from(section_count) * TOC_ENTRY_LEN;
+        if section_count > MAX_SECTIONS
+            || payload_offset > toc_offset
+            || toc_offset.checked_add(toc_len) != Some(file_len - FOOTER_LEN)
+        {
+            return Err(invalid_data("Corrupted container footer".to_string()));
+        }
+
+        reader.seek(SeekFrom::Start(toc_offset))?;
+        let mut toc = vec![0u8; toc_len as usize];
+        reader.read_exact(&mut toc)?;
+
+        let mut sections = Vec::with_capacity(section_count as usize);
+        for entry in toc.chunks_exact(TOC_ENTRY_LEN as usize) {
+            let section = Section {
+                kind: SectionKind::from_code(u32::from_le_bytes(entry[0..4].try_into().unwrap())),
+                flags: u32::from_le_bytes(entry[4..8].try_into().unwrap()),
+                offset: u64::from_le_bytes(entry[8..16].try_into().unwrap()),
+                len: u64::from_le_bytes(entry[16..24].try_into().unwrap()),
+            };
+            let end = section.offset.checked_add(section.len)
+                .ok_or_else(|| invalid_data("Corrupted container footer".to_string()))?;
+            if section.offset < payload_offset || end > toc_offset {
+                return Err(invalid_data(format!("Section {:?} is out of bounds", section.kind)));
+            }
+            sections.push(section);
+        }
+
+        Ok(Some(Container { version, payload_offset, sections }))
+    }
+
+    fn read_legacy<R: Read + Seek>(reader: &mut R, file_len: u64) -> io::Result<Option<Container>> {
+        reader.seek(SeekFrom::Start(0))?;
+        let mut buffer = Vec::new();
+        reader.take(LEGACY_SCAN_LIMIT).read_to_end(&mut buffer)?;
+
+        // Le dernier marqueur trouvé est le vrai : les précédents sont les
+        // constantes compilées dans le stub lui-même.
+        let found = [
+            (LEGACY_PORTABLE_MARKER, SectionKind::PortableConfig),
+            (LEGACY_LAUNCHER_MARKER, SectionKind::LaunchConfig),
+        ]
+        .into_iter()
+        .filter_map(|(marker, kind)| {
+            buffer
+                .windows(marker.len())
+                .rposition(|window| window == marker)
+                .map(|pos| (pos, marker.len(), kind))
+        })
+        .max_by_key(|(pos, _, _)| *pos);
+
+        let (pos, marker_len, kind) = match found {
+            Some(f) => f,
+            None => return Ok(None),
+        };
+
+        let len_start = pos + marker_len;
+        if buffer.len() < len_start + 4 {
+            return Ok(None);
+        }
+        let config_len = u64::from(u32::from_le_bytes(buffer[len_start..len_start + 4].try_into().unwrap()));
+        let config_offset = (len_start + 4) as u64;
+        if config_offset + config_len > file_len {
+            return Ok(None);
+        }
+
+        let mut sections = vec![Section { kind, flags: 0, offset: config_offset, len: config_len }];
+        if kind == SectionKind::PortableConfig {
+            // V2 : l'archive ZIP suit directement la config jusqu'à la fin du fichier
+            let archive_offset = config_offset + config_len;
+            sections.push(Section {
+                kind: SectionKind::Archive,
+                flags: 0,
+                offset: archive_offset,
+                len: file_len - archive_offset,
+            });
+        }
+
+        Ok(Some(Container {
+            version: LEGACY_V2_VERSION,
+            payload_offset: pos as u64,
+            sections,
+        }))
+    }
+}
+
+/// Vue bornée sur une section : `Read` s'arrête à sa fin et `Seek` est
+/// relatif à son début.
+pub struct SectionReader<R> {
+    inner: R,
+    start: u64,
+    len: u64,
+    position: u64,
+}
+
+impl<R: Read + Seek> SectionReader<R> {
+    pub fn new(mut inner: R, section: &Section) -> io::Result<Self> {
+        inner.seek(SeekFrom::Start(section.offset))?;
+        Ok(Self { inner, start: section.offset, len: section.len, position: 0 })
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read + Seek> Read for SectionReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.len.saturating_sub(self.position);
+        let max = buf.len().min(usize::try_from(remaining).unwrap_or(usize::MAX));
+        if max == 0 {
+            return Ok(0);
+        }
+        let n = self.inner.read(&mut buf[..max])?;
+        self.position += n as u64;
+        Ok(n)
+    }
+}
+
+impl<R: Read + Seek> Seek for SectionReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(n) => Some(n),
+            SeekFrom::Current(delta) => self.position.checked_add_signed(delta),
+            SeekFrom::End(delta) => self.len.checked_add_signed(delta),
+        };
+        let target = target.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Seek before the start of the section"))?;
+        self.inner.seek(SeekFrom::Start(self.start + target))?;
+        self.position = target;
+        Ok(target)
+    }
+}
+
+/// Écrit les sections puis la table et le footer à la suite d'un stub.
+///
+/// La charge utile commence à la position courante de `inner` : l'appelant
+/// écrit d'abord le stub, puis crée le writer.
+pub struct ContainerWriter<W: Write + Seek> {
+    inner: W,
+    payload_offset: u64,
+    position: u64,
+    /// Fin des données écrites dans la section ouverte (la position peut
+    /// être en deçà après un `seek`).
+    open_end: u64,
+    sections: Vec<Section>,
+    open_section: Option<Section>,
+}
+
+impl<W: Write + Seek> ContainerWriter<W> {
+    pub fn new(mut inner: W) -> io::Result<Self> {
+        let payload_offset = inner.stream_position()?;
+        Ok(Self {
+            inner,
+            payload_offset,
+            position: payload_offset,
+            open_end: payload_offset,
+            sections: Vec::new(),
+            open_section: None,
+        })
+    }
+
+    /// Ajoute une section complète.
+    pub fn add_section(&mut self, kind: SectionKind, data: &[u8]) -> io::Result<()> {
+        self.start_section(kind)?;
+        self.write_all(data)?;
+        self.end_section()
+    }
+
+    /// Ouvre une section : les écritures suivantes (`Write`) y sont ajoutées.
+    /// `Seek` se déplace dans la section, les positions étant relatives à son
+    /// début (un `ZipWriter` peut donc écrire directement dedans).
+    pub fn start_section(&mut self, kind: SectionKind) -> io::Result<()> {
+        if self.open_section.is_some() {
+            return Err(io::Error::other("A section is already open"));
+        }
+        self.open_section = Some(Section { kind, flags: 0, offset: self.position, len: 0 });
+        self.open_end = self.position;
+        Ok(())
+    }
+
+    /// Ferme la section ouverte par `start_section`.
+    pub fn end_section(&mut self) -> io::Result<()> {
+        let mut section = self
+            .open_section
+            .take()
+            .ok_or_else(|| io::Error::other("No open section"))?;
+        if self.position != self.open_end {
+            self.inner.seek(SeekFrom::Start(self.open_end))?;
+            self.position = self.open_end;
+        }
+        section.len = self.open_end - section.offset;
+        self.sections.push(section);
+        Ok(())
+    }
+
+    /// Écrit la table des sections et le footer, puis rend le writer sous-jacent.
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.open_section.is_some() {
+            self.end_section()?;
+        }
+
+        let toc_offset = self.position;
+        for section in &self.sections {
+            self.inner.write_all(&section.kind.code().to_le_bytes())?;
+            self.inner.write_all(&section.flags.to_le_bytes())?;
+            self.inner.write_all(&section.offset.to_le_bytes())?;
+            self.inner.write_all(&section.len.to_le_bytes())?;
+        }
+
+        self.inner.write_all(&self.payload_offset.to_le_bytes())?;
+        self.inner.write_all(&toc_offset.to_le_bytes())?;
+        self.inner.write_all(&(self.sections.len() as u32).to_le_bytes())?;
+        self.inner.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        self.inner.write_all(&0u16.to_le_bytes())?;
+        self.inner.write_all(&MAGIC)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Read + Write + Seek> ContainerWriter<W> {
+    /// Relit ce qui a déjà été écrit (ex: pour le signer) puis se replace en fin
+    /// de charge utile. `f` reçoit le flux, `payload_offset` et les sections fermées.
+    pub fn reread<T>(&mut self, f: impl FnOnce(&mut W, u64, &[Section]) -> io::Result<T>) -> io::Result<T> {
+        if self.open_section.is_some() {
+            return Err(io::Error::other("Cannot reread while a section is open"));
+        }
+        let result = f(&mut self.inner, self.payload_offset, &self.sections);
+        self.inner.seek(SeekFrom::Start(self.position))?;
+        result
+    }
+}
+
+impl<W: Write + Seek> Write for ContainerWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.open_section.is_none() {
+            return Err(io::Error::other("Write outside of a section"));
+        }
+        let n = self.inner.write(buf)?;
+        self.position += n as u64;
+        self.open_end = self.open_end.max(self.position);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W: Write + Seek> Seek for ContainerWriter<W> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let start = match &self.open_section {
+            Some(section) => section.offset,
+            None => return Err(io::Error::other("Seek outside of a section")),
+        };
+        let target = match pos {
+            SeekFrom::Start(n) => start.checked_add(n),
+            SeekFrom::Current(delta) => self.position.checked_add_signed(delta),
+            SeekFrom::End(delta) => self.open_end.checked_add_signed(delta),
+        };
+        let target = match target {
+            Some(target) if target >= start => target,
+            _ => return Err(io::Error::new(io::ErrorKind::InvalidInput, "Seek before the start of the section")),
+        };
+        self.inner.seek(SeekFrom::Start(target))?;
+        self.position = target;
+        Ok(target - start)
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
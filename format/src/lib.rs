@@ -0,0 +1,13 @@
+//! Formats partagés entre le forge (`emuforge-core`) et le stub.
+//!
+//! Ce crate ne doit dépendre que du strict minimum : il est compilé dans
+//! chaque exécutable forgé.
+
+pub mod bundle;
+pub mod config;
+pub mod config_merge;
+pub mod container;
+pub mod manifest;
+pub mod sidecar;
+pub mod signature;
+pub mod template;
@@ -0,0 +1,198 @@
+//! Manifeste d'intégrité de l'archive embarquée.
+//!
+//! Le forge enregistre la taille et le SHA-256 de chaque entrée du ZIP ;
+//! le stub les compare aux fichiers extraits avant de lancer l'émulateur.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::io::{self, Read, Write};
+
+/// Version du schéma JSON du manifeste.
+pub const MANIFEST_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Nom de l'entrée dans l'archive (séparateurs `/`).
+    pub path: String,
+    pub size: u64,
+    /// SHA-256 en hexadécimal minuscule.
+    pub sha256: String,
+}
+
+/// Composant partageable entre jeux (émulateur), identifié par son contenu :
+/// le stub l'extrait une seule fois dans un magasin commun et le lie dans
+/// chaque dossier de jeu.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Component {
+    /// Fichier ou dossier racine dans l'archive.
+    pub path: String,
+    /// Empreinte du contenu, voir `IntegrityManifest::component_id`.
+    pub id: String,
+    /// Absent de l'archive : fourni par un pack `<id>.emupack` distribué à part.
+    #[serde(default)]
+    pub external: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntegrityManifest {
+    pub version: u32,
+    pub entries: Vec<ManifestEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<Component>,
+}
+
+impl Default for IntegrityManifest {
+    fn default() -> Self {
+        Self {
+            version: MANIFEST_VERSION,
+            entries: vec![],
+            components: vec![],
+        }
+    }
+}
+
+impl IntegrityManifest {
+    pub fn add(&mut self, path: &str, size: u64, sha256: String) {
+        self.entries.push(ManifestEntry {
+            path: path.to_string(),
+            size,
+            sha256,
+        });
+    }
+
+    pub fn get(&self, path: &str) -> Option<&ManifestEntry> {
+        self.entries.iter().find(|e| e.path == path)
+    }
+
+    /// Vrai si l'entrée `path` fait partie du composant (fichier ou dossier) `root`.
+    pub fn is_under(path: &str, root: &str) -> bool {
+        path == root || path.strip_prefix(root).is_some_and(|rest| rest.starts_with('/'))
+    }
+
+    pub fn component_of(&self, path: &str) -> Option<&Component> {
+        self.components.iter().find(|c| Self::is_under(path, &c.path))
+    }
+
+    /// SHA-256 des entrées (chemin, taille, SHA-256) sous `root`, triées :
+    /// deux jeux embarquant le même émulateur obtiennent le même identifiant.
+    pub fn component_id(&self, root: &str) -> String {
+        let mut entries: Vec<&ManifestEntry> = self.entries.iter().filter(|e| Self::is_under(&e.path, root)).collect();
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        let mut hasher = Sha256::new();
+        for entry in entries {
+            hasher.update(format!("{}\0{}\0{}\n", entry.path, entry.size, entry.sha256).as_bytes());
+        }
+        to_hex(&hasher.finalize())
+    }
+
+    /// Déclare `root` comme composant partagé et retourne son identifiant.
+    pub fn add_component(&mut self, root: &str, external: bool) -> String {
+        let id = self.component_id(root);
+        self.components.push(Component { path: root.to_string(), id: id.clone(), external });
+        id
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<Vec<u8>> {
+        serde_json::to_vec(self)
+    }
+
+    pub fn from_json(data: &[u8]) -> serde_json::Result<Self> {
+        serde_json::from_slice(data)
+    }
+
+    /// Compare une entrée extraite au manifeste.
+    pub fn check(&self, path: &str, size: u64, sha256: &str) -> Result<(), IntegrityError> {
+        match self.get(path) {
+            None => Err(IntegrityError::Unexpected(path.to_string())),
+            Some(e) if e.size != size => Err(IntegrityError::SizeMismatch {
+                path: path.to_string(),
+                expected: e.size,
+                actual: size,
+            }),
+            Some(e) if e.sha256 != sha256 => Err(IntegrityError::HashMismatch(path.to_string())),
+            Some(_) => Ok(()),
+        }
+    }
+
+    /// Entrées du manifeste qui ne figurent pas dans `seen` (hors composants externes).
+    pub fn missing_from(&self, seen: &HashSet<String>) -> Vec<IntegrityError> {
+        self.entries
+            .iter()
+            .filter(|e| !seen.contains(&e.path))
+            .filter(|e| !self.component_of(&e.path).is_some_and(|c| c.external))
+            .map(|e| IntegrityError::Missing(e.path.clone()))
+            .collect()
+    }
+}
+
+/// Écart entre l'archive et son manifeste.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegrityError {
+    /// Entrée présente dans l'archive mais absente du manifeste.
+    Unexpected(String),
+    /// Entrée du manifeste absente de l'archive (fichier tronqué ?).
+    Missing(String),
+    SizeMismatch { path: String, expected: u64, actual: u64 },
+    HashMismatch(String),
+}
+
+impl std::fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IntegrityError::Unexpected(p) => write!(f, "{}: absent du manifeste", p),
+            IntegrityError::Missing(p) => write!(f, "{}: absent de l'archive", p),
+            IntegrityError::SizeMismatch { path, expected, actual } => {
+                write!(f, "{}: taille {} au lieu de {} octets", path, actual, expected)
+            }
+            IntegrityError::HashMismatch(p) => write!(f, "{}: SHA-256 différent", p),
+        }
+    }
+}
+
+/// Writer qui calcule le SHA-256 de tout ce qui le traverse.
+pub struct HashingWriter<W: Write> {
+    inner: W,
+    hasher: Sha256,
+    written: u64,
+}
+
+impl<W: Write> HashingWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+            written: 0,
+        }
+    }
+
+    /// Retourne le writer, le SHA-256 hexadécimal et le nombre d'octets écrits.
+    pub fn finalize(self) -> (W, String, u64) {
+        (self.inner, to_hex(&self.hasher.finalize()), self.written)
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        self.written += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// SHA-256 hexadécimal et taille d'un flux.
+pub fn hash_reader<R: Read>(reader: &mut R) -> io::Result<(String, u64)> {
+    let mut writer = HashingWriter::new(io::sink());
+    io::copy(reader, &mut writer)?;
+    let (_, hash, size) = writer.finalize();
+    Ok((hash, size))
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
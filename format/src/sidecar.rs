@@ -0,0 +1,266 @@
+//! Données d'un exécutable portable écrites à côté de lui plutôt que dedans.
+//!
+//! L'archive ZIP qui irait dans la section `Archive` est écrite dans
+//! `<jeu>.emudata`, éventuellement découpé en parties de taille fixe
+//! (`<jeu>.emudata.001`, `.002`...) pour FAT32 ou les outils de partage.
+//! L'exécutable ne garde que sa config, le manifeste et une section
+//! `Sidecar` (nom, taille, SHA-256) ; le stub lit les parties comme un seul
+//! flux et extrait exactement comme depuis l'archive embarquée.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::manifest::hash_reader;
+
+pub const EXTENSION: &str = "emudata";
+
+/// Dossier supplémentaire où chercher les données (et les packs d'émulateur).
+pub const SEARCH_DIR_ENV: &str = "EMUFORGE_PACKS_DIR";
+
+/// Contenu de la section `Sidecar`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SidecarInfo {
+    /// Nom du fichier de données, sans suffixe de partie.
+    pub name: String,
+    /// Taille totale (toutes parties).
+    pub size: u64,
+    /// SHA-256 du flux complet.
+    pub sha256: String,
+    /// Taille de chaque partie (la dernière peut être plus courte) ; `None` : un seul fichier.
+    #[serde(default)]
+    pub part_size: Option<u64>,
+}
+
+/// Nom de la partie `index` (0-based) : `<name>.001`, `<name>.002`...
+pub fn part_name(name: &str, index: usize) -> String {
+    format!("{}.{:03}", name, index + 1)
+}
+
+impl SidecarInfo {
+    pub fn to_json(&self) -> serde_json::Result<Vec<u8>> {
+        serde_json::to_vec(self)
+    }
+
+    pub fn from_json(data: &[u8]) -> serde_json::Result<Self> {
+        serde_json::from_slice(data)
+    }
+
+    /// Noms des fichiers attendus, dans l'ordre.
+    pub fn part_names(&self, name: &str) -> Vec<String> {
+        match self.part_size {
+            Some(part_size) if part_size > 0 => {
+                let count = self.size.div_ceil(part_size).max(1) as usize;
+                (0..count).map(|i| part_name(name, i)).collect()
+            }
+            _ => vec![name.to_string()],
+        }
+    }
+
+    /// Cherche les données à côté de l'exécutable puis dans `SEARCH_DIR_ENV`,
+    /// de même taille et de même SHA-256 : d'abord sous leur nom, puis parmi
+    /// les autres `.emudata` (exécutable ou données renommés).
+    pub fn locate(&self, exe_path: &Path) -> io::Result<Vec<PathBuf>> {
+        let dirs: Vec<PathBuf> = exe_path.parent().map(Path::to_path_buf).into_iter()
+            .chain(std::env::var_os(SEARCH_DIR_ENV).map(PathBuf::from))
+            .collect();
+
+        // Données de même nom mais d'un autre forge : signalées si rien d'autre ne convient
+        let mut mismatch = false;
+        for dir in &dirs {
+            let parts = self.parts_in(dir, &self.name);
+            if total_size(&parts) == Some(self.size) {
+                if self.matches(&parts)? {
+                    return Ok(parts);
+                }
+                mismatch = true;
+            }
+        }
+        for dir in &dirs {
+            let Ok(entries) = fs::read_dir(dir) else { continue };
+            for entry in entries.flatten() {
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                let suffix = if self.part_size.is_some() { format!(".{}.001", EXTENSION) } else { format!(".{}", EXTENSION) };
+                let Some(stem) = file_name.strip_suffix(&suffix) else { continue };
+                let name = format!("{}.{}", stem, EXTENSION);
+                if name == self.name {
+                    continue;
+                }
+                let parts = self.parts_in(dir, &name);
+                if total_size(&parts) == Some(self.size) && self.matches(&parts)? {
+                    return Ok(parts);
+                }
+            }
+        }
+        if mismatch {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("{}: empreinte SHA-256 différente", self.name)));
+        }
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("données {} introuvables (à placer à côté de l'exécutable ou dans {})", self.name, SEARCH_DIR_ENV),
+        ))
+    }
+
+    fn parts_in(&self, dir: &Path, name: &str) -> Vec<PathBuf> {
+        self.part_names(name).into_iter().map(|part| dir.join(part)).collect()
+    }
+
+    /// Compare le SHA-256 du flux complet (lecture de toutes les parties).
+    pub fn matches(&self, parts: &[PathBuf]) -> io::Result<bool> {
+        let (sha256, size) = hash_reader(&mut PartsReader::open(parts)?)?;
+        Ok(size == self.size && sha256 == self.sha256)
+    }
+}
+
+fn total_size(parts: &[PathBuf]) -> Option<u64> {
+    parts.iter().map(|p| fs::metadata(p).ok().filter(|m| m.is_file()).map(|m| m.len())).sum()
+}
+
+/// Écriture (avec retours en arrière, pour le ZIP) répartie sur des parties
+/// de `part_size` octets créées à la demande.
+pub struct PartsWriter {
+    dir: PathBuf,
+    name: String,
+    part_size: Option<u64>,
+    parts: Vec<File>,
+    position: u64,
+    len: u64,
+}
+
+impl PartsWriter {
+    pub fn create(dir: &Path, name: &str, part_size: Option<u64>) -> io::Result<Self> {
+        let part_size = part_size.filter(|&size| size > 0);
+        let mut writer = PartsWriter { dir: dir.to_path_buf(), name: name.to_string(), part_size, parts: Vec::new(), position: 0, len: 0 };
+        writer.part(0)?;
+        Ok(writer)
+    }
+
+    fn path(&self, index: usize) -> PathBuf {
+        match self.part_size {
+            Some(_) => self.dir.join(part_name(&self.name, index)),
+            None => self.dir.join(&self.name),
+        }
+    }
+
+    fn part(&mut self, index: usize) -> io::Result<&mut File> {
+        while self.parts.len() <= index {
+            let path = self.path(self.parts.len());
+            self.parts.push(File::create(path)?);
+        }
+        Ok(&mut self.parts[index])
+    }
+
+    /// Chemins des parties écrites.
+    pub fn paths(&self) -> Vec<PathBuf> {
+        (0..self.parts.len()).map(|i| self.path(i)).collect()
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Vide les tampons et retourne les chemins des parties.
+    pub fn finish(mut self) -> io::Result<Vec<PathBuf>> {
+        for part in &mut self.parts {
+            part.sync_all()?;
+        }
+        Ok(self.paths())
+    }
+}
+
+impl Write for PartsWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let (index, offset, room) = match self.part_size {
+            Some(size) => ((self.position / size) as usize, self.position % size, (size - self.position % size) as usize),
+            None => (0, self.position, buf.len()),
+        };
+        let part = self.part(index)?;
+        part.seek(SeekFrom::Start(offset))?;
+        let written = part.write(&buf[..buf.len().min(room)])?;
+        self.position += written as u64;
+        self.len = self.len.max(self.position);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.parts.iter_mut().try_for_each(|part| part.flush())
+    }
+}
+
+impl Seek for PartsWriter {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.position = seek_target(pos, self.position, self.len)?;
+        Ok(self.position)
+    }
+}
+
+/// Lecture des parties comme un seul flux.
+pub struct PartsReader {
+    parts: Vec<(File, u64)>,
+    position: u64,
+    len: u64,
+}
+
+impl PartsReader {
+    pub fn open(paths: &[PathBuf]) -> io::Result<Self> {
+        let parts = paths.iter()
+            .map(|path| {
+                let file = File::open(path)?;
+                let len = file.metadata()?.len();
+                Ok((file, len))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        let len = parts.iter().map(|(_, len)| len).sum();
+        Ok(PartsReader { parts, position: 0, len })
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl Read for PartsReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut start = 0;
+        for (file, len) in &mut self.parts {
+            if self.position < start + *len {
+                let offset = self.position - start;
+                file.seek(SeekFrom::Start(offset))?;
+                let max = buf.len().min((*len - offset) as usize);
+                let read = file.read(&mut buf[..max])?;
+                self.position += read as u64;
+                return Ok(read);
+            }
+            start += *len;
+        }
+        Ok(0)
+    }
+}
+
+impl Seek for PartsReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.position = seek_target(pos, self.position, self.len)?;
+        Ok(self.position)
+    }
+}
+
+fn seek_target(pos: SeekFrom, position: u64, len: u64) -> io::Result<u64> {
+    let target = match pos {
+        SeekFrom::Start(n) => Some(n),
+        SeekFrom::Current(delta) => position.checked_add_signed(delta),
+        SeekFrom::End(delta) => len.checked_add_signed(delta),
+    };
+    target.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Seek before the start of the data"))
+}
@@ -0,0 +1,243 @@
+//! Signature ed25519 des exécutables forgés.
+//!
+//! Le forge ajoute une section `Signature` contenant un `SignatureBlock`.
+//! La signature porte sur `signed_digest` : le stub, puis chaque section
+//! (type, flags, taille et contenu). Quand un manifeste d'intégrité est
+//! présent, le contenu de l'archive n'est pas rehaché : il est couvert par
+//! les SHA-256 du manifeste, lui-même signé, et vérifié à l'extraction.
+//!
+//! Le stub compare la clé publique à un fichier de clés de confiance :
+//!
+//! ```text
+//! # commentaire
+//! policy = refuse
+//! 3b6a27bcceb6a42d62a3a8d02a6f0d73653215771de243a63ac048a18b59da29 equipe-qa
+//! ```
+
+use crate::container::{Container, Section, SectionKind};
+use crate::manifest::to_hex;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+pub const SIGNATURE_ALGORITHM: &str = "ed25519";
+
+/// Nom du fichier de clés de confiance dans le dossier de config `emuforge`.
+pub const TRUSTED_KEYS_FILE: &str = "trusted_keys";
+
+/// Variable d'environnement remplaçant le chemin du fichier de clés de confiance.
+pub const TRUSTED_KEYS_ENV: &str = "EMUFORGE_TRUSTED_KEYS";
+
+/// Variable d'environnement forçant la politique (`warn`, `refuse`, `ignore`).
+pub const POLICY_ENV: &str = "EMUFORGE_SIGNATURE_POLICY";
+
+/// Préfixe du condensat, pour ne jamais signer autre chose par accident.
+const DIGEST_DOMAIN: &[u8] = b"EMUFORGE-SIGNATURE-V1";
+
+/// Contenu JSON de la section `Signature`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignatureBlock {
+    pub algorithm: String,
+    /// Clé publique (32 octets, hexadécimal).
+    pub public_key: String,
+    /// Signature de `signed_digest` (64 octets, hexadécimal).
+    pub signature: String,
+}
+
+/// Comportement du stub face à un exécutable non signé ou non reconnu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SignaturePolicy {
+    /// Affiche un avertissement et lance quand même.
+    #[default]
+    Warn,
+    /// Refuse tout ce qui n'est pas signé par une clé de confiance.
+    Refuse,
+    /// Ne vérifie rien.
+    Ignore,
+}
+
+impl SignaturePolicy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "warn" => Some(SignaturePolicy::Warn),
+            "refuse" => Some(SignaturePolicy::Refuse),
+            "ignore" => Some(SignaturePolicy::Ignore),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrustedKey {
+    pub public_key: [u8; 32],
+    pub name: String,
+}
+
+/// Fichier de clés de confiance : une clé hexadécimale par ligne suivie
+/// d'un nom optionnel, et une ligne `policy = ...` optionnelle.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrustedKeys {
+    pub policy: Option<SignaturePolicy>,
+    pub keys: Vec<TrustedKey>,
+}
+
+impl TrustedKeys {
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut trusted = TrustedKeys::default();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(value) = line.strip_prefix("policy").and_then(|rest| rest.trim_start().strip_prefix('=')) {
+                let policy = SignaturePolicy::parse(value)
+                    .ok_or_else(|| format!("line {}: unknown policy '{}'", index + 1, value.trim()))?;
+                trusted.policy = Some(policy);
+                continue;
+            }
+            let (key, name) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let public_key = decode_key(key).ok_or_else(|| format!("line {}: invalid public key", index + 1))?;
+            trusted.keys.push(TrustedKey { public_key, name: name.trim().to_string() });
+        }
+        Ok(trusted)
+    }
+
+    /// Charge le fichier ; un fichier absent équivaut à une liste vide.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => Self::parse(&content).map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    /// Politique effective : variable d'environnement, puis fichier, puis `Warn`.
+    pub fn effective_policy(&self) -> SignaturePolicy {
+        std::env::var(POLICY_ENV)
+            .ok()
+            .and_then(|v| SignaturePolicy::parse(&v))
+            .or(self.policy)
+            .unwrap_or_default()
+    }
+
+    pub fn find(&self, public_key: &[u8; 32]) -> Option<&TrustedKey> {
+        self.keys.iter().find(|k| &k.public_key == public_key)
+    }
+}
+
+/// Résultat de la vérification d'un exécutable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verification {
+    Unsigned,
+    /// Signature valide d'une clé de confiance (nom de la clé).
+    Trusted(String),
+    /// Signature valide, mais clé inconnue (identifiant de la clé).
+    UntrustedKey(String),
+    /// Signature invalide : l'exécutable a été modifié après sa signature.
+    Invalid(String),
+}
+
+/// Identifiant court d'une clé publique (16 premiers caractères de son SHA-256).
+pub fn key_id(public_key: &[u8; 32]) -> String {
+    to_hex(&Sha256::digest(public_key))[..16].to_string()
+}
+
+/// Condensat signé : stub + sections, hors section `Signature`.
+pub fn signed_digest<R: Read + Seek>(reader: &mut R, payload_offset: u64, sections: &[Section]) -> io::Result<[u8; 32]> {
+    let mut hasher = Sha256::new();
+    hasher.update(DIGEST_DOMAIN);
+
+    reader.seek(SeekFrom::Start(0))?;
+    hash_range(reader, payload_offset, &mut hasher)?;
+
+    let has_manifest = sections.iter().any(|s| s.kind == SectionKind::Manifest);
+    for section in sections.iter().filter(|s| s.kind != SectionKind::Signature) {
+        hasher.update(section.kind.code().to_le_bytes());
+        hasher.update(section.flags.to_le_bytes());
+        hasher.update(section.len.to_le_bytes());
+        if section.kind == SectionKind::Archive && has_manifest {
+            continue;
+        }
+        reader.seek(SeekFrom::Start(section.offset))?;
+        hash_range(reader, section.len, &mut hasher)?;
+    }
+
+    Ok(hasher.finalize().into())
+}
+
+/// Vérifie la section `Signature` d'un conteneur.
+pub fn verify_container<R: Read + Seek>(reader: &mut R, container: &Container, trusted: &TrustedKeys) -> io::Result<Verification> {
+    let data = match container.read_section(reader, SectionKind::Signature)? {
+        Some(data) => data,
+        None => return Ok(Verification::Unsigned),
+    };
+    let block: SignatureBlock = match serde_json::from_slice(&data) {
+        Ok(block) => block,
+        Err(e) => return Ok(Verification::Invalid(format!("unreadable signature block: {}", e))),
+    };
+    if block.algorithm != SIGNATURE_ALGORITHM {
+        return Ok(Verification::Invalid(format!("unsupported algorithm '{}'", block.algorithm)));
+    }
+
+    let public_key = match decode_key(&block.public_key) {
+        Some(key) => key,
+        None => return Ok(Verification::Invalid("malformed public key".to_string())),
+    };
+    let signature = match from_hex(&block.signature).and_then(|b| <[u8; 64]>::try_from(b).ok()) {
+        Some(bytes) => Signature::from_bytes(&bytes),
+        None => return Ok(Verification::Invalid("malformed signature".to_string())),
+    };
+    let verifying_key = match VerifyingKey::from_bytes(&public_key) {
+        Ok(key) => key,
+        Err(_) => return Ok(Verification::Invalid("malformed public key".to_string())),
+    };
+
+    let digest = signed_digest(reader, container.payload_offset, &container.sections)?;
+    if verifying_key.verify(&digest, &signature).is_err() {
+        return Ok(Verification::Invalid("signature does not match content".to_string()));
+    }
+
+    Ok(match trusted.find(&public_key) {
+        Some(key) if !key.name.is_empty() => Verification::Trusted(key.name.clone()),
+        Some(_) => Verification::Trusted(key_id(&public_key)),
+        None => Verification::UntrustedKey(key_id(&public_key)),
+    })
+}
+
+pub fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    let hex = hex.trim();
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+fn decode_key(hex: &str) -> Option<[u8; 32]> {
+    from_hex(hex).and_then(|bytes| bytes.try_into().ok())
+}
+
+fn hash_range<R: Read>(reader: &mut R, len: u64, hasher: &mut Sha256) -> io::Result<()> {
+    let copied = io::copy(&mut reader.take(len), &mut HasherSink(hasher))?;
+    if copied != len {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Truncated section"));
+    }
+    Ok(())
+}
+
+struct HasherSink<'a>(&'a mut Sha256);
+
+impl io::Write for HasherSink<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+//! Modèles des variables d'environnement et arguments embarqués.
+//!
+//! `{variable}` est remplacé par le stub au lancement, avec les mêmes règles
+//! en mode portable et en mode raccourci ; `{{` et `}}` donnent des
+//! accolades littérales. Le forge valide chaque modèle : une variable
+//! inconnue ou une accolade orpheline est refusée avant d'écrire
+//! l'exécutable.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+/// Variables disponibles, avec leur valeur au lancement.
+pub const VARIABLES: &[(&str, &str)] = &[
+    ("exe_dir", "dossier d'extraction (portable) ou de l'exécutable (raccourci)"),
+    ("config_dir", "racine de config du plugin (portable), dossier de l'exécutable (raccourci)"),
+    ("cache_dir", "cache du jeu, ~/.cache/emuforge/<jeu>"),
+    ("save_dir", "sauvegardes persistantes, ~/.local/share/emuforge/saves/<jeu>"),
+    ("rom", "chemin absolu de la ROM"),
+    ("home", "dossier personnel de l'utilisateur"),
+    ("config_home", "configuration de l'utilisateur, ~/.config"),
+    ("data_home", "données de l'utilisateur, ~/.local/share"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    /// `{nom}` hors de `VARIABLES`
+    UnknownVariable(String),
+    /// Variable connue mais sans valeur dans ce contexte
+    Unavailable(String),
+    /// `{` sans `}` ou `}` isolée (à doubler pour un littéral)
+    UnbalancedBrace(String),
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::UnknownVariable(name) => write!(f, "variable inconnue {{{}}}", name),
+            TemplateError::Unavailable(name) => write!(f, "variable {{{}}} non définie", name),
+            TemplateError::UnbalancedBrace(template) => {
+                write!(f, "accolade non fermée dans \"{}\" ({{{{ et }}}} pour un littéral)", template)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+enum Part<'a> {
+    Text(&'a str),
+    Variable(&'a str),
+}
+
+fn parse(template: &str) -> Result<Vec<Part<'_>>, TemplateError> {
+    let unbalanced = || TemplateError::UnbalancedBrace(template.to_string());
+    let mut parts = Vec::new();
+    let mut rest = template;
+    while let Some(i) = rest.find(['{', '}']) {
+        parts.push(Part::Text(&rest[..i]));
+        let brace = &rest[i..i + 1];
+        if rest[i + 1..].starts_with(brace) {
+            parts.push(Part::Text(brace));
+            rest = &rest[i + 2..];
+        } else if brace == "}" {
+            return Err(unbalanced());
+        } else {
+            let end = rest[i + 1..].find('}').ok_or_else(unbalanced)?;
+            let name = &rest[i + 1..i + 1 + end];
+            if !VARIABLES.iter().any(|(known, _)| *known == name) {
+                return Err(TemplateError::UnknownVariable(name.to_string()));
+            }
+            parts.push(Part::Variable(name));
+            rest = &rest[i + end + 2..];
+        }
+    }
+    parts.push(Part::Text(rest));
+    Ok(parts)
+}
+
+/// Vérifie qu'un modèle ne référence que des variables connues.
+pub fn validate(template: &str) -> Result<(), TemplateError> {
+    parse(template).map(|_| ())
+}
+
+/// Texte littéral (chemin de l'utilisateur...) utilisable comme modèle.
+pub fn escape(text: &str) -> String {
+    text.replace('{', "{{").replace('}', "}}")
+}
+
+/// Valeurs des variables pour un lancement.
+#[derive(Debug, Clone, Default)]
+pub struct Variables {
+    values: HashMap<&'static str, String>,
+}
+
+impl Variables {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Définit une variable de `VARIABLES` (les autres noms sont ignorés).
+    pub fn set(&mut self, name: &str, path: impl AsRef<Path>) -> &mut Self {
+        if let Some((known, _)) = VARIABLES.iter().find(|(known, _)| *known == name) {
+            self.values.insert(known, path.as_ref().to_string_lossy().to_string());
+        }
+        self
+    }
+
+    pub fn render(&self, template: &str) -> Result<String, TemplateError> {
+        let mut out = String::with_capacity(template.len());
+        for part in parse(template)? {
+            match part {
+                Part::Text(text) => out.push_str(text),
+                Part::Variable(name) => {
+                    out.push_str(self.values.get(name).ok_or_else(|| TemplateError::Unavailable(name.to_string()))?)
+                }
+            }
+        }
+        Ok(out)
+    }
+}
@@ -0,0 +1,82 @@
+use emuforge_format::bundle::Bundle;
+use emuforge_format::container::{ContainerWriter, SectionKind};
+use emuforge_format::manifest::{hash_reader, IntegrityManifest};
+use std::fs::{self, File};
+use std::io::Write;
+use tempfile::tempdir;
+use zip::write::SimpleFileOptions;
+
+#[test]
+fn test_bundle_lists_and_extracts_without_launching() {
+    let temp_dir = tempdir().unwrap();
+    let exe = temp_dir.path().join("Game");
+
+    // 1. Stub + portable config + a small archive
+    let mut file = File::create(&exe).unwrap();
+    file.write_all(b"STUB").unwrap();
+    let mut writer = ContainerWriter::new(file).unwrap();
+    writer.add_section(SectionKind::PortableConfig, br#"{"game_name":"Game"}"#).unwrap();
+    writer.start_section(SectionKind::Archive).unwrap();
+    let mut zip = zip::ZipWriter::new(&mut writer);
+    zip.add_directory("config/", SimpleFileOptions::default()).unwrap();
+    zip.start_file("config/emu.ini", SimpleFileOptions::default()).unwrap();
+    zip.write_all(b"[General]\nfullscreen = true\n").unwrap();
+    zip.start_file("game.iso", SimpleFileOptions::default()).unwrap();
+    zip.write_all(&[7u8; 1000]).unwrap();
+    zip.finish().unwrap();
+    writer.end_section().unwrap();
+    writer.finish().unwrap();
+
+    // 2. Config and entries
+    let bundle = Bundle::open(&exe).unwrap().expect("No container");
+    assert!(bundle.is_portable());
+    assert_eq!(bundle.config().unwrap().unwrap()["game_name"], "Game");
+    let entries = bundle.entries().unwrap();
+    let files: Vec<_> = entries.iter().filter(|e| !e.is_dir).map(|e| (e.name.as_str(), e.size)).collect();
+    assert_eq!(files, [("config/emu.ini", 28), ("game.iso", 1000)]);
+
+    // 3. Extraction
+    let out = temp_dir.path().join("out");
+    assert_eq!(bundle.extract_to(&out).unwrap(), 2);
+    assert_eq!(fs::read(out.join("game.iso")).unwrap(), vec![7u8; 1000]);
+    assert!(fs::read_to_string(out.join("config/emu.ini")).unwrap().contains("fullscreen"));
+
+    // A plain file is not a bundle
+    fs::write(temp_dir.path().join("plain"), b"nothing here").unwrap();
+    assert!(Bundle::open(&temp_dir.path().join("plain")).unwrap().is_none());
+}
+
+#[test]
+fn test_extract_to_checks_the_manifest() {
+    let temp_dir = tempdir().unwrap();
+    let build = |name: &str, iso: &[u8]| {
+        let exe = temp_dir.path().join(name);
+        let mut manifest = IntegrityManifest::default();
+        let (sha256, size) = hash_reader(&mut &[7u8; 100][..]).unwrap();
+        manifest.add("game.iso", size, sha256);
+
+        let mut file = File::create(&exe).unwrap();
+        file.write_all(b"STUB").unwrap();
+        let mut writer = ContainerWriter::new(file).unwrap();
+        writer.add_section(SectionKind::PortableConfig, br#"{"game_name":"Game"}"#).unwrap();
+        writer.start_section(SectionKind::Archive).unwrap();
+        let mut zip = zip::ZipWriter::new(&mut writer);
+        zip.start_file("game.iso", SimpleFileOptions::default()).unwrap();
+        zip.write_all(iso).unwrap();
+        zip.finish().unwrap();
+        writer.end_section().unwrap();
+        writer.add_section(SectionKind::Manifest, &manifest.to_json().unwrap()).unwrap();
+        writer.finish().unwrap();
+        Bundle::open(&exe).unwrap().unwrap()
+    };
+
+    let good = build("Good", &[7u8; 100]);
+    assert!(good.manifest().unwrap().is_some());
+    assert_eq!(good.extract_to(&temp_dir.path().join("good")).unwrap(), 1);
+
+    // Valid ZIP (CRC32 included) whose content differs from what was forged
+    let tampered = build("Tampered", &[8u8; 100]);
+    let err = tampered.extract_to(&temp_dir.path().join("tampered")).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(err.to_string().contains("game.iso"), "{}", err);
+}
@@ -0,0 +1,60 @@
+use emuforge_format::config_merge::{is_mergeable, merge};
+
+#[test]
+fn test_merge_ini_keeps_user_values() {
+    let user = "[Graphics]\nResolution = 4\nVSync = false\n\n[Player]\nName = me\n";
+    let embedded = "[Graphics]\nResolution = 1\nVSync = true\nShaders = on\n";
+    let merged = merge("config/emu.ini", user, embedded).unwrap();
+
+    assert_eq!(
+        merged,
+        "[Graphics]\nResolution = 4\nVSync = false\nShaders = on\n\n[Player]\nName = me\n"
+    );
+}
+
+#[test]
+fn test_merge_ini_keeps_repeated_keys_apart() {
+    // PCSX2 lie chaque touche deux fois : clavier puis manette
+    let user = "[Pad1]\nUp = Keyboard/W\nUp = SDL-0/DPadUp\nDown = Keyboard/S\nDown = SDL-0/DPadDown\nDown = SDL-1/DPadDown\n";
+    let embedded = "[Pad1]\nUp = Keyboard/Up\nUp = SDL-0/DPadUp\nDown = Keyboard/Down\nDown = SDL-0/DPadDown\nLeft = Keyboard/Left\n";
+    let merged = merge("PCSX2.ini", user, embedded).unwrap();
+
+    assert_eq!(
+        merged,
+        "[Pad1]\nUp = Keyboard/W\nUp = SDL-0/DPadUp\nDown = Keyboard/S\nDown = SDL-0/DPadDown\nLeft = Keyboard/Left\nDown = SDL-1/DPadDown\n"
+    );
+}
+
+#[test]
+fn test_merge_toml_adds_user_keys() {
+    let user = "top = 1\n[video]\nscale = 3\nfilter = \"linear\"\n";
+    let embedded = "[video]\nscale = 2\nfullscreen = true\n";
+    let merged = merge("settings.toml", user, embedded).unwrap();
+
+    // Clé à la racine gardée en tête, clé ajoutée par le joueur dans sa section
+    assert_eq!(merged, "top = 1\n[video]\nscale = 3\nfullscreen = true\nfilter = \"linear\"\n");
+}
+
+#[test]
+fn test_merge_yaml_nested_keys() {
+    let user = "video:\n  scale: 3\n  backend: vulkan\naudio:\n  volume: 50\n";
+    let embedded = "video:\n  scale: 2\n  vsync: true\naudio:\n  volume: 100\n";
+    let merged = merge("config.yml", user, embedded).unwrap();
+
+    assert_eq!(merged, "video:\n  scale: 3\n  vsync: true\n  backend: vulkan\naudio:\n  volume: 50\n");
+}
+
+#[test]
+fn test_merge_rejects_unsupported_content() {
+    assert!(is_mergeable("a/b.INI"));
+    assert!(!is_mergeable("memcard.ps2"));
+    assert_eq!(merge("save.bin", "a=1", "a=2"), None);
+
+    // Listes YAML et tableaux de tables TOML : fichier du joueur gardé tel quel
+    assert_eq!(merge("games.yaml", "paths:\n  - /roms\n", "paths:\n  - /games\n"), None);
+    assert_eq!(merge("c.toml", "[[input]]\nport = 1\n", "[[input]]\nport = 2\n"), None);
+    assert_eq!(merge("c.toml", "keys = [\n  1,\n]\n", "keys = []\n"), None);
+
+    // Clé YAML dont le parent a disparu de la version embarquée
+    assert_eq!(merge("c.yml", "old:\n  key: 1\n", "new:\n  key: 2\n"), None);
+}
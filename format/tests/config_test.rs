@@ -0,0 +1,58 @@
+use emuforge_format::config::{LaunchConfig, PortableConfig, SCHEMA_VERSION};
+use emuforge_format::template::TemplateError;
+use std::path::PathBuf;
+
+#[test]
+fn test_config_roundtrip_is_versioned() {
+    let config = PortableConfig {
+        game_name: "Game".into(),
+        emulator_filename: "pcsx2".into(),
+        rom_filename: "game.iso".into(),
+        driver_id: "pcsx2".into(),
+        ..Default::default()
+    };
+    let json = config.to_json().unwrap();
+    let value: serde_json::Value = serde_json::from_slice(&json).unwrap();
+    assert_eq!(value["schema_version"], SCHEMA_VERSION);
+
+    let read = PortableConfig::from_json(&json).unwrap();
+    assert_eq!(read.rom_filename, "game.iso");
+    assert_eq!(read.driver_id, "pcsx2");
+}
+
+#[test]
+fn test_config_migrates_v0_and_refuses_newer() {
+    // Raccourci forgé avant driver_id : deviné d'après le chemin
+    let v0 = br#"{"emulator_path":"/opt/Ryujinx/Ryujinx","rom_path":"/games/zelda.nsp","bios_path":null,"args":[],"working_dir":null,"env_vars":[]}"#;
+    let config = LaunchConfig::from_json(v0).unwrap();
+    assert_eq!(config.driver_id, "ryujinx");
+    assert_eq!(config.rom_path, PathBuf::from("/games/zelda.nsp"));
+
+    let v0 = br#"{"game_name":"A","emulator_filename":"e","rom_filename":"r","config_dir":"config","fullscreen":false}"#;
+    assert_eq!(PortableConfig::from_json(v0).unwrap().driver_id, "generic");
+
+    // v1 : `./` relatif au dossier d'extraction, arguments sans modèles
+    let v1 = br#"{"schema_version":1,"game_name":"A","emulator_filename":"e","rom_filename":"r","config_dir":"./xemu_data","fullscreen":false,
+        "env_vars":[["XDG_DATA_HOME","./xemu_data"],["PCSX2_USER_PATH","{exe_dir}/pcsx2_data"]],"args_after_rom":["{raw}"],"driver_id":"xemu"}"#;
+    let config = PortableConfig::from_json(v1).unwrap();
+    assert_eq!(config.env_vars[0].1, "{exe_dir}/xemu_data");
+    assert_eq!(config.env_vars[1].1, "{exe_dir}/pcsx2_data");
+    assert_eq!(config.args_after_rom, ["{{raw}}"]);
+    assert_eq!(config.validate_templates(), Ok(()));
+
+    let newer = format!(r#"{{"schema_version":{},"game_name":"A"}}"#, SCHEMA_VERSION + 1);
+    let err = PortableConfig::from_json(newer.as_bytes()).unwrap_err();
+    assert!(err.to_string().contains("version"));
+}
+
+#[test]
+fn test_host_save_dirs_are_templates() {
+    let mut config = PortableConfig {
+        host_save_dirs: vec!["{config_home}/ppsspp/PSP/SAVEDATA".into(), "{data_home}/dolphin-emu/GC".into()],
+        ..Default::default()
+    };
+    assert_eq!(config.validate_templates(), Ok(()));
+
+    config.host_save_dirs.push("{xdg_state}/emu".into());
+    assert_eq!(config.validate_templates(), Err(TemplateError::UnknownVariable("xdg_state".into())));
+}
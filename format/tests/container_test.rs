@@ -0,0 +1,109 @@
+use emuforge_format::container::{Container, ContainerWriter, SectionKind, LEGACY_PORTABLE_MARKER};
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+
+#[test]
+fn test_container_roundtrip() {
+    let mut out = Cursor::new(Vec::new());
+    out.write_all(b"stub").unwrap();
+
+    let mut writer = ContainerWriter::new(out).unwrap();
+    writer.add_section(SectionKind::PortableConfig, b"{}").unwrap();
+    writer.start_section(SectionKind::Archive).unwrap();
+    writer.write_all(b"zip-").unwrap();
+    writer.write_all(b"data").unwrap();
+    writer.end_section().unwrap();
+    writer.add_section(SectionKind::Other(42), b"future").unwrap();
+    let mut out = writer.finish().unwrap();
+
+    let container = Container::read_from(&mut out).unwrap().expect("No container");
+    assert_eq!(container.version, emuforge_format::container::FORMAT_VERSION);
+    assert_eq!(container.payload_offset, 4);
+    assert_eq!(container.sections.len(), 3);
+
+    let archive = container.read_section(&mut out, SectionKind::Archive).unwrap().unwrap();
+    assert_eq!(archive, b"zip-data");
+    let future = container.read_section(&mut out, SectionKind::Other(42)).unwrap().unwrap();
+    assert_eq!(future, b"future");
+
+    // Bounded, in-place view of the archive
+    let mut reader = container.section_reader(&mut out, SectionKind::Archive).unwrap().unwrap();
+    assert_eq!(reader.len(), 8);
+    reader.seek(SeekFrom::End(-4)).unwrap();
+    let mut rest = Vec::new();
+    reader.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, b"data");
+    assert!(reader.seek(SeekFrom::Current(-9)).is_err());
+}
+
+#[test]
+fn test_seek_inside_open_section_is_relative() {
+    let mut out = Cursor::new(Vec::new());
+    out.write_all(b"stub").unwrap();
+
+    // Like a ZipWriter: write, go back to patch a header, return to the end
+    let mut writer = ContainerWriter::new(out).unwrap();
+    writer.add_section(SectionKind::PortableConfig, b"{}").unwrap();
+    writer.start_section(SectionKind::Archive).unwrap();
+    writer.write_all(b"HDR?body").unwrap();
+    assert_eq!(writer.seek(SeekFrom::Start(3)).unwrap(), 3);
+    writer.write_all(b"!").unwrap();
+    assert_eq!(writer.stream_position().unwrap(), 4);
+    assert!(writer.seek(SeekFrom::Current(-5)).is_err());
+    writer.end_section().unwrap();
+    writer.add_section(SectionKind::Manifest, b"m").unwrap();
+    let mut out = writer.finish().unwrap();
+
+    let container = Container::read_from(&mut out).unwrap().expect("No container");
+    let archive = container.read_section(&mut out, SectionKind::Archive).unwrap().unwrap();
+    assert_eq!(archive, b"HDR!body");
+    let manifest = container.read_section(&mut out, SectionKind::Manifest).unwrap().unwrap();
+    assert_eq!(manifest, b"m");
+}
+
+#[test]
+fn test_legacy_v2_executable_is_readable() {
+    // stub + marker + config_len + config + zip (format V2)
+    let mut data = b"stub".to_vec();
+    data.extend_from_slice(LEGACY_PORTABLE_MARKER);
+    data.extend_from_slice(&2u32.to_le_bytes());
+    data.extend_from_slice(b"{}");
+    data.extend_from_slice(b"PK-zip");
+
+    let mut reader = Cursor::new(data);
+    let container = Container::read_from(&mut reader).unwrap().expect("No container");
+    assert!(container.is_legacy());
+
+    let config = container.read_section(&mut reader, SectionKind::PortableConfig).unwrap().unwrap();
+    assert_eq!(config, b"{}");
+    let archive = container.read_section(&mut reader, SectionKind::Archive).unwrap().unwrap();
+    assert_eq!(archive, b"PK-zip");
+}
+
+#[test]
+fn test_plain_stub_has_no_container() {
+    let mut reader = Cursor::new(b"just a stub binary without payload".to_vec());
+    assert!(Container::read_from(&mut reader).unwrap().is_none());
+}
+
+#[test]
+fn test_overflowing_footer_is_rejected() {
+    let mut out = Cursor::new(Vec::new());
+    out.write_all(b"stub").unwrap();
+    let mut writer = ContainerWriter::new(out).unwrap();
+    writer.add_section(SectionKind::PortableConfig, b"{}").unwrap();
+    let data = writer.finish().unwrap().into_inner();
+    let toc_offset = 6;
+    let footer = data.len() - emuforge_format::container::FOOTER_LEN as usize;
+
+    // Section length wrapping past the end of the address space
+    let mut section = data.clone();
+    section[toc_offset + 16..toc_offset + 24].copy_from_slice(&(u64::MAX - 1).to_le_bytes());
+    let err = Container::read_from(&mut Cursor::new(section)).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    // Table offset for which offset + length overflows
+    let mut toc = data;
+    toc[footer + 8..footer + 16].copy_from_slice(&(u64::MAX - 8).to_le_bytes());
+    let err = Container::read_from(&mut Cursor::new(toc)).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
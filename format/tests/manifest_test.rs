@@ -0,0 +1,52 @@
+use emuforge_format::manifest::{hash_reader, HashingWriter, IntegrityError, IntegrityManifest};
+use std::collections::HashSet;
+use std::io::Write;
+
+#[test]
+fn test_manifest_detects_corruption() {
+    let mut writer = HashingWriter::new(Vec::new());
+    writer.write_all(b"game data").unwrap();
+    let (data, sha256, size) = writer.finalize();
+    assert_eq!(size, 9);
+
+    let mut manifest = IntegrityManifest::default();
+    manifest.add("game.iso", size, sha256.clone());
+    manifest.add("bios/scph.bin", 4, "00".repeat(32));
+    let manifest = IntegrityManifest::from_json(&manifest.to_json().unwrap()).unwrap();
+
+    // Same bytes re-read: OK
+    let (hash, len) = hash_reader(&mut data.as_slice()).unwrap();
+    assert_eq!(manifest.check("game.iso", len, &hash), Ok(()));
+
+    // One flipped byte
+    let (hash, len) = hash_reader(&mut &b"game dato"[..]).unwrap();
+    assert_eq!(manifest.check("game.iso", len, &hash), Err(IntegrityError::HashMismatch("game.iso".into())));
+
+    // Truncated archive: BIOS never seen
+    let seen: HashSet<String> = ["game.iso".to_string()].into();
+    assert_eq!(manifest.missing_from(&seen), vec![IntegrityError::Missing("bios/scph.bin".into())]);
+}
+
+#[test]
+fn test_component_id_depends_only_on_content() {
+    let mut first = IntegrityManifest::default();
+    first.add("Ryujinx/Ryujinx", 10, "aa".repeat(32));
+    first.add("Ryujinx/lib.so", 5, "bb".repeat(32));
+    first.add("game.nsp", 100, "cc".repeat(32));
+
+    // Another game, same emulator listed in another order
+    let mut second = IntegrityManifest::default();
+    second.add("other.xci", 200, "dd".repeat(32));
+    second.add("Ryujinx/lib.so", 5, "bb".repeat(32));
+    second.add("Ryujinx/Ryujinx", 10, "aa".repeat(32));
+
+    let id = first.add_component("Ryujinx", true);
+    assert_eq!(id, second.component_id("Ryujinx"));
+    assert_ne!(id, first.component_id("Ryujinx/lib.so"));
+    assert_eq!(first.component_of("Ryujinx/lib.so").map(|c| c.id.as_str()), Some(id.as_str()));
+    assert!(first.component_of("Ryujinx2/x").is_none());
+
+    // External entries are not expected in the archive
+    let seen: HashSet<String> = ["game.nsp".to_string()].into();
+    assert!(first.missing_from(&seen).is_empty());
+}
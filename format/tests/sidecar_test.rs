@@ -0,0 +1,66 @@
+use emuforge_format::manifest::hash_reader;
+use emuforge_format::sidecar::{PartsReader, PartsWriter, SidecarInfo};
+use std::fs;
+use std::io::{Read, Seek, SeekFrom, Write};
+use tempfile::tempdir;
+
+#[test]
+fn test_parts_roundtrip_and_locate_renamed_data() {
+    let temp_dir = tempdir().unwrap();
+    let dir = temp_dir.path();
+    let data: Vec<u8> = (0..2500u32).map(|i| (i % 251) as u8).collect();
+
+    // 1. Write with a seek back (as the ZIP writer does for local headers)
+    let mut writer = PartsWriter::create(dir, "Game.emudata", Some(1000)).unwrap();
+    writer.write_all(&[0u8; 10]).unwrap();
+    writer.write_all(&data[10..]).unwrap();
+    writer.seek(SeekFrom::Start(0)).unwrap();
+    writer.write_all(&data[..10]).unwrap();
+    let parts = writer.finish().unwrap();
+    assert_eq!(parts.len(), 3);
+    assert_eq!(fs::metadata(&parts[2]).unwrap().len(), 500);
+
+    let mut reader = PartsReader::open(&parts).unwrap();
+    let mut read = Vec::new();
+    reader.read_to_end(&mut read).unwrap();
+    assert_eq!(read, data);
+    reader.seek(SeekFrom::Start(995)).unwrap();
+    let mut across = [0u8; 10];
+    reader.read_exact(&mut across).unwrap();
+    assert_eq!(across, data[995..1005]);
+
+    // 2. Found by name next to the executable
+    let (sha256, size) = hash_reader(&mut PartsReader::open(&parts).unwrap()).unwrap();
+    let info = SidecarInfo { name: "Game.emudata".into(), size, sha256, part_size: Some(1000) };
+    let info = SidecarInfo::from_json(&info.to_json().unwrap()).unwrap();
+    let exe = dir.join("Game");
+    assert_eq!(info.locate(&exe).unwrap(), parts);
+
+    // 3. Renamed data: found by size and hash; a truncated part is not used
+    for (i, part) in parts.iter().enumerate() {
+        fs::rename(part, dir.join(format!("Renamed.emudata.{:03}", i + 1))).unwrap();
+    }
+    let found = info.locate(&exe).unwrap();
+    assert!(found[0].ends_with("Renamed.emudata.001"));
+
+    fs::write(dir.join("Renamed.emudata.003"), [0u8; 500]).unwrap();
+    assert_eq!(info.locate(&exe).unwrap_err().kind(), std::io::ErrorKind::NotFound);
+}
+
+#[test]
+fn test_locate_checks_the_hash_of_data_found_by_name() {
+    let temp_dir = tempdir().unwrap();
+    let dir = temp_dir.path();
+    let data = b"current data";
+    let (sha256, size) = hash_reader(&mut &data[..]).unwrap();
+    let info = SidecarInfo { name: "Game.emudata".into(), size, sha256, part_size: None };
+    let exe = dir.join("Game");
+
+    // Données d'un autre forge, de même nom et de même taille : refusées
+    fs::write(dir.join("Game.emudata"), b"stale   data").unwrap();
+    assert_eq!(info.locate(&exe).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+
+    // ... et les bonnes données, renommées, sont trouvées par la recherche
+    fs::write(dir.join("Copy.emudata"), data).unwrap();
+    assert_eq!(info.locate(&exe).unwrap(), [dir.join("Copy.emudata")]);
+}
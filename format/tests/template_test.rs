@@ -0,0 +1,24 @@
+use emuforge_format::template::{self, TemplateError, Variables};
+
+#[test]
+fn test_template_renders_and_escapes() {
+    let mut vars = Variables::new();
+    vars.set("exe_dir", "/cache/Game").set("rom", "/cache/Game/game.iso");
+
+    assert_eq!(vars.render("{exe_dir}/config").unwrap(), "/cache/Game/config");
+    assert_eq!(vars.render("--boot={rom}").unwrap(), "--boot=/cache/Game/game.iso");
+    assert_eq!(vars.render("{{exe_dir}} }}").unwrap(), "{exe_dir} }");
+    assert_eq!(vars.render(&template::escape("/home/me/{USA}")).unwrap(), "/home/me/{USA}");
+
+    // Connue mais absente de ce lancement
+    assert_eq!(vars.render("{save_dir}"), Err(TemplateError::Unavailable("save_dir".into())));
+}
+
+#[test]
+fn test_template_validation() {
+    assert_eq!(template::validate("{config_dir}/data"), Ok(()));
+    assert_eq!(template::validate("xcb"), Ok(()));
+    assert_eq!(template::validate("{exe_path}"), Err(TemplateError::UnknownVariable("exe_path".into())));
+    assert!(matches!(template::validate("{exe_dir"), Err(TemplateError::UnbalancedBrace(_))));
+    assert!(matches!(template::validate("a}b"), Err(TemplateError::UnbalancedBrace(_))));
+}
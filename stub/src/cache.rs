@@ -0,0 +1,221 @@
+//! Index du cache d'extraction partagé (`~/.cache/emuforge`) et éviction LRU.
+//!
+//! Chaque lancement met à jour la date d'utilisation et la taille du jeu dans
+//! `index.json`, puis les jeux les moins récemment utilisés sont supprimés
+//! tant que le cache dépasse la limite (`EMUFORGE_CACHE_LIMIT`, 20 Go par
+//! défaut). Le jeu en cours de lancement n'est jamais évincé ; ses sauvegardes
+//! vivent hors du cache (liens symboliques) et ne sont pas touchées. Les
+//! émulateurs partagés (`store/`) comptent dans la limite et sont supprimés
+//! quand plus aucun jeu ne les utilise.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::extract::game_lock_path;
+use crate::{lock, store};
+
+const INDEX_FILE: &str = "index.json";
+const INDEX_LOCK: &str = "index.lock";
+
+/// Limite du cache, ex: `20G`, `512M`, `0` pour désactiver l'éviction.
+pub const CACHE_LIMIT_ENV: &str = "EMUFORGE_CACHE_LIMIT";
+const DEFAULT_LIMIT: u64 = 20 * 1024 * 1024 * 1024;
+
+#[derive(Serialize, Deserialize, Default)]
+struct CacheIndex {
+    games: HashMap<String, CachedGame>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+struct CachedGame {
+    /// Secondes depuis l'epoch Unix
+    last_used: u64,
+    size: u64,
+}
+
+/// Résultat d'un passage du ramasse-miettes.
+pub struct GcReport {
+    pub evicted: Vec<(String, u64)>,
+    pub total: u64,
+    pub limit: u64,
+}
+
+pub fn cache_root() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join("emuforge")
+}
+
+/// Limite configurée en octets (`0` = illimité).
+pub fn size_limit() -> u64 {
+    match std::env::var(CACHE_LIMIT_ENV) {
+        Ok(value) => parse_size(&value).unwrap_or_else(|| {
+            eprintln!("⚠️ {}={} invalide, limite par défaut utilisée", CACHE_LIMIT_ENV, value);
+            DEFAULT_LIMIT
+        }),
+        Err(_) => DEFAULT_LIMIT,
+    }
+}
+
+pub fn parse_size(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let (digits, unit) = match value.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+        Some((i, _)) => value.split_at(i),
+        None => (value, ""),
+    };
+    let multiplier: u64 = match unit.trim().to_ascii_uppercase().trim_end_matches(['B', 'O']) {
+        "" => 1,
+        "K" => 1 << 10,
+        "M" => 1 << 20,
+        "G" => 1 << 30,
+        "T" => 1 << 40,
+        _ => return None,
+    };
+    digits.parse::<u64>().ok()?.checked_mul(multiplier)
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+fn load_index(root: &Path) -> CacheIndex {
+    fs::read(root.join(INDEX_FILE))
+        .ok()
+        .and_then(|data| serde_json::from_slice(&data).ok())
+        .unwrap_or_default()
+}
+
+fn lock_index(root: &Path) -> io::Result<lock::Lock> {
+    lock::exclusive(&root.join(INDEX_LOCK), "L'index du cache")
+}
+
+fn save_index(root: &Path, index: &CacheIndex) -> io::Result<()> {
+    let temp = root.join(format!("{}.tmp", INDEX_FILE));
+    fs::write(&temp, serde_json::to_vec_pretty(index)?)?;
+    fs::rename(temp, root.join(INDEX_FILE))
+}
+
+/// Taille d'un dossier, sans suivre les liens (sauvegardes, magasin partagé).
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else { return 0 };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(t) if t.is_dir() => dir_size(&entry.path()),
+            Ok(t) if t.is_file() => entry.metadata().map_or(0, |m| m.len()),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Marque `game` comme utilisé maintenant et met à jour sa taille.
+pub fn record_use(root: &Path, game: &str) -> io::Result<()> {
+    let _lock = lock_index(root)?;
+    let mut index = load_index(root);
+    let size = dir_size(&root.join(game));
+    index.games.insert(game.to_string(), CachedGame { last_used: now(), size });
+    save_index(root, &index)
+}
+
+/// Passe le verrou d'un jeu en partagé (voir `lock::Lock::downgrade`) sous
+/// le verrou de l'index : l'éviction et `--clean`, qui le détiennent pendant
+/// toute leur passe, ne voient jamais le jeu sans verrou.
+pub fn downgrade_game_lock(root: &Path, game_lock: lock::Lock) -> io::Result<lock::Lock> {
+    let _lock = lock_index(root)?;
+    game_lock.downgrade()
+}
+
+/// Supprime le dossier de `game` et le retire de l'index (`--clean`).
+/// `Ok(false)` si une autre instance du jeu est en cours.
+pub fn remove_game(root: &Path, game: &str) -> io::Result<bool> {
+    let _lock = lock_index(root)?;
+    let Some(_game_lock) = lock::try_exclusive(&game_lock_path(root, game))? else {
+        return Ok(false);
+    };
+    fs::remove_dir_all(root.join(game))?;
+    let mut index = load_index(root);
+    if index.games.remove(game).is_some() {
+        save_index(root, &index)?;
+    }
+    Ok(true)
+}
+
+/// Évince les jeux les moins récemment utilisés jusqu'à repasser sous `limit`.
+/// Les dossiers absents de l'index (extractions antérieures) y sont ajoutés
+/// avec leur date de modification. Un jeu en cours de partie ou
+/// d'extraction (verrouillé) n'est jamais évincé.
+pub fn collect_garbage(root: &Path, limit: u64, protected: Option<&str>) -> io::Result<GcReport> {
+    if !root.is_dir() {
+        return Ok(GcReport { evicted: vec![], total: 0, limit });
+    }
+    let _lock = lock_index(root)?;
+    let mut index = load_index(root);
+    let mut present = HashMap::new();
+    for entry in fs::read_dir(root)?.flatten() {
+        // Dossiers de préparation (`.<jeu>.staging`) et magasin partagé exclus
+        let is_game = entry.file_type().is_ok_and(|t| t.is_dir())
+            && entry.file_name() != store::STORE_DIR
+            && !entry.file_name().to_string_lossy().starts_with('.');
+        if !is_game {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        let game = index.games.get(&name).copied().unwrap_or_else(|| CachedGame {
+            last_used: entry.metadata().ok()
+                .and_then(|m| m.modified().ok())
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |d| d.as_secs()),
+            size: dir_size(&entry.path()),
+        });
+        present.insert(name, game);
+    }
+    index.games = present;
+
+    // Composants partagés dont plus aucun jeu ne dépend (jeux nettoyés)
+    store::prune(root)?;
+    let mut total: u64 = index.games.values().map(|g| g.size).sum::<u64>() + store::size(root);
+    let mut evicted = Vec::new();
+    if limit > 0 && total > limit {
+        let mut candidates: Vec<(String, CachedGame)> = index.games.iter()
+            .filter(|(name, _)| Some(name.as_str()) != protected)
+            .map(|(name, game)| (name.clone(), *game))
+            .collect();
+        candidates.sort_by_key(|(_, game)| game.last_used);
+        for (name, game) in candidates {
+            if total <= limit {
+                break;
+            }
+            let Some(_game_lock) = lock::try_exclusive(&game_lock_path(root, &name))? else {
+                eprintln!("⏳ {} en cours d'utilisation, non évincé", name);
+                continue;
+            };
+            match fs::remove_dir_all(root.join(&name)) {
+                Ok(()) => {
+                    index.games.remove(&name);
+                    total -= game.size;
+                    total -= store::prune(root)?;
+                    evicted.push((name, game.size));
+                }
+                Err(e) => eprintln!("⚠️ Impossible d'évincer {}: {}", name, e),
+            }
+        }
+    }
+
+    save_index(root, &index)?;
+    Ok(GcReport { evicted, total, limit })
+}
+
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["o", "Ko", "Mo", "Go"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 { format!("{} {}", bytes, UNITS[0]) } else { format!("{:.1} {}", value, UNITS[unit]) }
+}
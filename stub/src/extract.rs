@@ -0,0 +1,462 @@
+//! Extraction de l'archive embarquée dans le cache : fichiers inchangés
+//! conservés, configs du joueur préservées ou fusionnées, sauvegardes
+//! déplacées hors du cache.
+
+use emuforge_format::bundle::{self, EmbeddedArchive};
+use emuforge_format::config::PortableConfig;
+use emuforge_format::config_merge;
+use emuforge_format::container::{Container, SectionKind};
+use emuforge_format::manifest::{HashingWriter, IntegrityError, IntegrityManifest};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::store;
+
+/// Marqueur d'extraction : contient l'état des fichiers extraits.
+pub const EXTRACTION_STATE_FILE: &str = ".emuforge_extracted";
+
+/// Taille, CRC32 et date de modification de chaque fichier extrait, pour ne
+/// réextraire que ce qui a changé au lancement suivant.
+#[derive(Serialize, Deserialize, Default)]
+pub struct ExtractionState {
+    pub entries: HashMap<String, ExtractedEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ExtractedEntry {
+    pub size: u64,
+    pub crc32: u32,
+    pub mtime: u64,
+}
+
+/// Verrou d'un dossier de jeu, à côté de celui-ci (il survit au renommage)
+pub fn game_lock_path(cache_base: &Path, game_name: &str) -> PathBuf {
+    cache_base.join(format!("{}.lock", game_name))
+}
+
+/// Dossier de préparation d'une première extraction, à côté du dossier du jeu.
+pub fn staging_dir(cache_base: &Path, game_name: &str) -> PathBuf {
+    cache_base.join(format!(".{}.staging", game_name))
+}
+
+/// Vrai tant qu'aucune extraction complète n'a été publiée pour ce jeu.
+pub fn is_first_launch(target_dir: &Path) -> bool {
+    !target_dir.join(EXTRACTION_STATE_FILE).exists()
+}
+
+/// Extrait le jeu dans `cache_base/<jeu>`, verrou du jeu détenu par l'appelant.
+///
+/// La première extraction se fait dans un dossier de préparation, renommé
+/// une fois complet : un stub tué en cours de route ne laisse jamais un
+/// dossier de jeu à moitié écrit, et le lancement suivant reprend en gardant
+/// les fichiers complets. Les mises à jour remplacent chaque fichier
+/// atomiquement, l'état n'est écrit qu'à la fin.
+pub fn extract_game(
+    exe_path: &Path,
+    container: &Container,
+    cache_base: &Path,
+    config: &PortableConfig,
+    reset_config: bool,
+) -> io::Result<()> {
+    let target_dir = cache_base.join(&config.game_name);
+    let staging_dir = staging_dir(cache_base, &config.game_name);
+    let first_launch = is_first_launch(&target_dir);
+    let extract_dir = if first_launch { &staging_dir } else { &target_dir };
+    fs::create_dir_all(extract_dir)?;
+
+    // Saves live outside the disposable cache (before extraction, so that
+    // default saves shipped in the archive land in the persistent directory)
+    link_save_paths(extract_dir, &persistent_saves_dir(&config.game_name), config);
+
+    extract_embedded_archive(exe_path, container, extract_dir, config, reset_config)?;
+    if first_launch {
+        publish_staging(&staging_dir, &target_dir)?;
+    }
+    Ok(())
+}
+
+/// Met en place une première extraction complète. Un dossier de jeu sans
+/// état d'extraction (extraction interrompue d'une ancienne version) est remplacé.
+pub fn publish_staging(staging_dir: &Path, target_dir: &Path) -> io::Result<()> {
+    if fs::symlink_metadata(target_dir).is_ok() {
+        fs::remove_dir_all(target_dir)?;
+    }
+    fs::rename(staging_dir, target_dir)
+}
+
+/// Extract the embedded ZIP archive from the executable, checking every
+/// extracted entry against the integrity manifest when there is one before
+/// putting it in place. Entries already on disk (same size and CRC32, and
+/// the manifest's SHA-256 when the state does not vouch for them) are skipped, and
+/// user data modified since the last extraction is preserved unless
+/// `reset_config` is set. Existing saves are never overwritten.
+pub fn extract_embedded_archive(
+    exe_path: &Path,
+    container: &Container,
+    target_dir: &Path,
+    config: &PortableConfig,
+    reset_config: bool,
+) -> io::Result<()> {
+    let manifest = read_manifest(exe_path, container)?;
+    if manifest.is_none() {
+        eprintln!("⚠️ Pas de manifeste d'intégrité (exécutable forgé par une ancienne version)");
+    }
+    
+    let state_path = target_dir.join(EXTRACTION_STATE_FILE);
+    let previous: ExtractionState = fs::read(&state_path)
+        .ok()
+        .and_then(|data| serde_json::from_slice(&data).ok())
+        .unwrap_or_default();
+    let mut state = ExtractionState::default();
+    
+    let mut archive = open_embedded_archive(exe_path, container)?;
+    let mut seen = HashSet::new();
+    let mut errors = Vec::new();
+    let mut skipped = 0;
+    let mut preserved = 0;
+    
+    // Composants partagés (émulateur) : une copie dans le magasin commun, liée ici
+    if let Some(manifest) = &manifest {
+        let cache_root = target_dir.parent().unwrap_or(target_dir);
+        for component in &manifest.components {
+            let (object, _lock) = store::ensure_component(cache_root, component, manifest, &mut archive, exe_path)?;
+            store::link_component(&object, target_dir, &component.path)?;
+        }
+    }
+    
+    for i in 0..archive.len() {
+        let mut out_file = archive.by_index(i)?;
+        let outpath = match out_file.enclosed_name() {
+            Some(path) => target_dir.join(path),
+            None => continue,
+        };
+        
+        if let Some(component) = manifest.as_ref().and_then(|m| m.component_of(out_file.name().trim_end_matches('/'))) {
+            if !component.external && !out_file.is_dir() {
+                seen.insert(out_file.name().to_string());
+            }
+            continue;
+        }
+        
+        if out_file.name().ends_with('/') {
+            fs::create_dir_all(&outpath)?;
+            continue;
+        }
+        
+        let name = out_file.name().to_string();
+        if manifest.is_some() {
+            seen.insert(name.clone());
+        }
+        
+        if is_save_path(&name, config) && outpath.exists() {
+            continue;
+        }
+        
+        if let Some(entry) = unchanged_entry(&outpath, &name, out_file.size(), out_file.crc32(), previous.entries.get(&name), manifest.as_ref()) {
+            state.entries.insert(name, entry);
+            skipped += 1;
+            continue;
+        }
+        
+        // Config modifiée par le joueur depuis la dernière extraction
+        let modified_by_user = previous.entries.get(&name)
+            .filter(|prev| is_user_data(&name, config) && user_modified(&outpath, prev));
+        if let (Some(prev), false) = (modified_by_user, reset_config) {
+            if prev.crc32 != out_file.crc32() {
+                // Nouvelle version embarquée : fusion clé par clé si possible
+                let mut embedded = HashingWriter::new(Vec::new());
+                io::copy(&mut out_file, &mut embedded)?;
+                let (embedded, sha256, size) = embedded.finalize();
+                // Contenu non vérifié : le fichier du joueur reste intact
+                if let Some(Err(e)) = manifest.as_ref().map(|m| m.check(&name, size, &sha256)) {
+                    errors.push(e);
+                    continue;
+                }
+                merge_user_file(&name, &outpath, &embedded)?;
+            }
+            // mtime 0 : le fichier reste considéré comme modifié par le joueur
+            state.entries.insert(name, ExtractedEntry { size: out_file.size(), crc32: out_file.crc32(), mtime: 0 });
+            preserved += 1;
+            continue;
+        }
+        
+        if let Some(p) = outpath.parent() {
+            if !p.exists() {
+                fs::create_dir_all(p)?;
+            }
+        }
+        
+        eprintln!("📄 Extraction: {} ({} bytes)", name, out_file.size());
+        
+        // Écrit à côté puis renommé : jamais de fichier à moitié écrit à sa place
+        let dest = link_target(&outpath);
+        let partial = partial_path(&dest);
+        let mut outfile = HashingWriter::new(File::create(&partial)?);
+        io::copy(&mut out_file, &mut outfile)?;
+        let (_, sha256, size) = outfile.finalize();
+        
+        // Vérifié avant d'être mis en place : un fichier refusé ne doit pas
+        // être repris comme inchangé par une extraction suivante
+        if let Some(Err(e)) = manifest.as_ref().map(|m| m.check(&name, size, &sha256)) {
+            let _ = fs::remove_file(&partial);
+            errors.push(e);
+            continue;
+        }
+        
+        // Set executable permissions if needed (Linux)
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            if let Some(mode) = out_file.unix_mode() {
+                fs::set_permissions(&partial, fs::Permissions::from_mode(mode)).ok();
+            }
+        }
+        fs::rename(&partial, &dest)?;
+        
+        state.entries.insert(name, ExtractedEntry { size, crc32: out_file.crc32(), mtime: mtime_of(&outpath) });
+    }
+    
+    if skipped > 0 {
+        eprintln!("⚡ {} fichier(s) déjà à jour, non réextrait(s)", skipped);
+    }
+    if preserved > 0 {
+        eprintln!("📝 {} fichier(s) modifié(s) par le joueur conservé(s) (--reset-config pour restaurer)", preserved);
+    }
+    if let Some(manifest) = &manifest {
+        errors.extend(manifest.missing_from(&seen));
+    }
+    if !errors.is_empty() {
+        return Err(integrity_error(&errors));
+    }
+    
+    let data = serde_json::to_vec(&state).map_err(io::Error::other)?;
+    let partial = partial_path(&state_path);
+    fs::write(&partial, data)?;
+    fs::rename(&partial, &state_path)
+}
+
+pub fn partial_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".emuforge-partial");
+    PathBuf::from(name)
+}
+
+/// Cible d'un lien de sauvegarde (le fichier est écrit dans le dossier persistant)
+fn link_target(path: &Path) -> PathBuf {
+    match fs::read_link(path) {
+        Ok(target) => path.parent().map_or(target.clone(), |parent| parent.join(&target)),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+/// Retourne l'état de `path` s'il correspond déjà à l'entrée `name` : taille
+/// et CRC32, plus le SHA-256 du manifeste quand il y en a un. Le fichier
+/// n'est relu que s'il a changé depuis la dernière extraction vérifiée.
+pub fn unchanged_entry(
+    path: &Path,
+    name: &str,
+    size: u64,
+    crc32: u32,
+    previous: Option<&ExtractedEntry>,
+    manifest: Option<&IntegrityManifest>,
+) -> Option<ExtractedEntry> {
+    let metadata = fs::metadata(path).ok()?;
+    if !metadata.is_file() || metadata.len() != size {
+        return None;
+    }
+    let current = ExtractedEntry { size, crc32, mtime: mtime_of(path) };
+    if previous == Some(&current) {
+        return Some(current);
+    }
+    
+    let mut file = File::open(path).ok()?;
+    let mut hasher = crc32fast::Hasher::new();
+    let mut sha256 = HashingWriter::new(io::sink());
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let n = file.read(&mut buffer).ok()?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+        if manifest.is_some() {
+            sha256.write_all(&buffer[..n]).ok()?;
+        }
+    }
+    let (_, sha256, read) = sha256.finalize();
+    let verified = manifest.is_none_or(|m| m.check(name, read, &sha256).is_ok());
+    (hasher.finalize() == crc32 && verified).then_some(current)
+}
+
+/// Dossier persistant des sauvegardes d'un jeu, hors du cache d'extraction.
+pub fn persistent_saves_dir(game_name: &str) -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("emuforge")
+        .join("saves")
+        .join(game_name)
+}
+
+fn is_save_path(name: &str, config: &PortableConfig) -> bool {
+    config.save_files.iter().any(|f| f == name)
+        || config.save_dirs.iter().any(|d| name.strip_prefix(d.as_str()).is_some_and(|rest| rest.starts_with('/')))
+}
+
+/// Remplace chaque emplacement de sauvegarde du dossier d'extraction par un
+/// lien vers `saves_dir`. Les sauvegardes déjà présentes dans le cache
+/// (versions précédentes) y sont d'abord déplacées.
+pub fn link_save_paths(target_dir: &Path, saves_dir: &Path, config: &PortableConfig) {
+    let paths = config.save_dirs.iter().map(|p| (p, true)).chain(config.save_files.iter().map(|p| (p, false)));
+    for (relative, is_dir) in paths {
+        let link = target_dir.join(relative);
+        let persistent = saves_dir.join(relative);
+        if let Err(e) = link_save_path(&link, &persistent, is_dir) {
+            eprintln!("⚠️ Sauvegardes laissées dans le cache ({}): {}", relative, e);
+        }
+    }
+    if !config.save_dirs.is_empty() || !config.save_files.is_empty() {
+        eprintln!("💾 Sauvegardes: {:?}", saves_dir);
+    }
+}
+
+fn link_save_path(link: &Path, persistent: &Path, is_dir: bool) -> io::Result<()> {
+    match fs::symlink_metadata(link) {
+        Ok(meta) if meta.file_type().is_symlink() => {
+            if fs::read_link(link)? == persistent {
+                return Ok(());
+            }
+            fs::remove_file(link)?;
+        }
+        Ok(meta) if meta.is_dir() => {
+            move_merge(link, persistent)?;
+            fs::remove_dir_all(link)?;
+        }
+        Ok(_) => {
+            if persistent.exists() {
+                fs::remove_file(link)?;
+            } else {
+                move_merge(link, persistent)?;
+            }
+        }
+        Err(_) => {}
+    }
+    
+    if is_dir {
+        fs::create_dir_all(persistent)?;
+    } else if let Some(parent) = persistent.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if let Some(parent) = link.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(persistent, link)?;
+    #[cfg(windows)]
+    {
+        if is_dir {
+            std::os::windows::fs::symlink_dir(persistent, link)?;
+        } else {
+            std::os::windows::fs::symlink_file(persistent, link)?;
+        }
+    }
+    Ok(())
+}
+
+/// Déplace `src` vers `dst` sans écraser ce qui existe déjà dans `dst`.
+fn move_merge(src: &Path, dst: &Path) -> io::Result<()> {
+    if src.is_dir() {
+        fs::create_dir_all(dst)?;
+        for entry in fs::read_dir(src)? {
+            let entry = entry?;
+            move_merge(&entry.path(), &dst.join(entry.file_name()))?;
+        }
+        return Ok(());
+    }
+    if dst.exists() {
+        return Ok(());
+    }
+    if let Some(parent) = dst.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // rename échoue entre deux systèmes de fichiers : copie dans ce cas
+    if fs::rename(src, dst).is_err() {
+        fs::copy(src, dst)?;
+        fs::remove_file(src)?;
+    }
+    Ok(())
+}
+
+/// Les fichiers du dossier de config appartiennent au joueur une fois
+/// extraits ; tout le reste (émulateur, ROM, pistes) est toujours restauré.
+pub fn is_user_data(name: &str, config: &PortableConfig) -> bool {
+    let dir = config.config_dir.trim_start_matches("./").trim_matches('/');
+    !dir.is_empty() && dir != "." && name.strip_prefix(dir).is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Vrai si le fichier a changé sur disque depuis que le stub l'a écrit.
+fn user_modified(path: &Path, previous: &ExtractedEntry) -> bool {
+    match fs::metadata(path) {
+        Ok(metadata) => metadata.len() != previous.size || mtime_of(path) != previous.mtime,
+        Err(_) => false,
+    }
+}
+
+/// Fusionne le fichier du joueur avec la nouvelle version embarquée, ou le
+/// garde tel quel si son format ne le permet pas.
+fn merge_user_file(name: &str, path: &Path, embedded: &[u8]) -> io::Result<()> {
+    let user = fs::read(path)?;
+    let merged = match (std::str::from_utf8(&user), std::str::from_utf8(embedded)) {
+        (Ok(user), Ok(embedded)) if config_merge::is_mergeable(name) => config_merge::merge(name, user, embedded),
+        _ => None,
+    };
+    match merged {
+        Some(merged) => {
+            eprintln!("🔀 {}: réglages du joueur fusionnés avec la nouvelle config", name);
+            let dest = link_target(path);
+            let partial = partial_path(&dest);
+            fs::write(&partial, merged)?;
+            fs::rename(&partial, &dest)
+        }
+        None => {
+            eprintln!("📝 {}: modifié par le joueur, conservé", name);
+            Ok(())
+        }
+    }
+}
+
+pub fn mtime_of(path: &Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_nanos() as u64)
+}
+
+/// Open the archive section in place, through a bounded reader over the executable,
+/// or the `.emudata` data file found next to it
+pub fn open_embedded_archive(exe_path: &Path, container: &Container) -> io::Result<EmbeddedArchive> {
+    bundle::open_archive(exe_path, container)
+}
+
+/// Read the integrity manifest section, if the forge wrote one
+pub fn read_manifest(exe_path: &Path, container: &Container) -> io::Result<Option<IntegrityManifest>> {
+    let mut file = File::open(exe_path)?;
+    match container.read_section(&mut file, SectionKind::Manifest)? {
+        Some(data) => IntegrityManifest::from_json(&data)
+            .map(Some)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("Manifeste d'intégrité illisible: {}", e))),
+        None => Ok(None),
+    }
+}
+
+pub fn integrity_error(errors: &[IntegrityError]) -> io::Error {
+    let details: Vec<String> = errors.iter().map(|e| format!("  - {}", e)).collect();
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{} fichier(s) corrompu(s):\n{}", errors.len(), details.join("\n")),
+    )
+}
+
@@ -0,0 +1,239 @@
+//! Journal de lancement par jeu et rapports de crash.
+//!
+//! En release le stub n'a pas de console (`windows_subsystem = "windows"`) :
+//! tout ce qui compte pour un diagnostic est écrit dans
+//! `<data>/emuforge/logs/<jeu>/launch.log` (commande résolue, environnement,
+//! stderr de l'émulateur, code de sortie). Si l'émulateur échoue, le journal,
+//! la configuration et l'environnement sont réunis dans un `crash-<date>.zip`
+//! à joindre à une demande d'aide.
+
+use std::fmt::Display;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::signals;
+
+pub const LOG_FILE: &str = "launch.log";
+/// Au-delà, le journal est renommé en `launch.log.1` (jusqu'à `MAX_ROTATED`).
+pub const MAX_LOG_SIZE: u64 = 1024 * 1024;
+pub const MAX_ROTATED: u32 = 3;
+pub const MAX_CRASH_REPORTS: usize = 5;
+
+/// Variables d'environnement de l'hôte utiles au diagnostic (le reste peut
+/// contenir des secrets et n'est pas recopié).
+const HOST_ENV: &[&str] = &[
+    "DISPLAY", "WAYLAND_DISPLAY", "XDG_SESSION_TYPE", "XDG_CURRENT_DESKTOP", "DESKTOP_SESSION",
+    "LANG", "LC_ALL", "SDL_VIDEODRIVER", "QT_QPA_PLATFORM", "APPIMAGE", "container",
+];
+
+pub struct LaunchLog {
+    game: String,
+    dir: PathBuf,
+    file: Option<File>,
+}
+
+pub fn logs_dir(game: &str) -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("emuforge")
+        .join("logs")
+        .join(game)
+}
+
+impl LaunchLog {
+    /// Ouvre (et fait tourner si besoin) le journal du jeu. Sans journal
+    /// possible, le lancement continue avec stderr seulement.
+    pub fn open(game: &str) -> LaunchLog {
+        let dir = logs_dir(game);
+        let file = (|| {
+            fs::create_dir_all(&dir)?;
+            rotate(&dir)?;
+            OpenOptions::new().create(true).append(true).open(dir.join(LOG_FILE))
+        })();
+        let file = match file {
+            Ok(file) => Some(file),
+            Err(e) => {
+                eprintln!("⚠️ Journal de lancement indisponible ({:?}): {}", dir, e);
+                None
+            }
+        };
+        let mut log = LaunchLog { game: game.to_string(), dir, file };
+        log.line(format!("==== {} — stub {} ({}/{})", game, env!("CARGO_PKG_VERSION"), std::env::consts::OS, std::env::consts::ARCH));
+        log
+    }
+
+    pub fn path(&self) -> PathBuf {
+        self.dir.join(LOG_FILE)
+    }
+
+    /// Ligne horodatée dans le journal.
+    pub fn line(&mut self, message: impl Display) {
+        if let Some(file) = &mut self.file {
+            let _ = writeln!(file, "[{}] {}", utc_timestamp(SystemTime::now()), message);
+        }
+    }
+
+    /// Lance l'émulateur (dans son propre groupe, signaux relayés) en
+    /// recopiant son stderr dans le journal et sur celui du stub, puis
+    /// consigne son code de sortie.
+    pub fn run(&mut self, cmd: &mut Command) -> io::Result<ExitStatus> {
+        self.line(format!("Commande: {:?}", cmd.get_program()));
+        for arg in cmd.get_args() {
+            self.line(format!("  arg: {:?}", arg));
+        }
+        if let Some(dir) = cmd.get_current_dir() {
+            self.line(format!("  cwd: {:?}", dir));
+        }
+        for (key, value) in cmd.get_envs() {
+            self.line(format!("  env: {}={}", key.to_string_lossy(), value.map_or("<supprimée>".into(), |v| v.to_string_lossy())));
+        }
+
+        cmd.stderr(Stdio::piped());
+        signals::prepare(cmd);
+        let mut child: Child = match cmd.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                self.line(format!("Échec du lancement: {}", e));
+                return Err(e);
+            }
+        };
+        signals::attach(child.id());
+        self.line(format!("Émulateur démarré (pid {})", child.id()));
+
+        // Recopie ligne à ligne : le journal reste utile si le stub est tué
+        let stderr = child.stderr.take();
+        let mut sink = self.file.as_ref().and_then(|f| f.try_clone().ok());
+        let copier = std::thread::spawn(move || {
+            let Some(stderr) = stderr else { return };
+            for line in BufReader::new(stderr).split(b'\n').map_while(Result::ok) {
+                let line = String::from_utf8_lossy(&line);
+                eprintln!("{}", line);
+                if let Some(file) = &mut sink {
+                    let _ = writeln!(file, "[emu] {}", line);
+                }
+            }
+        });
+
+        let status = child.wait();
+        let _ = copier.join();
+        match &status {
+            Ok(status) => self.line(format!("Émulateur terminé: {}", describe(status))),
+            Err(e) => self.line(format!("Attente de l'émulateur impossible: {}", e)),
+        }
+        status
+    }
+
+    /// Réunit journal, configuration et environnement dans une archive.
+    pub fn crash_report(&mut self, config: &impl serde::Serialize, cmd: &Command) -> io::Result<PathBuf> {
+        if let Some(file) = &mut self.file {
+            file.flush()?;
+        }
+        let stamp = utc_timestamp(SystemTime::now()).replace([' ', ':'], "-");
+        let path = self.dir.join(format!("crash-{}.zip", stamp.trim_end_matches('Z')));
+
+        let mut zip = zip::ZipWriter::new(File::create(&path)?);
+        let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        zip.start_file(LOG_FILE, options)?;
+        if let Ok(mut log) = File::open(self.path()) {
+            io::copy(&mut log, &mut zip)?;
+        }
+        zip.start_file("config.json", options)?;
+        zip.write_all(serde_json::to_string_pretty(config)?.as_bytes())?;
+        zip.start_file("environment.txt", options)?;
+        zip.write_all(environment_summary(&self.game, cmd).as_bytes())?;
+        zip.finish()?;
+
+        self.line(format!("Rapport de crash: {:?}", path));
+        prune_crash_reports(&self.dir);
+        Ok(path)
+    }
+}
+
+/// Renomme `launch.log` en `launch.log.1` (les précédents décalés) s'il est trop gros.
+pub fn rotate(dir: &Path) -> io::Result<()> {
+    let current = dir.join(LOG_FILE);
+    if fs::metadata(&current).map_or(true, |m| m.len() < MAX_LOG_SIZE) {
+        return Ok(());
+    }
+    for n in (1..MAX_ROTATED).rev() {
+        let from = dir.join(format!("{}.{}", LOG_FILE, n));
+        if from.exists() {
+            fs::rename(&from, dir.join(format!("{}.{}", LOG_FILE, n + 1)))?;
+        }
+    }
+    fs::rename(current, dir.join(format!("{}.1", LOG_FILE)))
+}
+
+/// Ne garde que les `MAX_CRASH_REPORTS` rapports de crash les plus récents.
+pub fn prune_crash_reports(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    let mut reports: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.file_name().is_some_and(|n| n.to_string_lossy().starts_with("crash-")))
+        .collect();
+    // Les noms sont horodatés : l'ordre alphabétique est chronologique
+    reports.sort();
+    let excess = reports.len().saturating_sub(MAX_CRASH_REPORTS);
+    for old in &reports[..excess] {
+        let _ = fs::remove_file(old);
+    }
+}
+
+fn environment_summary(game: &str, cmd: &Command) -> String {
+    let mut text = format!(
+        "game: {}\nstub: {}\nos: {} {}\nexe: {:?}\n\n[emulator]\n{:?}\n",
+        game,
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        std::env::current_exe().ok(),
+        cmd,
+    );
+    text.push_str("\n[host]\n");
+    for key in HOST_ENV {
+        if let Ok(value) = std::env::var(key) {
+            text.push_str(&format!("{}={}\n", key, value));
+        }
+    }
+    if let Ok(release) = fs::read_to_string("/etc/os-release") {
+        text.push_str("\n[os-release]\n");
+        text.push_str(&release);
+    }
+    text
+}
+
+/// Code de sortie lisible (signal sous Unix).
+pub fn describe(status: &ExitStatus) -> String {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return format!("tué par le signal {}", signal);
+        }
+    }
+    match status.code() {
+        Some(code) => format!("code {}", code),
+        None => "code inconnu".to_string(),
+    }
+}
+
+/// `AAAA-MM-JJ HH:MM:SSZ` (UTC), sans dépendance de date.
+pub fn utc_timestamp(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let (days, rest) = ((secs / 86400) as i64, secs % 86400);
+    // Conversion jours -> date civile (algorithme de H. Hinnant)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02}Z", year, month, day, rest / 3600, rest / 60 % 60, rest % 60)
+}
@@ -0,0 +1,10 @@
+//! Logique du stub partagée avec ses tests : extraction dans le cache,
+//! magasin de composants, sauvegardes, journal de lancement.
+
+pub mod cache;
+pub mod extract;
+pub mod launch_log;
+pub mod lock;
+pub mod saves;
+pub mod signals;
+pub mod store;
@@ -0,0 +1,55 @@
+//! Verrous consultatifs (`flock` / `LockFileEx`) entre stubs lancés en même
+//! temps : extraction d'un jeu, magasin partagé, index du cache.
+//!
+//! Le verrou est libéré à la fermeture du fichier, y compris si le stub est tué.
+
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io;
+use std::path::Path;
+
+pub struct Lock {
+    file: File,
+}
+
+fn open(path: &Path) -> io::Result<File> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    OpenOptions::new().create(true).truncate(false).write(true).open(path)
+}
+
+/// Verrou exclusif ; attend (en le signalant) si un autre stub le détient.
+pub fn exclusive(path: &Path, what: &str) -> io::Result<Lock> {
+    let file = open(path)?;
+    match file.try_lock() {
+        Ok(()) => {}
+        Err(TryLockError::WouldBlock) => {
+            eprintln!("⏳ {} est utilisé par un autre lancement, attente...", what);
+            file.lock()?;
+        }
+        Err(TryLockError::Error(e)) => return Err(e),
+    }
+    Ok(Lock { file })
+}
+
+/// Verrou exclusif sans attente : `None` s'il est détenu ailleurs.
+pub fn try_exclusive(path: &Path) -> io::Result<Option<Lock>> {
+    let file = open(path)?;
+    match file.try_lock() {
+        Ok(()) => Ok(Some(Lock { file })),
+        Err(TryLockError::WouldBlock) => Ok(None),
+        Err(TryLockError::Error(e)) => Err(e),
+    }
+}
+
+impl Lock {
+    /// Passe en verrou partagé : d'autres lancements du même jeu peuvent
+    /// démarrer, mais le dossier ne peut plus être évincé ni nettoyé. Le
+    /// verrou est brièvement relâché : pour un dossier de jeu, passer par
+    /// `cache::downgrade_game_lock`.
+    pub fn downgrade(self) -> io::Result<Lock> {
+        self.file.unlock()?;
+        self.file.lock_shared()?;
+        Ok(self)
+    }
+}
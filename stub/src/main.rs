@@ -3,140 +3,355 @@
 
 mod ryujinx_input;
 
-use serde::Deserialize;
+use emuforge_format::bundle::{self, Bundle};
+use emuforge_format::config::{LaunchConfig, PortableConfig};
+use emuforge_format::container::{Container, SectionKind};
+use emuforge_format::manifest::hash_reader;
+use emuforge_format::signature::{self, SignaturePolicy, TrustedKeys, Verification};
+use emuforge_format::template::Variables;
+use emuforge_stub::extract::{
+    extract_game, game_lock_path, integrity_error, is_first_launch, open_embedded_archive, persistent_saves_dir,
+    read_manifest, staging_dir, EXTRACTION_STATE_FILE,
+};
+use emuforge_stub::launch_log::{self, LaunchLog};
+use emuforge_stub::{cache, lock, saves, signals};
+use serde::Serialize;
+use std::collections::HashSet;
 use std::env;
+use std::ffi::OsString;
 use std::fs::{self, File};
-use std::io::{self, Read};
-use std::path::PathBuf;
-use std::process::Command;
-
-/// Unique binary marker (not a string) to avoid false positives in the binary
-const PORTABLE_MARKER: &[u8] = &[0xDE, 0xAD, 0xBE, 0xEF, 0x45, 0x4D, 0x55, 0x46, 0x4F, 0x52, 0x47, 0x45, 0x56, 0x32];
-
-// Include the config generated by build.rs
-include!(concat!(env!("OUT_DIR"), "/config_gen.rs"));
-
-#[derive(Deserialize)]
-#[allow(dead_code)]
-struct LaunchConfig {
-    emulator_path: PathBuf,
-    rom_path: PathBuf,
-    #[allow(dead_code)]
-    bios_path: Option<PathBuf>,
-    args: Vec<String>,
-    #[serde(default)]
-    args_after_rom: Vec<String>,
-    working_dir: Option<PathBuf>,
-    env_vars: Vec<(String, String)>,
-}
-
-#[derive(Deserialize)]
-struct PortableConfig {
-    game_name: String,
-    emulator_filename: String,
-    rom_filename: String,
-    config_dir: String,
-    #[allow(dead_code)]
-    fullscreen: bool,
-    #[serde(default)]
-    env_vars: Vec<(String, String)>,
-    #[serde(default)]
-    args_before_rom: Vec<String>,
-    #[serde(default)]
-    args_after_rom: Vec<String>,
-    #[serde(default)]
-    driver_id: String,  // Identifiant du plugin (ryujinx, pcsx2, etc.)
-}
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus};
 
 fn main() {
-    // Check if we're in portable mode by looking for the marker in ourselves
+    // Read the container appended to ourselves by the forge
     let exe_path = env::current_exe().expect("Failed to get current exe path");
+    let container = match Container::open(&exe_path) {
+        Ok(Some(container)) => container,
+        Ok(None) => {
+            eprintln!("❌ Aucune configuration embarquée: ce stub est un modèle, il doit être forgé par EmuForge.");
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("❌ Exécutable corrompu: {}", e);
+            std::process::exit(1);
+        }
+    };
+    
+    // Publisher signature, checked before anything is extracted
+    let (trusted, trusted_path) = load_trusted_keys();
+    let policy = trusted.effective_policy();
+    
+    if has_flag("--gc") {
+        std::process::exit(run_gc_mode());
+    }
+    
+    // Inspection: nothing is extracted or launched
+    let extract_to = arg_value("--extract-to");
+    if has_flag("--info") || has_flag("--list") {
+        let code = run_inspect_mode(&Bundle { path: exe_path.clone(), container: container.clone() });
+        if code != 0 || extract_to.is_none() {
+            std::process::exit(code);
+        }
+    }
+    
+    if has_flag("--verify") {
+        let verification = verify_signature(&exe_path, &container, &trusted, &trusted_path);
+        let rejected = matches!(verification, Verification::Invalid(_))
+            || (policy == SignaturePolicy::Refuse && !matches!(verification, Verification::Trusted(_)));
+        let code = run_verify_mode(&exe_path, &container);
+        std::process::exit(if rejected { 1 } else { code });
+    }
+    if policy != SignaturePolicy::Ignore {
+        let verification = verify_signature(&exe_path, &container, &trusted, &trusted_path);
+        if policy == SignaturePolicy::Refuse && !matches!(verification, Verification::Trusted(_)) {
+            eprintln!("❌ Lancement refusé par la politique de signature (refuse).");
+            std::process::exit(1);
+        }
+    }
     
-    if let Some(portable_config) = check_portable_mode(&exe_path) {
-        run_portable_mode(exe_path, portable_config);
+    // Extraction only once the signature policy has accepted the executable
+    if let Some(dir) = extract_to {
+        std::process::exit(run_extract_mode(&Bundle { path: exe_path, container }, &dir));
+    }
+    
+    let saves_action = ["--export-saves", "--import-saves"].into_iter().find_map(|flag| Some((flag, arg_value(flag)?)));
+    
+    if let Some(portable_config) = read_config(&exe_path, &container, SectionKind::PortableConfig, PortableConfig::from_json) {
+        if let Some((flag, file)) = saves_action {
+            std::process::exit(run_saves_mode(&portable_config, flag, &file));
+        }
+        run_portable_mode(exe_path, &container, portable_config);
+    } else if saves_action.is_some() {
+        eprintln!("❌ --export-saves / --import-saves ne concernent que les exécutables portables.");
+        std::process::exit(1);
+    } else if let Some(launch_config) = read_config(&exe_path, &container, SectionKind::LaunchConfig, LaunchConfig::from_json) {
+        run_launcher_mode(launch_config);
     } else {
-        run_launcher_mode();
+        eprintln!("❌ Configuration embarquée illisible.");
+        std::process::exit(1);
     }
 }
 
-/// Check if the executable contains embedded portable data
-fn check_portable_mode(exe_path: &PathBuf) -> Option<PortableConfig> {
-    let mut file = File::open(exe_path).ok()?;
-    
-    // We only need to read the beginning of the file to find the marker
-    // because the marker is appended directly after the stub binary.
-    // 10MB is more than enough for any stub binary.
-    let mut buffer = vec![0u8; 10 * 1024 * 1024]; 
-    let bytes_read = file.read(&mut buffer).ok()?;
-    buffer.truncate(bytes_read);
-    
-    // Search for marker starting from the END of the buffer to find the REAL one
-    if let Some(pos) = buffer.windows(PORTABLE_MARKER.len()).rposition(|window| window == PORTABLE_MARKER) {
-        // Marker found! Read the config JSON that follows
-        let config_start = pos + PORTABLE_MARKER.len();
-        
-        // Read 4 bytes for config length
-        if buffer.len() < config_start + 4 {
-            return None;
-        }
-        let config_len = u32::from_le_bytes([
-            buffer[config_start],
-            buffer[config_start + 1],
-            buffer[config_start + 2],
-            buffer[config_start + 3],
-        ]) as usize;
-        
-        let config_data_start = config_start + 4;
-        if buffer.len() < config_data_start + config_len {
-            return None;
+/// Arguments destinés au stub : ceux qui suivent `--` vont à l'émulateur.
+fn stub_args() -> Vec<String> {
+    env::args().skip(1).take_while(|a| a != "--").collect()
+}
+
+fn has_flag(flag: &str) -> bool {
+    stub_args().iter().any(|a| a == flag)
+}
+
+/// Arguments après `--`, transmis tels quels à l'émulateur.
+fn passthrough_args() -> Vec<OsString> {
+    env::args_os().skip(1).skip_while(|a| a != "--").skip(1).collect()
+}
+
+/// Préfixe des variables de l'appelant qui surchargent celles embarquées :
+/// `EMUFORGE_ENV_FOO=bar` donne `FOO=bar` à l'émulateur, une valeur vide la retire.
+const ENV_OVERRIDE_PREFIX: &str = "EMUFORGE_ENV_";
+
+fn apply_env_overrides(cmd: &mut Command) {
+    for (key, value) in env::vars_os() {
+        let Some(name) = key.to_str().and_then(|k| k.strip_prefix(ENV_OVERRIDE_PREFIX)) else { continue };
+        if name.is_empty() {
+            continue;
         }
-        
-        let config_json = &buffer[config_data_start..config_data_start + config_len];
-        serde_json::from_slice::<PortableConfig>(config_json).ok()
+        if value.is_empty() {
+            eprintln!("🔧 ENV (surcharge): {} retirée", name);
+            cmd.env_remove(name);
+        } else {
+            eprintln!("🔧 ENV (surcharge): {} = {}", name, value.to_string_lossy());
+            cmd.env(name, value);
+        }
+    }
+}
+
+/// Valeur de `--option <valeur>` ou `--option=<valeur>`
+fn arg_value(flag: &str) -> Option<PathBuf> {
+    let args = stub_args();
+    args.iter().enumerate().find_map(|(i, arg)| {
+        if arg == flag {
+            args.get(i + 1).map(PathBuf::from)
+        } else {
+            arg.strip_prefix(flag)?.strip_prefix('=').map(PathBuf::from)
+        }
+    })
+}
+
+/// `--info` and `--list`: describe the bundle without launching
+fn run_inspect_mode(bundle: &Bundle) -> i32 {
+    let result = (|| -> io::Result<()> {
+        if has_flag("--info") {
+            println!("Format: v{}{}", bundle.container.version, if bundle.container.is_legacy() { " (legacy)" } else { "" });
+            println!("Mode: {}", if bundle.is_portable() { "portable" } else { "raccourci" });
+            for section in &bundle.container.sections {
+                println!("Section {:?}: {} bytes", section.kind, section.len);
+            }
+            if let Some(sidecar) = bundle.sidecar()? {
+                println!("Données: {} ({} bytes, {} partie(s))", sidecar.name, sidecar.size, sidecar.part_names(&sidecar.name).len());
+                match sidecar.locate(&bundle.path) {
+                    Ok(parts) => parts.iter().for_each(|part| println!("  {}", part.display())),
+                    Err(e) => println!("  ⚠️ {}", e),
+                }
+            }
+            if let Some(config) = bundle.config()? {
+                println!("{}", serde_json::to_string_pretty(&config)?);
+            }
+        }
+        if has_flag("--list") {
+            let entries = bundle.entries()?;
+            let mut total = 0;
+            for entry in entries.iter().filter(|e| !e.is_dir) {
+                println!("{:>12}  {:>12}  {}", entry.size, entry.compressed_size, entry.name);
+                total += entry.size;
+            }
+            println!("{:>12}  {:>12}  {} fichier(s)", total, "", entries.iter().filter(|e| !e.is_dir).count());
+        }
+        Ok(())
+    })();
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            1
+        }
+    }
+}
+
+/// `--extract-to <dir>`: unpack the bundle, checking each file against the manifest
+fn run_extract_mode(bundle: &Bundle, dir: &Path) -> i32 {
+    if bundle.manifest().ok().flatten().is_none() {
+        eprintln!("⚠️ Pas de manifeste d'intégrité: seuls les CRC32 du ZIP seront vérifiés.");
+    }
+    match bundle.extract_to(dir) {
+        Ok(count) => {
+            eprintln!("📦 {} fichier(s) extrait(s) dans {:?}", count, dir);
+            0
+        }
+        Err(e) => {
+            eprintln!("❌ --extract-to: {}", e);
+            1
+        }
+    }
+}
+
+/// `--gc`: evict least recently used games until the cache fits its size cap
+fn run_gc_mode() -> i32 {
+    let root = cache::cache_root();
+    match cache::collect_garbage(&root, cache::size_limit(), None) {
+        Ok(report) => {
+            for (game, size) in &report.evicted {
+                eprintln!("🧹 {} évincé ({})", game, cache::format_size(*size));
+            }
+            let limit = if report.limit == 0 { "illimité".to_string() } else { cache::format_size(report.limit) };
+            eprintln!("✅ Cache {:?}: {} / {}", root, cache::format_size(report.total), limit);
+            0
+        }
+        Err(e) => {
+            eprintln!("❌ --gc: {}", e);
+            1
+        }
+    }
+}
+
+/// `--export-saves <file>` / `--import-saves <file>`
+fn run_saves_mode(config: &PortableConfig, flag: &str, file: &Path) -> i32 {
+    let result = if flag == "--export-saves" {
+        saves::export_saves(config, file).map(|n| eprintln!("💾 {} fichier(s) de sauvegarde exporté(s) vers {:?}", n, file))
     } else {
-        None
+        saves::import_saves(config, file).map(|n| eprintln!("💾 {} fichier(s) de sauvegarde importé(s) depuis {:?}", n, file))
+    };
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("❌ {}: {}", flag, e);
+            1
+        }
+    }
+}
+
+/// Load the trusted keys file (EMUFORGE_TRUSTED_KEYS or <config>/emuforge/trusted_keys)
+fn load_trusted_keys() -> (TrustedKeys, PathBuf) {
+    let path = env::var_os(signature::TRUSTED_KEYS_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            dirs::config_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("emuforge")
+                .join(signature::TRUSTED_KEYS_FILE)
+        });
+    match TrustedKeys::load(&path) {
+        Ok(trusted) => (trusted, path),
+        Err(e) => {
+            eprintln!("⚠️ Fichier de clés de confiance illisible: {}", e);
+            (TrustedKeys::default(), path)
+        }
+    }
+}
+
+/// Verify the ed25519 signature and report the result
+fn verify_signature(exe_path: &Path, container: &Container, trusted: &TrustedKeys, trusted_path: &Path) -> Verification {
+    let verification = File::open(exe_path)
+        .and_then(|mut file| signature::verify_container(&mut file, container, trusted))
+        .unwrap_or_else(|e| Verification::Invalid(e.to_string()));
+    
+    match &verification {
+        Verification::Trusted(name) => eprintln!("🔏 Signé par: {}", name),
+        Verification::Unsigned => eprintln!("⚠️ Exécutable non signé."),
+        Verification::UntrustedKey(key_id) => {
+            eprintln!("⚠️ Signé par une clé inconnue ({}), absente de {:?}", key_id, trusted_path)
+        }
+        Verification::Invalid(reason) => {
+            eprintln!("❌ Signature invalide: {}. L'exécutable a été modifié après sa création !", reason)
+        }
     }
+    verification
 }
 
-/// Find subsequence in buffer
+/// Read and parse (migrating older schemas) a JSON config section of the container
+fn read_config<T>(exe_path: &Path, container: &Container, kind: SectionKind, parse: fn(&[u8]) -> serde_json::Result<T>) -> Option<T> {
+    let mut file = File::open(exe_path).ok()?;
+    let config_json = container.read_section(&mut file, kind).ok()??;
+    match parse(&config_json) {
+        Ok(config) => Some(config),
+        Err(e) => {
+            eprintln!("⚠️ Config {:?} invalide: {}", kind, e);
+            None
+        }
+    }
+}
 
 /// Run in portable mode - extract and launch
-fn run_portable_mode(exe_path: PathBuf, config: PortableConfig) {
+fn run_portable_mode(exe_path: PathBuf, container: &Container, config: PortableConfig) {
     // Determine cache directory
-    let cache_base = dirs::cache_dir()
-        .unwrap_or_else(|| PathBuf::from("/tmp"))
-        .join("emuforge");
+    let cache_base = cache::cache_root();
     let target_dir = cache_base.join(&config.game_name);
+    let mut log = LaunchLog::open(&config.game_name);
+    log.line(format!("Exécutable: {:?} (mode portable, émulateur {})", exe_path, config.driver_id));
+    
+    // Les fichiers déjà extraits et inchangés sont conservés : seuls les
+    // nouveaux ou modifiés sont (ré)extraits. Les configs modifiées par le
+    // joueur sont gardées (ou fusionnées), sauf avec --reset-config.
+    let reset_config = has_flag("--reset-config");
     
-    // Check if already extracted
-    let marker_file = target_dir.join(".emuforge_extracted");
-    let needs_extraction = !marker_file.exists();
+    // Un seul stub extrait un jeu donné à la fois : les autres attendent
+    let lock_path = game_lock_path(&cache_base, &config.game_name);
+    let game_lock = lock::exclusive(&lock_path, &config.game_name)
+        .unwrap_or_else(|e| abort_launch(&mut log, format!("Verrouillage du cache impossible: {}", e)));
     
-    if needs_extraction {
+    let first_launch = is_first_launch(&target_dir);
+    let staging_dir = staging_dir(&cache_base, &config.game_name);
+    if first_launch {
         eprintln!("🎮 Préparation du jeu: {}...", config.game_name);
         eprintln!("📁 Dossier de données: {:?}", target_dir);
-        
-        // Create cache directory
-        fs::create_dir_all(&target_dir).expect("Failed to create cache directory");
-        
-        // Extract the embedded zip archive
-        if let Err(e) = extract_embedded_archive(&exe_path, &target_dir) {
-            eprintln!("❌ Erreur d'extraction: {}", e);
-            std::process::exit(1);
+        if staging_dir.exists() {
+            eprintln!("♻️ Extraction interrompue détectée, reprise (les fichiers complets sont conservés)");
         }
-        
-        // Create marker file
-        let _ = File::create(&marker_file);
+    } else if reset_config {
+        eprintln!("♻️ --reset-config: restauration des configs embarquées");
+    }
+    
+    if let Err(e) = extract_game(&exe_path, container, &cache_base, &config, reset_config) {
+        if e.kind() == io::ErrorKind::InvalidData {
+            let _ = fs::remove_file(target_dir.join(EXTRACTION_STATE_FILE));
+            let _ = fs::remove_dir_all(&staging_dir);
+            refuse_corrupted(&e);
+        }
+        if first_launch {
+            abort_launch(&mut log, format!("Erreur d'extraction: {}", e));
+        }
+        eprintln!("❌ Erreur d'extraction: {}", e);
+        log.line(format!("Erreur d'extraction: {}", e));
+    } else if first_launch {
         eprintln!("✅ Extraction terminée !");
-    } else {
-        // Even if already extracted, we RE-EXTRACT ONLY THE CONFIG to ensure resolution settings
-        // are updated if the user changed them.
-        let _ = extract_embedded_archive(&exe_path, &target_dir);
     }
     
-    // Build paths to extracted files
-    let mut emulator_path = target_dir.join(&config.emulator_filename);
-    let rom_path = target_dir.join(&config.rom_filename);
+    // Pendant la partie : verrou partagé (le dossier ne peut pas être évincé)
+    let game_lock = cache::downgrade_game_lock(&cache_base, game_lock)
+        .unwrap_or_else(|e| abort_launch(&mut log, format!("Verrouillage du cache impossible: {}", e)));
+    
+    // Keep the shared cache under its size cap (never evicting this game)
+    if let Err(e) = cache::record_use(&cache_base, &config.game_name) {
+        eprintln!("⚠️ Index du cache non mis à jour: {}", e);
+    }
+    match cache::collect_garbage(&cache_base, cache::size_limit(), Some(&config.game_name)) {
+        Ok(report) => {
+            for (game, size) in &report.evicted {
+                eprintln!("🧹 Cache plein: {} évincé ({})", game, cache::format_size(*size));
+            }
+        }
+        Err(e) => eprintln!("⚠️ Nettoyage du cache impossible: {}", e),
+    }
+    
+    // Build paths to extracted files (AppImage: emulator and ROM stay in the mounted image)
+    let payload_dir = match exe_path.parent() {
+        Some(app_dir) if config.appdir => app_dir.to_path_buf(),
+        _ => target_dir.clone(),
+    };
+    let mut emulator_path = payload_dir.join(&config.emulator_filename);
+    let rom_path = payload_dir.join(&config.rom_filename);
     let config_path = target_dir.join(&config.config_dir);
     
     // If emulator is a directory (e.g., extracted AppImage squashfs-root), use AppRun inside it
@@ -175,8 +390,7 @@ fn run_portable_mode(exe_path: PathBuf, config: PortableConfig) {
     }
     
     // Check for --clean argument
-    let args: Vec<String> = env::args().collect();
-    let clean_mode = args.contains(&"--clean".to_string());
+    let clean_mode = has_flag("--clean");
     
     // CHECK PERMISSIONS BEFORE LAUNCH
     eprintln!("🔍 DEBUG: Checking executable before launch: {:?}", emulator_path);
@@ -200,6 +414,11 @@ fn run_portable_mode(exe_path: PathBuf, config: PortableConfig) {
         eprintln!("   ❌ Invalid path (metadata failed)");
     }
 
+    // Modèles de la config : {exe_dir} est le dossier d'extraction
+    let templates = config.env_vars.iter().map(|(_, v)| v).chain(&config.args_before_rom).chain(&config.args_after_rom);
+    let vars = template_variables(&config.game_name, &target_dir, &config_path, &rom_path, templates);
+    let env_vars = render_env(&vars, &config.env_vars);
+    
     // === DÉTECTION DYNAMIQUE DES MANETTES POUR RYUJINX ===
     // Utilise driver_id de la config portable (plus fiable que deviner à partir du nom)
     if config.driver_id == "ryujinx" {
@@ -230,23 +449,7 @@ fn run_portable_mode(exe_path: PathBuf, config: PortableConfig) {
         let profile_index = if has_gamepad { 0 } else { 1 };
         
         // Resolve config path dynamically from env vars (like in launcher mode)
-        let mut config_home = None;
-        for (key, val) in &config.env_vars {
-             if key == "XDG_CONFIG_HOME" {
-                 // We need to resolve the placeholder manually here as we haven't launched the process yet
-                 let resolved_val = val
-                     .replace("{config_dir}", &config_path.to_string_lossy())
-                     .replace("{exe_dir}", &target_dir.to_string_lossy());
-                 // Handle relative paths
-                 let final_path = if resolved_val.starts_with("./") {
-                     target_dir.join(&resolved_val).to_string_lossy().to_string()
-                 } else {
-                     resolved_val
-                 };
-                 config_home = Some(PathBuf::from(final_path));
-                 break;
-             }
-        }
+        let config_home = env_vars.iter().find(|(key, _)| key == "XDG_CONFIG_HOME").map(|(_, val)| PathBuf::from(val));
 
         // Use resolved path or fallback to previous hardcoded guess (though incorrect)
         let config_file = if let Some(home) = config_home {
@@ -283,28 +486,23 @@ fn run_portable_mode(exe_path: PathBuf, config: PortableConfig) {
     
     // === GENERIC LAUNCH LOGIC (from plugin config) ===
     
-    // Apply environment variables from config (with path substitution)
-    for (key, value) in &config.env_vars {
-        // Substitute {config_dir} placeholder with actual path
-        let resolved_value = value
-            .replace("{config_dir}", &config_path.to_string_lossy())
-            .replace("{exe_dir}", &target_dir.to_string_lossy());
-        
-        // Handle relative paths ONLY if they start with ./
-        // This prevents strings like "xcb" or "wayland" from being treated as paths
-        let final_value = if resolved_value.starts_with("./") {
-            target_dir.join(&resolved_value).to_string_lossy().to_string()
-        } else {
-            resolved_value
-        };
-        
-        eprintln!("🔧 ENV: {} = {}", key, final_value);
-        cmd.env(key, &final_value);
+    // Apply environment variables from config (templates already resolved)
+    for (key, value) in &env_vars {
+        eprintln!("🔧 ENV: {} = {}", key, value);
+        cmd.env(key, value);
+    }
+    apply_env_overrides(&mut cmd);
+    
+    // Arguments de l'appelant (`exe -- ...`) : en tête, car les arguments du
+    // plugin peuvent se terminer par une option qui attend la ROM (-e, -dvd_path)
+    let extra_args = passthrough_args();
+    if !config.extra_args_after_rom {
+        cmd.args(&extra_args);
     }
     
     // Add args before ROM
     for arg in &config.args_before_rom {
-        cmd.arg(arg);
+        cmd.arg(render(&vars, arg));
     }
     
     // Add ROM path
@@ -312,103 +510,183 @@ fn run_portable_mode(exe_path: PathBuf, config: PortableConfig) {
     
     // Add args after ROM
     for arg in &config.args_after_rom {
-        cmd.arg(arg);
+        cmd.arg(render(&vars, arg));
+    }
+    if config.extra_args_after_rom {
+        cmd.args(&extra_args);
     }
     
-    let mut child = cmd.spawn().expect("Failed to launch emulator");
-    let _ = child.wait();
+    let status = log.run(&mut cmd);
+    report_failure(&mut log, &status, &config, &cmd);
     
     if clean_mode {
         eprintln!("🧹 Nettoyage du cache demandé...");
-        if let Err(e) = fs::remove_dir_all(&target_dir) {
-            eprintln!("❌ Erreur lors du nettoyage du cache: {}", e);
-        } else {
-            eprintln!("✅ Cache nettoyé avec succès: {:?}", target_dir);
+        drop(game_lock);
+        match cache::remove_game(&cache_base, &config.game_name) {
+            Ok(true) => eprintln!("✅ Cache nettoyé avec succès: {:?}", target_dir),
+            Ok(false) => eprintln!("⚠️ Une autre instance du jeu est en cours, nettoyage ignoré"),
+            Err(e) => eprintln!("❌ Erreur lors du nettoyage du cache: {}", e),
         }
+    } else {
+        // The emulator may have grown the directory (shader caches...)
+        let _ = cache::record_use(&cache_base, &config.game_name);
+    }
+    
+    // Same exit status as the emulator, for Steam, Lutris and scripts
+    if let Ok(status) = status {
+        std::process::exit(signals::exit_code(&status));
     }
 }
 
-/// Extract the embedded ZIP archive from the executable
-fn extract_embedded_archive(exe_path: &PathBuf, target_dir: &PathBuf) -> io::Result<()> {
-    use std::io::{Seek, SeekFrom};
-    let mut file = File::open(exe_path)?;
-    
-    // Search for the marker in the first 10MB
-    let mut header = vec![0u8; 10 * 1024 * 1024];
-    let n = file.read(&mut header)?;
-    header.truncate(n);
+/// Valeurs des modèles (`template`) pour ce lancement. Les dossiers de cache
+/// et de sauvegarde sont créés s'ils sont référencés.
+fn template_variables<'a>(game: &str, exe_dir: &Path, config_dir: &Path, rom: &Path, templates: impl Iterator<Item = &'a String>) -> Variables {
+    let cache_dir = cache::cache_root().join(game);
+    let save_dir = persistent_saves_dir(game);
+    for template in templates {
+        for (name, dir) in [("{cache_dir}", &cache_dir), ("{save_dir}", &save_dir)] {
+            if template.contains(name) {
+                let _ = fs::create_dir_all(dir);
+            }
+        }
+    }
+
+    let mut vars = Variables::new();
+    vars.set("exe_dir", exe_dir)
+        .set("config_dir", config_dir)
+        .set("cache_dir", cache_dir)
+        .set("save_dir", save_dir)
+        .set("rom", rom);
+    for (name, dir) in [("home", dirs::home_dir()), ("config_home", dirs::config_dir()), ("data_home", dirs::data_dir())] {
+        if let Some(dir) = dir {
+            vars.set(name, dir);
+        }
+    }
+    vars
+}
+
+/// Résout un modèle de la config ; une config invalide arrête le lancement.
+fn render(vars: &Variables, template: &str) -> String {
+    vars.render(template).unwrap_or_else(|e| {
+        eprintln!("❌ Configuration embarquée invalide: {}", e);
+        std::process::exit(1);
+    })
+}
+
+fn render_env(vars: &Variables, env_vars: &[(String, String)]) -> Vec<(String, String)> {
+    env_vars.iter().map(|(key, value)| (key.clone(), render(vars, value))).collect()
+}
+
+/// Consigne l'échec de l'émulateur et produit un rapport de crash ; quitte
+/// (`EXIT_SPAWN_FAILED`) si l'émulateur n'a même pas pu démarrer.
+fn report_failure(log: &mut LaunchLog, status: &io::Result<ExitStatus>, config: &impl Serialize, cmd: &Command) {
+    match status {
+        Ok(status) if status.success() => return,
+        Ok(status) if signals::stop_requested() => {
+            eprintln!("🛑 Arrêt demandé, émulateur terminé ({})", launch_log::describe(status));
+            return;
+        }
+        Ok(status) => eprintln!("❌ L'émulateur s'est arrêté en erreur ({})", launch_log::describe(status)),
+        Err(e) => eprintln!("❌ Impossible de lancer l'émulateur: {}", e),
+    }
+    match log.crash_report(config, cmd) {
+        Ok(path) => eprintln!("📦 Rapport de crash: {:?} (à joindre à une demande d'aide)", path),
+        Err(e) => eprintln!("⚠️ Rapport de crash impossible: {}", e),
+    }
+    eprintln!("📝 Journal: {:?}", log.path());
+    if status.is_err() {
+        std::process::exit(signals::EXIT_SPAWN_FAILED);
+    }
+}
+
+/// Erreur avant le lancement : consignée dans le journal puis affichée. Pas de
+/// panique, qui serait silencieuse sans console (`panic = "abort"`).
+fn abort_launch(log: &mut LaunchLog, message: String) -> ! {
+    eprintln!("❌ {}", message);
+    log.line(&message);
+    std::process::exit(1);
+}
+
+/// Abort before launching the emulator on corrupted data
+fn refuse_corrupted(error: &io::Error) -> ! {
+    eprintln!("❌ Les données du jeu sont corrompues, lancement annulé.");
+    eprintln!("{}", error);
+    eprintln!("💡 L'exécutable est probablement incomplet (téléchargement ou copie interrompu). Copiez-le à nouveau puis relancez avec --verify.");
+    std::process::exit(1);
+}
+
+/// `--verify`: check the embedded payload against the manifest without launching
+fn run_verify_mode(exe_path: &Path, container: &Container) -> i32 {
+    if !bundle::has_archive(container) {
+        eprintln!("✅ Raccourci sans archive embarquée: rien à vérifier.");
+        return 0;
+    }
     
-    // Search for marker starting from the END of the buffer
-    if let Some(pos) = header.windows(PORTABLE_MARKER.len()).rposition(|window| window == PORTABLE_MARKER) {
-        let config_start = pos + PORTABLE_MARKER.len();
-        
-        // Read config length (4 bytes)
-        let config_len = u32::from_le_bytes([
-            header[config_start],
-            header[config_start + 1],
-            header[config_start + 2],
-            header[config_start + 3],
-        ]) as usize;
-        
-        let zip_start = (config_start + 4 + config_len) as u64;
-        
-        // Seek to the start of ZIP data
-        file.seek(SeekFrom::Start(zip_start))?;
-        
-        // Write ZIP to temporary file by streaming
-        let zip_temp_path = target_dir.join("temp_data.zip");
-        let mut temp_file = File::create(&zip_temp_path)?;
-        io::copy(&mut file, &mut temp_file)?;
-        
-        // Extract ZIP
-        let zip_read_file = File::open(&zip_temp_path)?;
-        let mut archive = zip::ZipArchive::new(zip_read_file)?;
+    let result = (|| -> io::Result<()> {
+        // Données séparées : empreinte du flux complet (vérifiée par `locate`)
+        // avant le détail des entrées
+        if let Some(sidecar) = bundle::read_sidecar(exe_path, container)? {
+            let parts = sidecar.locate(exe_path)?;
+            eprintln!("📦 Données: {:?}", parts);
+        }
+        let manifest = read_manifest(exe_path, container)?;
+        let mut archive = open_embedded_archive(exe_path, container)?;
         
+        let mut seen = HashSet::new();
+        let mut errors = Vec::new();
         for i in 0..archive.len() {
-            let mut out_file = archive.by_index(i)?;
-            let outpath = match out_file.enclosed_name() {
-                Some(path) => target_dir.join(path),
-                None => continue,
-            };
-            
-            if out_file.name().ends_with('/') {
-                fs::create_dir_all(&outpath)?;
-            } else {
-                if let Some(p) = outpath.parent() {
-                    if !p.exists() {
-                        fs::create_dir_all(&p)?;
-                    }
-                }
-                
-                eprintln!("📄 Extraction: {} ({} bytes)", out_file.name(), out_file.size());
-                
-                let mut outfile = File::create(&outpath)?;
-                io::copy(&mut out_file, &mut outfile)?;
-                
-                // Set executable permissions if needed (Linux)
-                #[cfg(unix)]
-                {
-                    use std::os::unix::fs::PermissionsExt;
-                    if let Some(mode) = out_file.unix_mode() {
-                        fs::set_permissions(&outpath, fs::Permissions::from_mode(mode)).ok();
-                    }
+            let mut entry = archive.by_index(i)?;
+            if entry.is_dir() {
+                continue;
+            }
+            let name = entry.name().to_string();
+            // Le crate zip vérifie aussi le CRC32 en lisant l'entrée jusqu'au bout
+            let (sha256, size) = hash_reader(&mut entry)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", name, e)))?;
+            eprintln!("📄 {} ({} bytes)", name, size);
+            if let Some(manifest) = &manifest {
+                if let Err(e) = manifest.check(&name, size, &sha256) {
+                    errors.push(e);
                 }
             }
+            seen.insert(name);
         }
         
-        // Remove temporary ZIP
-        let _ = fs::remove_file(&zip_temp_path);
-        Ok(())
-    } else {
-        Err(io::Error::new(io::ErrorKind::NotFound, "Marker not found"))
+        match &manifest {
+            Some(manifest) => errors.extend(manifest.missing_from(&seen)),
+            None => eprintln!("⚠️ Pas de manifeste d'intégrité: seuls les CRC32 du ZIP ont été vérifiés."),
+        }
+        if errors.is_empty() { Ok(()) } else { Err(integrity_error(&errors)) }
+    })();
+    
+    match result {
+        Ok(()) => {
+            eprintln!("✅ Intégrité vérifiée: {:?}", exe_path);
+            0
+        }
+        Err(e) => {
+            eprintln!("❌ Vérification échouée: {}", e);
+            1
+        }
     }
 }
 
 /// Run in launcher mode - use embedded config
-fn run_launcher_mode() {
-    let mut config: LaunchConfig = serde_json::from_str(CONFIG_JSON)
-        .expect("Failed to parse embedded config");
-
+fn run_launcher_mode(mut config: LaunchConfig) {
+    // Un raccourci n'a pas de nom de jeu : celui de la ROM (ou de l'émulateur) en tient lieu
+    let game = config.rom_path.file_stem().or_else(|| config.emulator_path.file_stem())
+        .map_or_else(|| "launcher".to_string(), |s| s.to_string_lossy().to_string());
+    let mut log = LaunchLog::open(&game);
+    log.line("Mode raccourci");
+    let config_snapshot = serde_json::to_value(&config).unwrap_or_default();
+    
+    // Chemins enregistrés relativement à l'exécutable (dossier déplaçable)
+    let exe_dir = env::current_exe()
+        .ok()
+        .and_then(|p| p.parent().map(|p| p.to_path_buf()))
+        .unwrap_or_else(|| PathBuf::from("."));
+    config.resolve_paths(&exe_dir);
+    
     // Fix for patched AppImages (Ryujinx) which are directories
     if config.emulator_path.is_dir() {
         let app_run = config.emulator_path.join("AppRun");
@@ -422,8 +700,13 @@ fn run_launcher_mode() {
         }
     }
 
+    // Modèles de la config : {exe_dir} et {config_dir} sont le dossier de l'exécutable
+    let templates = config.env_vars.iter().map(|(_, v)| v).chain(&config.args).chain(&config.args_after_rom);
+    let vars = template_variables(&game, &exe_dir, &exe_dir, &config.rom_path, templates);
+    let env_vars = render_env(&vars, &config.env_vars);
+
     // === DÉTECTION DYNAMIQUE DES MANETTES POUR RYUJINX ===
-    if is_ryujinx_emulator(&config.emulator_path) {
+    if config.driver_id == "ryujinx" {
         eprintln!("🎮 Détection dynamique des manettes pour Ryujinx...");
         if let Err(e) = ryujinx_input::update_ryujinx_input_config() {
             eprintln!("⚠️ Erreur config manettes: {}", e);
@@ -431,9 +714,7 @@ fn run_launcher_mode() {
     }
 
     // === DÉTECTION DYNAMIQUE DES MANETTES POUR AZAHAR (LAUNCHER MODE) ===
-    // Check if emulator path contains "azahar" or "lime3ds"
-    let emu_lower = config.emulator_path.to_string_lossy().to_lowercase();
-    if emu_lower.contains("azahar") || emu_lower.contains("lime3ds") {
+    if config.driver_id == "azahar" {
          eprintln!("🎮 [Azahar-Launcher] Début détection manettes...");
          
          // 1. Detect Gamepad
@@ -452,13 +733,7 @@ fn run_launcher_mode() {
          eprintln!("   📊 [Azahar-Launcher] Manette: {} -> Profil: {}", has_gamepad, profile_index);
          
          // 2. Find config path via XDG_CONFIG_HOME in env_vars
-         let mut config_home = None;
-         for (key, val) in &config.env_vars {
-             if key == "XDG_CONFIG_HOME" {
-                 config_home = Some(PathBuf::from(val));
-                 break;
-             }
-         }
+         let config_home = env_vars.iter().find(|(key, _)| key == "XDG_CONFIG_HOME").map(|(_, val)| PathBuf::from(val));
          
          if let Some(path) = config_home {
              let config_file = path.join("azahar-emu/qt-config.ini");
@@ -492,17 +767,10 @@ fn run_launcher_mode() {
         cmd.current_dir(working_dir);
     }
 
-    // Get executable directory for relative path resolution
-    let exe_dir = env::current_exe()
-        .ok()
-        .and_then(|p| p.parent().map(|p| p.to_path_buf()))
-        .unwrap_or_else(|| PathBuf::from("."));
-
-    for (key, value) in config.env_vars {
-        // Support {exe_dir} placeholder for relative paths in launcher mode
-        let resolved_value = value.replace("{exe_dir}", &exe_dir.to_string_lossy());
-        cmd.env(key, resolved_value);
+    for (key, value) in &env_vars {
+        cmd.env(key, value);
     }
+    apply_env_overrides(&mut cmd);
 
     // Determine if we need DuckStation specific HOME isolation
     let is_duckstation = config.emulator_path.to_string_lossy().to_lowercase().contains("duckstation");
@@ -519,7 +787,13 @@ fn run_launcher_mode() {
         }
     }
 
-    cmd.args(&config.args);
+    // Arguments de l'appelant (`exe -- ...`) : en tête, ou après la ROM si le
+    // plugin le demande, comme en mode portable
+    let extra_args = passthrough_args();
+    if !config.extra_args_after_rom {
+        cmd.args(&extra_args);
+    }
+    cmd.args(config.args.iter().map(|arg| render(&vars, arg)));
     
     // In launcher mode, if rom_path is not empty, we add it.
     // If it's a wrapper script, rom_path might have been cleared in the UI logic.
@@ -528,7 +802,10 @@ fn run_launcher_mode() {
     }
 
     // Add arguments that come AFTER the ROM path (e.g., Cemu's -f for fullscreen)
-    cmd.args(&config.args_after_rom);
+    cmd.args(config.args_after_rom.iter().map(|arg| render(&vars, arg)));
+    if config.extra_args_after_rom {
+        cmd.args(&extra_args);
+    }
 
     eprintln!("🚀 DEBUG: Launching in LAUNCHER MODE: {:?}", cmd);
     
@@ -540,51 +817,11 @@ fn run_launcher_mode() {
         eprintln!("   ❌ Executable not found or not accessible: {:?}", config.emulator_path);
     }
 
-    let mut child = cmd.spawn().expect("Failed to launch emulator");
-    let _ = child.wait();
-}
-
-/// Vérifie si l'émulateur est Ryujinx (pour activer la détection dynamique des manettes)
-fn is_ryujinx_emulator(path: &std::path::Path) -> bool {
-    // 1. Vérifie le chemin complet (contient "ryujinx" quelque part)
-    let full_path = path.to_string_lossy().to_lowercase();
-    if full_path.contains("ryujinx") {
-        return true;
-    }
-    
-    // 2. Vérifie si c'est un AppRun dans un dossier squashfs-root qui contient Ryujinx
-    if path.file_name().map(|n| n == "AppRun").unwrap_or(false) {
-        // Chercher usr/bin/Ryujinx dans le même dossier
-        if let Some(parent) = path.parent() {
-            let ryujinx_bin = parent.join("usr/bin/Ryujinx");
-            if ryujinx_bin.exists() {
-                return true;
-            }
-            let ryujinx_bin_alt = parent.join("usr/bin/Ryujinx.sh");
-            if ryujinx_bin_alt.exists() {
-                return true;
-            }
-        }
+    let status = log.run(&mut cmd);
+    report_failure(&mut log, &status, &config_snapshot, &cmd);
+    if let Ok(status) = status {
+        std::process::exit(signals::exit_code(&status));
     }
-    
-    // 3. Remonte les parents pour chercher "ryujinx" dans le chemin
-    let mut current = path.parent();
-    for _ in 0..5 {  // Max 5 niveaux
-        if let Some(parent) = current {
-            let parent_name = parent.file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("")
-                .to_lowercase();
-            if parent_name.contains("ryujinx") {
-                return true;
-            }
-            current = parent.parent();
-        } else {
-            break;
-        }
-    }
-    
-    false
 }
 
 /// Detect if a gamepad is connected (Linux only for now)
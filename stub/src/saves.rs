@@ -0,0 +1,197 @@
+//! `--export-saves` / `--import-saves` : cartes mémoire, sauvegardes et states
+//! d'un jeu forgé dans une seule archive ZIP, avec un petit manifeste.
+//!
+//! Chaque entrée de l'archive est nommée `<emplacement>/<chemin relatif>`, où
+//! l'emplacement est celui déclaré par le plugin (`save_dirs`, `save_files` ou
+//! `host_save_dirs` non résolu) : l'import résout ces emplacements sur la
+//! machine courante, quelle que soit celle qui a exporté.
+
+use emuforge_format::config::PortableConfig;
+use emuforge_format::template::Variables;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::extract::{mtime_of, persistent_saves_dir};
+
+const MANIFEST_NAME: &str = "emuforge-saves.json";
+const MANIFEST_FORMAT: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct SavesManifest {
+    format: u32,
+    game: String,
+    driver_id: String,
+    /// Secondes depuis l'epoch Unix
+    exported_at: u64,
+    files: Vec<SavedFile>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedFile {
+    path: String,
+    size: u64,
+    /// Date de modification d'origine (secondes depuis l'epoch Unix)
+    modified: u64,
+}
+
+/// Emplacement de sauvegarde déclaré, et son chemin sur cette machine.
+struct Location {
+    name: String,
+    path: PathBuf,
+    is_dir: bool,
+}
+
+fn locations(config: &PortableConfig) -> Vec<Location> {
+    let saves_dir = persistent_saves_dir(&config.game_name);
+    let bundle_dirs = config.save_dirs.iter().map(|d| Location { name: d.clone(), path: saves_dir.join(d), is_dir: true });
+    let bundle_files = config.save_files.iter().map(|f| Location { name: f.clone(), path: saves_dir.join(f), is_dir: false });
+    let host_dirs = config.host_save_dirs.iter().filter_map(|d| match resolve_host_path(d) {
+        Some(path) => Some(Location { name: d.clone(), path, is_dir: true }),
+        None => {
+            eprintln!("⚠️ Emplacement de sauvegarde non résolu: {}", d);
+            None
+        }
+    });
+    bundle_dirs.chain(bundle_files).chain(host_dirs).collect()
+}
+
+/// Résout le modèle (`{config_home}`, `{data_home}`, `{home}`) pour l'utilisateur courant.
+fn resolve_host_path(template: &str) -> Option<PathBuf> {
+    let mut vars = Variables::new();
+    for (name, dir) in [("config_home", dirs::config_dir()), ("data_home", dirs::data_dir()), ("home", dirs::home_dir())] {
+        if let Some(dir) = dir {
+            vars.set(name, dir);
+        }
+    }
+    vars.render(template).ok().map(PathBuf::from)
+}
+
+fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+/// Fichiers d'un emplacement, avec leur chemin relatif (`/` comme séparateur).
+fn collect_files(dir: &Path, prefix: &str, files: &mut Vec<(String, PathBuf)>) -> io::Result<()> {
+    let mut entries: Vec<_> = fs::read_dir(dir)?.collect::<io::Result<_>>()?;
+    entries.sort_by_key(|e| e.file_name());
+    for entry in entries {
+        let name = format!("{}/{}", prefix, entry.file_name().to_string_lossy());
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, &name, files)?;
+        } else if path.is_file() {
+            files.push((name, path));
+        }
+    }
+    Ok(())
+}
+
+pub fn export_saves(config: &PortableConfig, output: &Path) -> io::Result<usize> {
+    let mut files = Vec::new();
+    for location in locations(config) {
+        if location.is_dir && location.path.is_dir() {
+            collect_files(&location.path, &location.name, &mut files)?;
+        } else if !location.is_dir && location.path.is_file() {
+            files.push((location.name, location.path));
+        }
+    }
+    if files.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, "aucune sauvegarde à exporter (le jeu a-t-il été lancé ?)"));
+    }
+
+    let mut zip = zip::ZipWriter::new(File::create(output)?);
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut manifest = SavesManifest {
+        format: MANIFEST_FORMAT,
+        game: config.game_name.clone(),
+        driver_id: config.driver_id.clone(),
+        exported_at: unix_seconds(SystemTime::now()),
+        files: Vec::new(),
+    };
+    for (name, path) in &files {
+        let metadata = fs::metadata(path)?;
+        zip.start_file(name.as_str(), options)?;
+        io::copy(&mut File::open(path)?, &mut zip)?;
+        manifest.files.push(SavedFile {
+            path: name.clone(),
+            size: metadata.len(),
+            modified: metadata.modified().map_or(0, unix_seconds),
+        });
+    }
+    zip.start_file(MANIFEST_NAME, options)?;
+    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+    zip.finish()?;
+    Ok(files.len())
+}
+
+pub fn import_saves(config: &PortableConfig, input: &Path) -> io::Result<usize> {
+    let mut archive = zip::ZipArchive::new(File::open(input)?)?;
+    let manifest: SavesManifest = {
+        let mut entry = archive.by_name(MANIFEST_NAME)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "archive sans manifeste EmuForge"))?;
+        let mut json = String::new();
+        entry.read_to_string(&mut json)?;
+        serde_json::from_str(&json)?
+    };
+    if manifest.format > MANIFEST_FORMAT {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("format de sauvegarde {} non géré", manifest.format)));
+    }
+    if manifest.driver_id != config.driver_id {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("sauvegardes de l'émulateur '{}', ce jeu utilise '{}'", manifest.driver_id, config.driver_id),
+        ));
+    }
+    if manifest.game != config.game_name {
+        eprintln!("⚠️ Sauvegardes exportées depuis « {} », importées dans « {} »", manifest.game, config.game_name);
+    }
+
+    let locations = locations(config);
+    let mut imported = 0;
+    for saved in &manifest.files {
+        let Some(target) = target_path(&locations, &saved.path) else {
+            eprintln!("⚠️ {}: emplacement inconnu pour ce jeu, ignoré", saved.path);
+            continue;
+        };
+        let mut entry = match archive.by_name(&saved.path) {
+            Ok(entry) => entry,
+            Err(_) => {
+                eprintln!("⚠️ {}: absent de l'archive", saved.path);
+                continue;
+            }
+        };
+
+        // Une sauvegarde locale plus récente n'est jamais perdue
+        if target.is_file() && mtime_of(&target) / 1_000_000_000 > saved.modified {
+            let backup = PathBuf::from(format!("{}.bak", target.display()));
+            fs::rename(&target, &backup)?;
+            eprintln!("📝 {}: version locale plus récente sauvegardée en {:?}", saved.path, backup);
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = File::create(&target)?;
+        io::copy(&mut entry, &mut file)?;
+        let _ = file.set_modified(UNIX_EPOCH + Duration::from_secs(saved.modified));
+        imported += 1;
+    }
+    Ok(imported)
+}
+
+/// Chemin local d'une entrée, si elle appartient à un emplacement du jeu.
+/// Seuls les chemins relatifs simples sont acceptés : `..`, `.`, segment vide
+/// ou chemin absolu pourraient écrire hors de l'emplacement.
+fn target_path(locations: &[Location], name: &str) -> Option<PathBuf> {
+    locations.iter().find_map(|location| {
+        if !location.is_dir {
+            return (name == location.name).then(|| location.path.clone());
+        }
+        let relative = name.strip_prefix(location.name.as_str())?.strip_prefix('/')?;
+        let plain = relative.split('/').all(|part| !part.is_empty() && part != "." && part != "..")
+            && Path::new(relative).components().all(|c| matches!(c, Component::Normal(_)));
+        plain.then(|| location.path.join(relative))
+    })
+}
@@ -0,0 +1,92 @@
+//! Relais des signaux d'arrêt vers l'émulateur et code de sortie du stub.
+//!
+//! L'émulateur est lancé dans son propre groupe de processus : SIGTERM,
+//! SIGINT et SIGHUP reçus par le stub (Steam, Lutris, Ctrl+C...) sont
+//! renvoyés à tout le groupe, l'émulateur et ses éventuels sous-processus,
+//! puis le stub attend leur fin et sort avec le même statut.
+
+use std::process::{Command, ExitStatus};
+
+/// Code de sortie quand l'émulateur n'a pas pu être lancé (convention shell).
+pub const EXIT_SPAWN_FAILED: i32 = 127;
+
+#[cfg(unix)]
+mod imp {
+    use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+
+    /// Groupe de processus de l'émulateur (0 tant qu'il n'est pas lancé).
+    static CHILD_GROUP: AtomicI32 = AtomicI32::new(0);
+    /// Signal reçu avant que l'émulateur n'existe, relayé dès son lancement.
+    static PENDING: AtomicI32 = AtomicI32::new(0);
+    /// Un signal d'arrêt a été reçu (partie quittée, pas un crash).
+    pub static RECEIVED: AtomicBool = AtomicBool::new(false);
+
+    const FORWARDED: [libc::c_int; 3] = [libc::SIGTERM, libc::SIGINT, libc::SIGHUP];
+
+    extern "C" fn forward(signal: libc::c_int) {
+        // Uniquement des appels async-signal-safe ici
+        RECEIVED.store(true, Ordering::SeqCst);
+        let group = CHILD_GROUP.load(Ordering::SeqCst);
+        if group > 0 {
+            unsafe { libc::kill(-group, signal) };
+        } else {
+            PENDING.store(signal, Ordering::SeqCst);
+        }
+    }
+
+    pub fn install() {
+        for signal in FORWARDED {
+            unsafe { libc::signal(signal, forward as *const () as libc::sighandler_t) };
+        }
+    }
+
+    pub fn attach(pid: u32) {
+        let group = pid as i32;
+        CHILD_GROUP.store(group, Ordering::SeqCst);
+        let pending = PENDING.swap(0, Ordering::SeqCst);
+        if pending != 0 {
+            unsafe { libc::kill(-group, pending) };
+        }
+    }
+}
+
+/// À appeler avant `spawn` : groupe de processus dédié et relais des signaux.
+pub fn prepare(cmd: &mut Command) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+        imp::install();
+    }
+    #[cfg(not(unix))]
+    let _ = cmd;
+}
+
+/// À appeler juste après `spawn` avec le pid de l'émulateur.
+pub fn attach(pid: u32) {
+    #[cfg(unix)]
+    imp::attach(pid);
+    #[cfg(not(unix))]
+    let _ = pid;
+}
+
+/// Vrai si l'arrêt a été demandé au stub (l'émulateur n'a pas planté).
+pub fn stop_requested() -> bool {
+    #[cfg(unix)]
+    return imp::RECEIVED.load(std::sync::atomic::Ordering::SeqCst);
+    #[cfg(not(unix))]
+    false
+}
+
+/// Code de sortie du stub pour un statut de l'émulateur : le sien, ou
+/// 128 + numéro du signal qui l'a tué (convention shell).
+pub fn exit_code(status: &ExitStatus) -> i32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    status.code().unwrap_or(1)
+}
@@ -0,0 +1,232 @@
+//! Magasin adressé par contenu des composants partagés (émulateurs).
+//!
+//! Un composant déclaré dans le manifeste est extrait une seule fois dans
+//! `~/.cache/emuforge/store/<id>/`, puis lié dans chaque dossier de jeu :
+//! vingt jeux PS2 partagent une seule copie de PCSX2. Les composants
+//! "externes" (exécutables thin) sont lus depuis un pack `<id>.emupack`
+//! placé à côté de l'exécutable ou dans `EMUFORGE_PACKS_DIR`.
+
+use emuforge_format::bundle::EmbeddedArchive;
+use emuforge_format::manifest::{Component, HashingWriter, IntegrityError, IntegrityManifest};
+use emuforge_format::sidecar::SEARCH_DIR_ENV as PACKS_DIR_ENV;
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek};
+use std::path::{Path, PathBuf};
+
+use crate::extract::integrity_error;
+use crate::{cache, lock};
+
+pub const STORE_DIR: &str = "store";
+
+const PARTIAL_SUFFIX: &str = ".partial";
+
+fn lock_path(store: &Path, id: &str) -> PathBuf {
+    store.join(format!("{}.lock", id))
+}
+
+/// Dossier du composant dans le magasin, extrait au besoin, et son verrou :
+/// l'appelant le garde jusqu'à ce que le lien vers le composant existe, sans
+/// quoi `prune` pourrait le supprimer entre-temps.
+pub fn ensure_component(
+    root: &Path,
+    component: &Component,
+    manifest: &IntegrityManifest,
+    embedded: &mut EmbeddedArchive,
+    exe_path: &Path,
+) -> io::Result<(PathBuf, lock::Lock)> {
+    // Deux jeux partageant l'émulateur ne l'extraient pas en même temps
+    let store = root.join(STORE_DIR);
+    let object = store.join(&component.id);
+    let lock = lock::exclusive(&lock_path(&store, &component.id), &component.path)?;
+    if object.is_dir() {
+        return Ok((object, lock));
+    }
+
+    let pack = if component.external { Some(find_pack(&component.id, exe_path)?) } else { None };
+    let partial = store.join(format!("{}{}", component.id, PARTIAL_SUFFIX));
+    if partial.exists() {
+        eprintln!("♻️ Extraction interrompue de {}, reprise à zéro", component.path);
+        fs::remove_dir_all(&partial)?;
+    }
+    fs::create_dir_all(&partial)?;
+    let result = if let Some(pack) = pack {
+        eprintln!("📦 Pack émulateur: {:?}", pack);
+        File::open(&pack)
+            .and_then(|file| Ok(zip::ZipArchive::new(file)?))
+            .and_then(|mut archive| fill(&mut archive, component, manifest, &partial))
+    } else {
+        fill(embedded, component, manifest, &partial)
+    };
+    if let Err(e) = result {
+        let _ = fs::remove_dir_all(&partial);
+        return Err(e);
+    }
+
+    fs::rename(&partial, &object)?;
+    eprintln!("🗃️ {} ajouté au magasin partagé", component.path);
+    Ok((object, lock))
+}
+
+fn find_pack(id: &str, exe_path: &Path) -> io::Result<PathBuf> {
+    let name = format!("{}.emupack", id);
+    let dirs = exe_path.parent().map(Path::to_path_buf).into_iter()
+        .chain(std::env::var_os(PACKS_DIR_ENV).map(PathBuf::from));
+    for dir in dirs {
+        let candidate = dir.join(&name);
+        if candidate.is_file() {
+            return Ok(candidate);
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("pack émulateur {} introuvable (à placer à côté de l'exécutable ou dans {})", name, PACKS_DIR_ENV),
+    ))
+}
+
+/// Extrait les entrées du composant dans `dest` en les vérifiant contre le manifeste.
+fn fill<R: Read + Seek>(archive: &mut zip::ZipArchive<R>, component: &Component, manifest: &IntegrityManifest, dest: &Path) -> io::Result<()> {
+    let mut seen = HashSet::new();
+    let mut errors = Vec::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        let name = entry.name().to_string();
+        if !IntegrityManifest::is_under(name.trim_end_matches('/'), &component.path) {
+            continue;
+        }
+        let Some(outpath) = entry.enclosed_name().map(|p| dest.join(p)) else { continue };
+        if entry.is_dir() {
+            fs::create_dir_all(&outpath)?;
+            continue;
+        }
+        if let Some(parent) = outpath.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        eprintln!("📄 Extraction: {} ({} bytes)", name, entry.size());
+        let mut out = HashingWriter::new(File::create(&outpath)?);
+        io::copy(&mut entry, &mut out)?;
+        let (_, sha256, size) = out.finalize();
+        if let Err(e) = manifest.check(&name, size, &sha256) {
+            errors.push(e);
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            if let Some(mode) = entry.unix_mode() {
+                fs::set_permissions(&outpath, fs::Permissions::from_mode(mode)).ok();
+            }
+        }
+        seen.insert(name);
+    }
+
+    errors.extend(
+        manifest.entries.iter()
+            .filter(|e| IntegrityManifest::is_under(&e.path, &component.path) && !seen.contains(&e.path))
+            .map(|e| IntegrityError::Missing(e.path.clone())),
+    );
+    if errors.is_empty() { Ok(()) } else { Err(integrity_error(&errors)) }
+}
+
+/// Remplace `<target_dir>/<path>` par un lien vers sa copie dans le magasin
+/// (copie si les liens symboliques ne sont pas disponibles).
+pub fn link_component(object: &Path, target_dir: &Path, path: &str) -> io::Result<()> {
+    let link = target_dir.join(path);
+    let source = object.join(path);
+    match fs::symlink_metadata(&link) {
+        Ok(meta) if meta.file_type().is_symlink() => {
+            if fs::read_link(&link)? == source {
+                return Ok(());
+            }
+            fs::remove_file(&link)?;
+        }
+        Ok(meta) if meta.is_dir() => fs::remove_dir_all(&link)?,
+        Ok(_) => fs::remove_file(&link)?,
+        Err(_) => {}
+    }
+    if let Some(parent) = link.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    #[cfg(unix)]
+    let linked = std::os::unix::fs::symlink(&source, &link);
+    #[cfg(windows)]
+    let linked = if source.is_dir() {
+        std::os::windows::fs::symlink_dir(&source, &link)
+    } else {
+        std::os::windows::fs::symlink_file(&source, &link)
+    };
+    if let Err(e) = linked {
+        eprintln!("⚠️ Lien vers le magasin impossible ({}), copie de {}", e, path);
+        copy_tree(&source, &link)?;
+    }
+    Ok(())
+}
+
+fn copy_tree(src: &Path, dst: &Path) -> io::Result<()> {
+    if src.is_dir() {
+        fs::create_dir_all(dst)?;
+        for entry in fs::read_dir(src)? {
+            let entry = entry?;
+            copy_tree(&entry.path(), &dst.join(entry.file_name()))?;
+        }
+        return Ok(());
+    }
+    fs::copy(src, dst).map(|_| ())
+}
+
+/// Taille totale des composants du magasin.
+pub fn size(root: &Path) -> u64 {
+    cache::dir_size(&root.join(STORE_DIR))
+}
+
+/// Composants du magasin liés depuis un dossier de jeu (ou de préparation).
+fn referenced_objects(root: &Path, store: &Path) -> io::Result<HashSet<OsString>> {
+    let mut referenced = HashSet::new();
+    for game in fs::read_dir(root)?.flatten() {
+        if game.file_name() == STORE_DIR || !game.file_type().is_ok_and(|t| t.is_dir()) {
+            continue;
+        }
+        for entry in fs::read_dir(game.path())?.flatten() {
+            if let Ok(target) = fs::read_link(entry.path()) {
+                if let Ok(rest) = target.strip_prefix(store) {
+                    referenced.extend(rest.components().next().map(|c| c.as_os_str().to_os_string()));
+                }
+            }
+        }
+    }
+    Ok(referenced)
+}
+
+/// Supprime les composants qu'aucun dossier de jeu ne référence plus et
+/// retourne la place libérée, ainsi que les extractions interrompues.
+/// Les composants verrouillés (extraction ou liaison en cours) sont ignorés.
+pub fn prune(root: &Path) -> io::Result<u64> {
+    let store = root.join(STORE_DIR);
+    if !store.is_dir() {
+        return Ok(0);
+    }
+
+    let referenced = referenced_objects(root, &store)?;
+    let mut freed = 0;
+    for object in fs::read_dir(&store)?.flatten() {
+        let name = object.file_name();
+        if referenced.contains(&name) || !object.file_type().is_ok_and(|t| t.is_dir()) {
+            continue;
+        }
+        let text = name.to_string_lossy();
+        let id = text.strip_suffix(PARTIAL_SUFFIX).unwrap_or(&text);
+        let Some(_lock) = lock::try_exclusive(&lock_path(&store, id))? else { continue };
+        // Lié par un lancement qui a rendu le verrou depuis le premier parcours
+        if referenced_objects(root, &store)?.contains(&name) {
+            continue;
+        }
+        let size = cache::dir_size(&object.path());
+        if fs::remove_dir_all(object.path()).is_ok() {
+            eprintln!("🧹 Composant partagé inutilisé supprimé: {}", name.to_string_lossy());
+            freed += size;
+        }
+    }
+    Ok(freed)
+}
@@ -0,0 +1,102 @@
+use emuforge_stub::cache::{collect_garbage, downgrade_game_lock, format_size, parse_size, remove_game};
+use emuforge_stub::extract::game_lock_path;
+use emuforge_stub::lock;
+use std::fs;
+use std::path::Path;
+
+/// Cache avec trois jeux de 100 octets, A le moins récemment utilisé.
+fn cache_with_games(root: &Path) {
+    for game in ["A", "B", "C"] {
+        fs::create_dir_all(root.join(game)).unwrap();
+        fs::write(root.join(game).join("game.iso"), [0u8; 100]).unwrap();
+    }
+    let index = r#"{"games": {
+        "A": {"last_used": 1, "size": 100},
+        "B": {"last_used": 2, "size": 100},
+        "C": {"last_used": 3, "size": 100}
+    }}"#;
+    fs::write(root.join("index.json"), index).unwrap();
+}
+
+fn evicted(report: &emuforge_stub::cache::GcReport) -> Vec<&str> {
+    report.evicted.iter().map(|(name, _)| name.as_str()).collect()
+}
+
+#[test]
+fn test_parse_size() {
+    assert_eq!(parse_size("20G"), Some(20 << 30));
+    assert_eq!(parse_size("512M"), Some(512 << 20));
+    assert_eq!(parse_size(" 10 GB "), Some(10 << 30));
+    assert_eq!(parse_size("2go"), Some(2 << 30));
+    assert_eq!(parse_size("4096"), Some(4096));
+    assert_eq!(parse_size("0"), Some(0));
+    assert_eq!(parse_size(""), None);
+    assert_eq!(parse_size("1.5G"), None);
+    assert_eq!(parse_size("20X"), None);
+    assert_eq!(parse_size("lots"), None);
+    assert_eq!(parse_size("99999999999T"), None);
+}
+
+#[test]
+fn test_format_size() {
+    assert_eq!(format_size(0), "0 o");
+    assert_eq!(format_size(1023), "1023 o");
+    assert_eq!(format_size(1536), "1.5 Ko");
+    assert_eq!(format_size(20 << 30), "20.0 Go");
+}
+
+#[test]
+fn test_gc_evicts_least_recently_used_first() {
+    let dir = tempfile::tempdir().unwrap();
+    cache_with_games(dir.path());
+
+    let report = collect_garbage(dir.path(), 150, None).unwrap();
+    assert_eq!(evicted(&report), ["A", "B"]);
+    assert_eq!(report.total, 100);
+    assert!(!dir.path().join("A").exists() && !dir.path().join("B").exists());
+    assert!(dir.path().join("C").exists());
+
+    // Sous la limite (ou sans limite) : rien n'est évincé
+    assert!(collect_garbage(dir.path(), 150, None).unwrap().evicted.is_empty());
+    assert!(collect_garbage(dir.path(), 0, None).unwrap().evicted.is_empty());
+}
+
+#[test]
+fn test_gc_never_evicts_the_protected_game() {
+    let dir = tempfile::tempdir().unwrap();
+    cache_with_games(dir.path());
+
+    let report = collect_garbage(dir.path(), 50, Some("A")).unwrap();
+    assert_eq!(evicted(&report), ["B", "C"]);
+    assert_eq!(report.total, 100);
+    assert!(dir.path().join("A/game.iso").exists());
+}
+
+#[test]
+fn test_gc_skips_locked_games() {
+    let dir = tempfile::tempdir().unwrap();
+    cache_with_games(dir.path());
+
+    // B est en cours de partie (verrou partagé d'un autre lancement)
+    let extracting = lock::exclusive(&game_lock_path(dir.path(), "B"), "B").unwrap();
+    let _playing = downgrade_game_lock(dir.path(), extracting).unwrap();
+    let report = collect_garbage(dir.path(), 150, None).unwrap();
+    assert_eq!(evicted(&report), ["A", "C"]);
+    assert!(dir.path().join("B/game.iso").exists());
+}
+
+#[test]
+fn test_clean_skips_a_game_in_use() {
+    let dir = tempfile::tempdir().unwrap();
+    cache_with_games(dir.path());
+
+    let playing = lock::exclusive(&game_lock_path(dir.path(), "A"), "A").unwrap();
+    let playing = downgrade_game_lock(dir.path(), playing).unwrap();
+    assert!(!remove_game(dir.path(), "A").unwrap());
+    assert!(dir.path().join("A/game.iso").exists());
+
+    drop(playing);
+    assert!(remove_game(dir.path(), "A").unwrap());
+    assert!(!dir.path().join("A").exists());
+    assert!(!fs::read_to_string(dir.path().join("index.json")).unwrap().contains("\"A\""));
+}
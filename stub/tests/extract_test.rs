@@ -0,0 +1,227 @@
+use emuforge_format::config::PortableConfig;
+use emuforge_format::container::{Container, ContainerWriter, SectionKind};
+use emuforge_format::manifest::{hash_reader, IntegrityManifest};
+use emuforge_stub::extract::{
+    extract_embedded_archive, extract_game, is_first_launch, is_user_data, mtime_of, staging_dir, unchanged_entry, ExtractedEntry,
+};
+use std::fs;
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+fn portable_config() -> PortableConfig {
+    PortableConfig {
+        game_name: "Game".into(),
+        emulator_filename: "emu".into(),
+        rom_filename: "game.iso".into(),
+        config_dir: "config".into(),
+        ..Default::default()
+    }
+}
+
+/// Exécutable forgé minimal : un faux stub suivi de la config et de l'archive.
+fn forge(dir: &Path, files: &[(&str, &[u8])]) -> (PathBuf, Container) {
+    forge_with_manifest(dir, files, None)
+}
+
+/// Manifeste des fichiers tels qu'ils ont été forgés.
+fn manifest_of(files: &[(&str, &[u8])]) -> IntegrityManifest {
+    let mut manifest = IntegrityManifest::default();
+    for (name, data) in files {
+        let (sha256, size) = hash_reader(&mut &data[..]).unwrap();
+        manifest.add(name, size, sha256);
+    }
+    manifest
+}
+
+fn forge_with_manifest(dir: &Path, files: &[(&str, &[u8])], manifest: Option<&IntegrityManifest>) -> (PathBuf, Container) {
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, data) in files {
+        zip.start_file(*name, zip::write::SimpleFileOptions::default()).unwrap();
+        zip.write_all(data).unwrap();
+    }
+    let archive = zip.finish().unwrap().into_inner();
+
+    let mut out = Cursor::new(b"stub".to_vec());
+    out.set_position(4);
+    let mut writer = ContainerWriter::new(out).unwrap();
+    writer.add_section(SectionKind::PortableConfig, &portable_config().to_json().unwrap()).unwrap();
+    writer.add_section(SectionKind::Archive, &archive).unwrap();
+    if let Some(manifest) = manifest {
+        writer.add_section(SectionKind::Manifest, &manifest.to_json().unwrap()).unwrap();
+    }
+    let path = dir.join("Game.exe");
+    fs::write(&path, writer.finish().unwrap().into_inner()).unwrap();
+    let container = Container::open(&path).unwrap().unwrap();
+    (path, container)
+}
+
+/// Recule la date de modification, comme un fichier écrit par un lancement précédent.
+fn age(path: &Path) {
+    let file = fs::File::options().write(true).open(path).unwrap();
+    file.set_modified(SystemTime::now() - Duration::from_secs(60)).unwrap();
+}
+
+#[test]
+fn test_unchanged_entry_skips_identical_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("game.iso");
+    fs::write(&path, b"disc image").unwrap();
+    let crc32 = crc32fast::hash(b"disc image");
+
+    // Missing file or different size: always extracted
+    assert!(unchanged_entry(&dir.path().join("missing"), "missing", 10, crc32, None, None).is_none());
+    assert!(unchanged_entry(&path, "game.iso", 11, crc32, None, None).is_none());
+
+    // Unknown state: the CRC32 on disk decides
+    let entry = unchanged_entry(&path, "game.iso", 10, crc32, None, None).expect("Identical file re-extracted");
+    assert_eq!(entry, ExtractedEntry { size: 10, crc32, mtime: mtime_of(&path) });
+    assert!(unchanged_entry(&path, "game.iso", 10, crc32 ^ 1, None, None).is_none());
+}
+
+#[test]
+fn test_unchanged_entry_trusts_previous_state() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("game.iso");
+    fs::write(&path, b"disc image").unwrap();
+
+    // Same size, CRC32 and mtime as the last extraction: the file is not reread
+    let recorded = ExtractedEntry { size: 10, crc32: 0x1234_5678, mtime: mtime_of(&path) };
+    assert_eq!(unchanged_entry(&path, "game.iso", 10, 0x1234_5678, Some(&recorded), None), Some(recorded));
+
+    // Touched since: the CRC32 is checked again
+    let stale = ExtractedEntry { mtime: recorded.mtime - 1, ..recorded };
+    assert!(unchanged_entry(&path, "game.iso", 10, 0x1234_5678, Some(&stale), None).is_none());
+}
+
+#[test]
+fn test_unchanged_entry_checks_the_manifest() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("game.iso");
+    fs::write(&path, b"disc image").unwrap();
+    let crc32 = crc32fast::hash(b"disc image");
+
+    // Sans état précédent, le CRC32 de l'archive ne suffit pas : SHA-256 du manifeste
+    let manifest = manifest_of(&[("game.iso", b"disc image")]);
+    assert!(unchanged_entry(&path, "game.iso", 10, crc32, None, Some(&manifest)).is_some());
+    let other = manifest_of(&[("game.iso", b"DISC IMAGE")]);
+    assert!(unchanged_entry(&path, "game.iso", 10, crc32, None, Some(&other)).is_none());
+    assert!(unchanged_entry(&path, "game.iso", 10, crc32, None, Some(&IntegrityManifest::default())).is_none());
+}
+
+#[test]
+fn test_tampered_entry_is_never_put_in_place() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache = dir.path().join("cache");
+    let staging = staging_dir(&cache, "Game");
+    let manifest = manifest_of(&[("emu", b"#!/bin/sh"), ("game.iso", b"disc")]);
+    let (exe, container) = forge_with_manifest(dir.path(), &[("emu", b"#!/bin/sh"), ("game.iso", b"DISC")], Some(&manifest));
+
+    // Refusé, et ni le fichier ni sa copie partielle ne restent pour un lancement suivant
+    for _ in 0..2 {
+        assert!(extract_game(&exe, &container, &cache, &portable_config(), false).is_err());
+        assert_eq!(fs::read(staging.join("emu")).unwrap(), b"#!/bin/sh");
+        assert!(!staging.join("game.iso").exists());
+        assert!(!staging.join("game.iso.emuforge-partial").exists());
+        assert!(is_first_launch(&cache.join("Game")));
+    }
+}
+
+#[test]
+fn test_only_the_config_dir_is_user_data() {
+    let mut config = portable_config();
+    assert!(is_user_data("config/emu.ini", &config));
+    assert!(!is_user_data("configs/emu.ini", &config));
+    assert!(!is_user_data("bios/scph.bin", &config));
+    assert!(!is_user_data("game.iso", &config));
+
+    config.config_dir = "./config/".into();
+    assert!(is_user_data("config/inis/emu.ini", &config));
+    config.config_dir = String::new();
+    assert!(!is_user_data("config/emu.ini", &config));
+}
+
+#[test]
+fn test_user_config_is_merged_on_update() {
+    let dir = tempfile::tempdir().unwrap();
+    let target = dir.path().join("cache/Game");
+    let config = portable_config();
+
+    let (exe, container) = forge(dir.path(), &[("config/emu.ini", b"[Video]\nscale = 1\n"), ("bios/bios.bin", b"v1")]);
+    extract_embedded_archive(&exe, &container, &target, &config, false).unwrap();
+
+    // Le joueur modifie sa config et le BIOS, puis une nouvelle version est forgée
+    fs::write(target.join("config/emu.ini"), "[Video]\nscale = 3\n").unwrap();
+    fs::write(target.join("bios/bios.bin"), "patched").unwrap();
+    age(&target.join("config/emu.ini"));
+    age(&target.join("bios/bios.bin"));
+    let (exe, container) = forge(dir.path(), &[("config/emu.ini", b"[Video]\nscale = 2\nvsync = on\n"), ("bios/bios.bin", b"v2")]);
+    extract_embedded_archive(&exe, &container, &target, &config, false).unwrap();
+
+    assert_eq!(fs::read_to_string(target.join("config/emu.ini")).unwrap(), "[Video]\nscale = 3\nvsync = on\n");
+    assert_eq!(fs::read(target.join("bios/bios.bin")).unwrap(), b"v2");
+    let leftovers: Vec<_> = fs::read_dir(target.join("config")).unwrap().map(|e| e.unwrap().file_name()).collect();
+    assert_eq!(leftovers, ["emu.ini"]);
+}
+
+#[test]
+fn test_unverified_config_is_not_merged() {
+    let dir = tempfile::tempdir().unwrap();
+    let target = dir.path().join("cache/Game");
+    let config = portable_config();
+
+    let files: &[(&str, &[u8])] = &[("config/emu.ini", b"[Video]\nscale = 1\n")];
+    let (exe, container) = forge_with_manifest(dir.path(), files, Some(&manifest_of(files)));
+    extract_embedded_archive(&exe, &container, &target, &config, false).unwrap();
+    fs::write(target.join("config/emu.ini"), "[Video]\nscale = 3\n").unwrap();
+    age(&target.join("config/emu.ini"));
+
+    // Nouvelle version dont la config ne correspond pas au manifeste
+    let manifest = manifest_of(&[("config/emu.ini", b"[Video]\nscale = 2\n")]);
+    let (exe, container) = forge_with_manifest(dir.path(), &[("config/emu.ini", b"[Video]\nscale = 2\nhook = evil\n")], Some(&manifest));
+    assert!(extract_embedded_archive(&exe, &container, &target, &config, false).is_err());
+    assert_eq!(fs::read_to_string(target.join("config/emu.ini")).unwrap(), "[Video]\nscale = 3\n");
+}
+
+#[test]
+fn test_first_extraction_is_published_from_staging() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache = dir.path().join("cache");
+    let target = cache.join("Game");
+    let (exe, container) = forge(dir.path(), &[("emu", b"#!/bin/sh"), ("game.iso", b"disc")]);
+
+    // Dossier de jeu sans état (extraction interrompue d'une ancienne version) : remplacé
+    fs::create_dir_all(&target).unwrap();
+    fs::write(target.join("stale"), b"old").unwrap();
+    assert!(is_first_launch(&target));
+
+    extract_game(&exe, &container, &cache, &portable_config(), false).unwrap();
+    assert!(!is_first_launch(&target));
+    assert!(!staging_dir(&cache, "Game").exists());
+    assert!(!target.join("stale").exists());
+    assert_eq!(fs::read(target.join("game.iso")).unwrap(), b"disc");
+}
+
+#[test]
+fn test_interrupted_extraction_is_resumed() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache = dir.path().join("cache");
+    let target = cache.join("Game");
+    let staging = staging_dir(&cache, "Game");
+    let (exe, container) = forge(dir.path(), &[("emu", b"#!/bin/sh"), ("game.iso", b"disc")]);
+
+    // Stub tué en cours de route : un fichier complet, un autre à moitié écrit
+    fs::create_dir_all(&staging).unwrap();
+    fs::write(staging.join("game.iso"), b"disc").unwrap();
+    fs::write(staging.join("emu.emuforge-partial"), b"#!/b").unwrap();
+    age(&staging.join("game.iso"));
+    let complete = mtime_of(&staging.join("game.iso"));
+    assert!(is_first_launch(&target));
+
+    extract_game(&exe, &container, &cache, &portable_config(), false).unwrap();
+    assert!(!staging.exists());
+    assert_eq!(mtime_of(&target.join("game.iso")), complete, "Complete file extracted again");
+    assert_eq!(fs::read(target.join("emu")).unwrap(), b"#!/bin/sh");
+    assert!(!target.join("emu.emuforge-partial").exists());
+    assert!(!is_first_launch(&target));
+}
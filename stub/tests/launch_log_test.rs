@@ -0,0 +1,65 @@
+use emuforge_stub::launch_log::{prune_crash_reports, rotate, utc_timestamp, LOG_FILE, MAX_CRASH_REPORTS, MAX_LOG_SIZE};
+use std::fs;
+use std::time::{Duration, UNIX_EPOCH};
+
+#[test]
+fn test_utc_timestamp_known_dates() {
+    let vectors = [
+        (0, "1970-01-01 00:00:00Z"),
+        (951_782_400, "2000-02-29 00:00:00Z"),
+        (1_234_567_890, "2009-02-13 23:31:30Z"),
+        (1_709_251_199, "2024-02-29 23:59:59Z"),
+        (4_102_444_799, "2099-12-31 23:59:59Z"),
+        (253_402_300_799, "9999-12-31 23:59:59Z"),
+    ];
+    for (secs, expected) in vectors {
+        assert_eq!(utc_timestamp(UNIX_EPOCH + Duration::from_secs(secs)), expected, "{}", secs);
+    }
+    // Avant l'epoch : ramené à l'epoch
+    assert_eq!(utc_timestamp(UNIX_EPOCH - Duration::from_secs(10)), "1970-01-01 00:00:00Z");
+}
+
+#[test]
+fn test_rotate_shifts_full_logs() {
+    let dir = tempfile::tempdir().unwrap();
+    let log = |name: &str| dir.path().join(name);
+
+    // Sous la limite : rien ne bouge
+    fs::write(log(LOG_FILE), b"small").unwrap();
+    rotate(dir.path()).unwrap();
+    assert_eq!(fs::read(log(LOG_FILE)).unwrap(), b"small");
+    assert!(!log("launch.log.1").exists());
+
+    fs::write(log(LOG_FILE), vec![b'x'; MAX_LOG_SIZE as usize]).unwrap();
+    fs::write(log("launch.log.1"), b"one").unwrap();
+    fs::write(log("launch.log.2"), b"two").unwrap();
+    fs::write(log("launch.log.3"), b"three").unwrap();
+    rotate(dir.path()).unwrap();
+
+    assert!(!log(LOG_FILE).exists());
+    assert_eq!(fs::metadata(log("launch.log.1")).unwrap().len(), MAX_LOG_SIZE);
+    assert_eq!(fs::read(log("launch.log.2")).unwrap(), b"one");
+    assert_eq!(fs::read(log("launch.log.3")).unwrap(), b"two");
+    assert!(!log("launch.log.4").exists());
+
+    // Pas de journal : rien à faire
+    let empty = tempfile::tempdir().unwrap();
+    rotate(empty.path()).unwrap();
+}
+
+#[test]
+fn test_prune_crash_reports_keeps_the_latest() {
+    let dir = tempfile::tempdir().unwrap();
+    let reports: Vec<String> = (1..=MAX_CRASH_REPORTS + 2).map(|n| format!("crash-2024-01-{:02}-12-00-00.zip", n)).collect();
+    for name in &reports {
+        fs::write(dir.path().join(name), b"zip").unwrap();
+    }
+    fs::write(dir.path().join(LOG_FILE), b"log").unwrap();
+
+    prune_crash_reports(dir.path());
+    let mut left: Vec<String> = fs::read_dir(dir.path()).unwrap().map(|e| e.unwrap().file_name().to_string_lossy().to_string()).collect();
+    left.sort();
+    let mut expected = reports[2..].to_vec();
+    expected.push(LOG_FILE.to_string());
+    assert_eq!(left, expected);
+}
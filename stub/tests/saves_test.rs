@@ -0,0 +1,92 @@
+use emuforge_format::config::PortableConfig;
+use emuforge_stub::saves::{export_saves, import_saves};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Les tests changent `XDG_DATA_HOME` : un seul à la fois.
+static ENV: Mutex<()> = Mutex::new(());
+
+fn files_under(dir: &Path) -> Vec<String> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            files.extend(files_under(&path));
+        } else {
+            files.push(path.display().to_string());
+        }
+    }
+    files
+}
+
+#[test]
+fn test_import_never_writes_outside_save_locations() {
+    let _env = ENV.lock().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let data_home = dir.path().join("data");
+    std::env::set_var("XDG_DATA_HOME", &data_home);
+    let outside = dir.path().join("outside");
+    fs::create_dir(&outside).unwrap();
+
+    let config = PortableConfig {
+        game_name: "Game".into(),
+        driver_id: "pcsx2".into(),
+        save_dirs: vec!["memcards".into()],
+        ..Default::default()
+    };
+
+    let names = [
+        "memcards/Mcd001.ps2".to_string(),
+        format!("memcards/{}/evil", outside.display()),
+        "memcards/../evil".to_string(),
+        "memcards/./evil".to_string(),
+        "memcards/".to_string(),
+    ];
+    let archive = dir.path().join("saves.zip");
+    let mut zip = zip::ZipWriter::new(File::create(&archive).unwrap());
+    let options = zip::write::SimpleFileOptions::default();
+    for name in &names {
+        zip.start_file(name.as_str(), options).unwrap();
+        zip.write_all(b"card").unwrap();
+    }
+    let files: Vec<_> = names.iter().map(|n| serde_json::json!({ "path": n, "size": 4, "modified": 0 })).collect();
+    let manifest = serde_json::json!({ "format": 1, "game": "Game", "driver_id": "pcsx2", "exported_at": 0, "files": files });
+    zip.start_file("emuforge-saves.json", options).unwrap();
+    zip.write_all(manifest.to_string().as_bytes()).unwrap();
+    zip.finish().unwrap();
+
+    assert_eq!(import_saves(&config, &archive).unwrap(), 1);
+    assert!(files_under(&outside).is_empty());
+    let mut written = files_under(dir.path());
+    written.sort();
+    let card = data_home.join("emuforge/saves/Game/memcards/Mcd001.ps2");
+    assert_eq!(written, [card.display().to_string(), archive.display().to_string()]);
+}
+
+#[test]
+fn test_host_save_dirs_roundtrip() {
+    let _env = ENV.lock().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let data_home = dir.path().join("data");
+    std::env::set_var("XDG_DATA_HOME", &data_home);
+    let config = PortableConfig {
+        game_name: "Game".into(),
+        driver_id: "dolphin".into(),
+        host_save_dirs: vec!["{data_home}/dolphin-emu/GC".into()],
+        ..Default::default()
+    };
+    let card = data_home.join("dolphin-emu/GC/USA/MemoryCardA.raw");
+    fs::create_dir_all(card.parent().unwrap()).unwrap();
+    fs::write(&card, b"card").unwrap();
+
+    let archive = dir.path().join("saves.zip");
+    assert_eq!(export_saves(&config, &archive).unwrap(), 1);
+    let zip = zip::ZipArchive::new(File::open(&archive).unwrap()).unwrap();
+    assert!(zip.file_names().any(|n| n == "{data_home}/dolphin-emu/GC/USA/MemoryCardA.raw"));
+
+    fs::remove_file(&card).unwrap();
+    assert_eq!(import_saves(&config, &archive).unwrap(), 1);
+    assert_eq!(fs::read(&card).unwrap(), b"card");
+}
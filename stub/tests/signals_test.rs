@@ -0,0 +1,22 @@
+use emuforge_stub::signals::exit_code;
+use std::process::Command;
+
+#[cfg(unix)]
+#[test]
+fn test_exit_code_passes_the_emulator_code_through() {
+    let status = Command::new("sh").args(["-c", "exit 42"]).status().unwrap();
+    assert_eq!(exit_code(&status), 42);
+    let status = Command::new("sh").args(["-c", "exit 0"]).status().unwrap();
+    assert_eq!(exit_code(&status), 0);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_exit_code_of_a_killed_emulator() {
+    use std::os::unix::process::ExitStatusExt;
+
+    let status = Command::new("sh").args(["-c", "kill -TERM $$"]).status().unwrap();
+    assert_eq!(exit_code(&status), 128 + libc::SIGTERM);
+    assert_eq!(exit_code(&std::process::ExitStatus::from_raw(libc::SIGKILL)), 137);
+    assert_eq!(exit_code(&std::process::ExitStatus::from_raw(3 << 8)), 3);
+}
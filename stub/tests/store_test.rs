@@ -0,0 +1,32 @@
+use emuforge_stub::lock;
+use emuforge_stub::store::{link_component, prune, STORE_DIR};
+use std::fs;
+
+#[test]
+fn test_prune_keeps_linked_and_locked_components() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    let store = root.join(STORE_DIR);
+    for id in ["linked", "busy", "unused"] {
+        fs::create_dir_all(store.join(id).join("emu")).unwrap();
+        fs::write(store.join(id).join("emu/emu.bin"), [0u8; 10]).unwrap();
+    }
+    link_component(&store.join("linked"), &root.join("Game"), "emu").unwrap();
+
+    // Composant verrouillé par un lancement qui ne l'a pas encore lié
+    let busy = lock::exclusive(&store.join("busy.lock"), "busy").unwrap();
+    assert_eq!(prune(root).unwrap(), 10);
+    assert!(store.join("linked").is_dir());
+    assert!(store.join("busy").is_dir());
+    assert!(!store.join("unused").exists());
+
+    // Verrou rendu : le composant lié entre-temps est gardé, sinon supprimé
+    link_component(&store.join("busy"), &root.join("Other"), "emu").unwrap();
+    drop(busy);
+    assert_eq!(prune(root).unwrap(), 0);
+    assert!(store.join("busy").is_dir());
+
+    fs::remove_dir_all(root.join("Other")).unwrap();
+    assert_eq!(prune(root).unwrap(), 10);
+    assert!(!store.join("busy").exists());
+}